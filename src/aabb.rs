@@ -8,6 +8,7 @@ use crate::{
     },
 };
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Copy, Clone)]
 pub struct AABB {
     pub min: Point3,
@@ -34,6 +35,15 @@ impl AABB {
         }
         true
     }
+
+    pub fn centroid(&self) -> Point3 {
+        self.min + 0.5 * (self.max - self.min)
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        let d = self.max - self.min;
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
 }
 
 pub fn surrounding_box(a: &AABB, b: &AABB) -> AABB {
@@ -73,6 +83,17 @@ mod tests {
         assert_eq!(c.max, Point3::new(3.0, 100.0, 5.0));
     }
 
+    #[test]
+    fn surface_area_test() {
+        let a = AABB{ 
+            min: Point3::new(0.0, 0.0, 0.0),
+            max: Point3::new(1.0, 2.0, 3.0),
+        };
+
+        assert_eq!(a.surface_area(), 22.0);
+        assert_eq!(a.centroid(), Point3::new(0.5, 1.0, 1.5));
+    }
+
     #[test]
     fn ray_outside_of_aabb_does_not_hit_it() {
         use crate::math::vec3;
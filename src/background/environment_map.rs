@@ -0,0 +1,214 @@
+use crate::{
+    math::{
+        self,
+        InnerSpace,
+        Point3,
+        Vec3,
+        Interval,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+    },
+    aabb::{
+        AABB,
+    },
+    render::{
+        self,
+        Colour,
+    },
+    input::{
+        self,
+        Image,
+    },
+    sampling::{
+        Distribution2D,
+    },
+};
+use std::{
+    f32::consts::PI,
+    path::Path,
+};
+
+// An equirectangular (latitude-longitude) image around the scene.
+// Rows go from straight up to straight down and columns once around the y axis.
+pub struct EnvironmentMap {
+    image: Image,
+    // radians around the y axis
+    rotation: f32,
+    intensity: f32,
+    // picks pixels in proportion to the light they send towards the scene
+    distribution: Distribution2D,
+}
+
+impl EnvironmentMap {
+    pub fn new(image: Image, rotation_in_degrees: f32, intensity: f32) -> Self {
+        // rows near the poles cover less of the sphere of directions
+        let weights = image.pixels
+            .iter()
+            .enumerate()
+            .map(|(i, pixel)| {
+                let row = i / image.width;
+                let theta = PI * (row as f32 + 0.5) / image.height as f32;
+                render::luminance(pixel).max(0.0) * theta.sin()
+            })
+            .collect::<Vec<_>>();
+        let distribution = Distribution2D::new(&weights, image.width, image.height);
+        Self {
+            image,
+            rotation: rotation_in_degrees.to_radians(),
+            intensity,
+            distribution,
+        }
+    }
+
+    pub fn from_file(path: &Path, rotation_in_degrees: f32, intensity: f32) -> Result<Self, String> {
+        input::read(path).map(|image| Self::new(image, rotation_in_degrees, intensity))
+    }
+
+    pub fn value(&self, direction: &Vec3) -> Colour {
+        let (u, v) = self.to_uv(direction);
+        let x = ((u * self.image.width as f32) as usize).min(self.image.width - 1);
+        let row = ((v * self.image.height as f32) as usize).min(self.image.height - 1);
+        self.intensity * self.image.get(x, row)
+    }
+
+    // Image coordinates in [0, 1)^2 of a direction, v grows downwards.
+    fn to_uv(&self, direction: &Vec3) -> (f32, f32) {
+        let d = direction.normalize();
+        let theta = d.y.clamp(-1.0, 1.0).acos();
+        let phi = d.z.atan2(d.x) - self.rotation;
+        let u = phi / (2.0 * PI);
+        (u - u.floor(), theta / PI)
+    }
+
+    // The direction which `to_uv` maps to (u, v).
+    fn to_direction(&self, (u, v): (f32, f32)) -> Vec3 {
+        let theta = v * PI;
+        let phi = u * 2.0 * PI + self.rotation;
+        math::vec3(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin())
+    }
+}
+
+// The map is infinitely far away so rays never hit it, it takes part in rendering as a light
+// which is sampled by the scene and seen through the background by rays which miss everything.
+impl Hittable for EnvironmentMap {
+    fn hit(&self, _: &Ray, _: &Interval<f32>) -> Option<HitRecord<'_>> {
+        None
+    }
+
+    fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
+        None
+    }
+
+    // the density over the image divided by the area of the sphere each unit of the image covers
+    fn pdf_value(&self, _: &Point3, direction: &Vec3) -> f32 {
+        let uv = self.to_uv(direction);
+        let sin_theta = (uv.1 * PI).sin();
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+        self.distribution.pdf(uv) / (2.0 * PI * PI * sin_theta)
+    }
+
+    fn random(&self, _: &Point3, u: (f32, f32)) -> Vec3 {
+        let (uv, _) = self.distribution.sample(u);
+        self.to_direction(uv)
+    }
+
+    fn is_light(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math;
+
+    // columns of red, green, blue and white, each covering a quarter turn from +x towards +z
+    fn image() -> Image {
+        let columns = [
+            Colour::new(1.0, 0.0, 0.0),
+            Colour::new(0.0, 1.0, 0.0),
+            Colour::new(0.0, 0.0, 1.0),
+            Colour::new(1.0, 1.0, 1.0),
+        ];
+        Image {
+            width: 4,
+            height: 2,
+            pixels: columns.iter().chain(columns.iter()).cloned().collect(),
+        }
+    }
+
+    #[test]
+    fn directions_map_to_their_columns() {
+        let map = EnvironmentMap::new(image(), 0.0, 2.0);
+
+        assert_eq!(map.value(&math::vec3(1.0, 0.1, 0.1)), Colour::new(2.0, 0.0, 0.0));
+        assert_eq!(map.value(&math::vec3(-0.1, -0.1, 1.0)), Colour::new(0.0, 2.0, 0.0));
+    }
+
+    #[test]
+    fn rotation_turns_the_map_around_the_y_axis() {
+        let map = EnvironmentMap::new(image(), 90.0, 1.0);
+
+        assert_eq!(map.value(&math::vec3(-0.1, 0.1, 1.0)), Colour::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn to_direction_inverts_to_uv() {
+        let map = EnvironmentMap::new(image(), 30.0, 1.0);
+        let direction = math::vec3(0.3, -0.5, 0.8).normalize();
+
+        let back = map.to_direction(map.to_uv(&direction));
+
+        assert!((back - direction).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn bright_pixels_are_sampled_more_often() {
+        // a dim map with one bright pixel
+        let mut image = Image {
+            width: 8,
+            height: 4,
+            pixels: vec![Colour::new(0.1, 0.1, 0.1); 32],
+        };
+        image.pixels[8 + 5] = Colour::new(100.0, 100.0, 100.0);
+        let map = EnvironmentMap::new(image, 0.0, 1.0);
+        let origin = Point3::new(0.0, 0.0, 0.0);
+
+        let mut bright = 0;
+        for i in 0..16 {
+            for j in 0..16 {
+                let direction = map.random(&origin, ((i as f32 + 0.5) / 16.0, (j as f32 + 0.5) / 16.0));
+                assert!(map.pdf_value(&origin, &direction) > 0.0);
+                if map.value(&direction).x > 1.0 {
+                    bright += 1;
+                }
+            }
+        }
+        assert!(bright > 200);
+    }
+
+    #[test]
+    fn pdf_integrates_to_one_over_the_sphere() {
+        let map = EnvironmentMap::new(image(), 45.0, 1.0);
+        let origin = Point3::new(0.0, 0.0, 0.0);
+        let n = 200;
+
+        // midpoint rule over theta and phi, sin(theta) is the area of the sphere per unit of both
+        let mut integral = 0.0;
+        for i in 0..n {
+            for j in 0..n {
+                let theta = PI * (i as f32 + 0.5) / n as f32;
+                let phi = 2.0 * PI * (j as f32 + 0.5) / n as f32;
+                let direction = math::vec3(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin());
+                let area = theta.sin() * (PI / n as f32) * (2.0 * PI / n as f32);
+                integral += map.pdf_value(&origin, &direction) * area;
+            }
+        }
+        assert!((integral - 1.0).abs() < 0.02, "{}", integral);
+    }
+}
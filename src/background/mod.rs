@@ -0,0 +1,75 @@
+mod environment_map;
+
+pub use environment_map::EnvironmentMap;
+
+use crate::{
+    math::{
+        InnerSpace,
+        Vec3,
+    },
+    render::{
+        Colour,
+    },
+};
+use std::sync::Arc;
+
+// Blends from `bottom` straight down to `top` straight up.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Gradient {
+    pub bottom: Colour,
+    pub top: Colour,
+}
+
+// white at the bottom turning blue towards the top, the sky of the books
+impl Default for Gradient {
+    fn default() -> Self {
+        Self {
+            bottom: Colour::new(1.0, 1.0, 1.0),
+            top: Colour::new(0.5, 0.7, 1.0),
+        }
+    }
+}
+
+// The light arriving along rays which miss every hittable.
+// Environment maps are shared with the scene's lights so they can be sampled directly.
+pub enum Background {
+    Constant(Colour),
+    Gradient(Gradient),
+    EnvironmentMap(Arc<EnvironmentMap>),
+}
+
+impl Background {
+    pub fn black() -> Self {
+        Background::Constant(Colour::new(0.0, 0.0, 0.0))
+    }
+
+    pub fn sky() -> Self {
+        Background::Gradient(Gradient::default())
+    }
+
+    pub fn value(&self, direction: &Vec3) -> Colour {
+        match self {
+            Background::Constant(colour) => *colour,
+            Background::Gradient(Gradient{ bottom, top }) => {
+                let t = 0.5 * (direction.normalize().y + 1.0);
+                (1.0 - t) * bottom + t * top
+            },
+            Background::EnvironmentMap(map) => map.value(direction),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math;
+
+    #[test]
+    fn gradient_goes_from_bottom_to_top() {
+        let sky = Background::sky();
+
+        assert_eq!(sky.value(&math::vec3(0.0, -2.0, 0.0)), Colour::new(1.0, 1.0, 1.0));
+        assert_eq!(sky.value(&math::vec3(0.0, 3.0, 0.0)), Colour::new(0.5, 0.7, 1.0));
+        assert_eq!(sky.value(&math::vec3(1.0, 0.0, 0.0)), Colour::new(0.75, 0.85, 1.0));
+    }
+}
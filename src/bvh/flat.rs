@@ -0,0 +1,250 @@
+use crate::{
+    core::{
+        Ray,
+        HitRecord,
+        Hittable,
+    },
+    aabb::{
+        self,
+        AABB,
+    },
+    math::{
+        Interval,
+    },
+    bvh::{
+        self,
+        BoxedHittable,
+        Primitive,
+        SplitMethod,
+        Options,
+        Stats,
+    },
+    random::{
+        Rng,
+    },
+};
+
+// Leaves hold up to this many hittables.
+const MAX_LEAF_SIZE: usize = 4;
+// Deeper nodes become leaves regardless of their size which bounds the traversal stack.
+const MAX_DEPTH: usize = 64;
+
+#[derive(Copy, Clone)]
+enum FlatNodeKind {
+    // the hittables in `primitives[first..first + count]`
+    Leaf { first: usize, count: usize },
+    // the first child is stored right after its parent
+    Interior { second_child: usize, axis: usize },
+}
+
+#[derive(Copy, Clone)]
+struct FlatNode {
+    bounding_box: AABB,
+    kind: FlatNodeKind,
+}
+
+// A BVH stored in a single array of nodes in depth first order.
+// The hittables are reordered so that the ones of each leaf are contiguous.
+pub struct FlatBvh {
+    nodes: Vec<FlatNode>,
+    primitives: Vec<BoxedHittable>,
+}
+
+impl FlatBvh {
+    pub fn new(hittables: Vec<BoxedHittable>, time_interval: &Interval<f32>, options: &Options) -> FlatBvh {
+        if hittables.is_empty() {
+            panic!("incorrect list");
+        }
+        let mut bvh = FlatBvh {
+            nodes: Vec::new(),
+            primitives: Vec::with_capacity(hittables.len()),
+        };
+        let mut rng = Rng::new(bvh::SPLIT_SEED);
+        bvh.build(bvh::to_primitives(hittables, time_interval), options.split_method, 1, &mut rng);
+        bvh
+    }
+
+    // Appends the subtree of the given primitives and returns the index of its root.
+    fn build(&mut self, primitives: Vec<Primitive>, split_method: SplitMethod, depth: usize, rng: &mut Rng) -> usize {
+        let bounding_box = primitives[1..]
+            .iter()
+            .fold(primitives[0].bounding_box, |b, p| aabb::surrounding_box(&b, &p.bounding_box));
+        let index = self.nodes.len();
+        if primitives.len() <= MAX_LEAF_SIZE || depth >= MAX_DEPTH {
+            self.nodes.push(FlatNode {
+                bounding_box,
+                kind: FlatNodeKind::Leaf {
+                    first: self.primitives.len(),
+                    count: primitives.len(),
+                },
+            });
+            self.primitives.extend(primitives.into_iter().map(|p| p.hittable));
+            return index;
+        }
+
+        let (left, right, axis) = bvh::split(primitives, split_method, rng);
+        // patched once the second child is built
+        self.nodes.push(FlatNode {
+            bounding_box,
+            kind: FlatNodeKind::Interior{ second_child: 0, axis },
+        });
+        self.build(left, split_method, depth + 1, rng);
+        let second_child = self.build(right, split_method, depth + 1, rng);
+        self.nodes[index].kind = FlatNodeKind::Interior{ second_child, axis };
+        index
+    }
+
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats::empty();
+        let root_area = self.nodes[0].bounding_box.surface_area();
+        let mut stack = vec![(0, 1)];
+        while let Some((index, depth)) = stack.pop() {
+            let node = &self.nodes[index];
+            let area = node.bounding_box.surface_area();
+            match node.kind {
+                FlatNodeKind::Leaf{ count, .. } => stats.add_leaf(depth, area, root_area, count),
+                FlatNodeKind::Interior{ second_child, .. } => {
+                    stats.add_node(depth, area, root_area);
+                    stack.push((index + 1, depth + 1));
+                    stack.push((second_child, depth + 1));
+                },
+            }
+        }
+        stats
+    }
+}
+
+impl Hittable for FlatBvh {
+    // Visits the child nearer to the ray origin first and shrinks the interval
+    // with each hit so that farther subtrees are culled by their boxes.
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let direction_is_negative = [ray.direction.x < 0.0, ray.direction.y < 0.0, ray.direction.z < 0.0];
+        let mut interval = *hit_interval;
+        let mut closest = None;
+        let mut stack = [0; MAX_DEPTH];
+        let mut stack_len = 0;
+        let mut current = 0;
+        loop {
+            let node = &self.nodes[current];
+            if node.bounding_box.hit(ray, &interval) {
+                match node.kind {
+                    FlatNodeKind::Leaf{ first, count } => {
+                        for hittable in &self.primitives[first..first + count] {
+                            if let Some(hit_record) = hittable.hit(ray, &interval) {
+                                interval = interval.with_max(hit_record.t).expect("invalid interval");
+                                closest = Some(hit_record);
+                            }
+                        }
+                    },
+                    FlatNodeKind::Interior{ second_child, axis } => {
+                        let (near, far) = if direction_is_negative[axis] {
+                            (second_child, current + 1)
+                        }
+                        else {
+                            (current + 1, second_child)
+                        };
+                        stack[stack_len] = far;
+                        stack_len += 1;
+                        current = near;
+                        continue;
+                    },
+                }
+            }
+            if stack_len == 0 {
+                break;
+            }
+            stack_len -= 1;
+            current = stack[stack_len];
+        }
+        closest
+    }
+
+    fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
+        Some(self.nodes[0].bounding_box)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shapes::Sphere,
+        materials::Dielectric,
+        math::{
+            self,
+            Point3,
+        },
+    };
+    use std::sync::Arc;
+
+    fn random_point_in_cube(half_size: f32, rng: &mut Rng) -> Point3 {
+        let mut coordinate = || half_size * (2.0 * rng.random_float_from_0_to_1() - 1.0);
+        Point3::new(coordinate(), coordinate(), coordinate())
+    }
+
+    fn spheres(centers_and_radii: &[(Point3, f32)]) -> Vec<BoxedHittable> {
+        centers_and_radii
+            .iter()
+            .map(|&(center, radius)| Box::new(Sphere::new(center, radius, Arc::new(Dielectric::new(1.5)))) as BoxedHittable)
+            .collect()
+    }
+
+    fn random_ray(rng: &mut Rng) -> Ray {
+        let origin = Point3::new(
+            30.0 * rng.random_float_from_0_to_1() - 15.0,
+            30.0 * rng.random_float_from_0_to_1() - 15.0,
+            -15.0,
+        );
+        Ray::new(origin, random_point_in_cube(10.0, rng) - origin, 0.0)
+    }
+
+    #[test]
+    fn flat_bvh_finds_the_same_hits_as_the_tree() {
+        let time_interval = Interval::new(0.0, 1.0).unwrap();
+        let hit_interval = Interval::new(0.001, f32::MAX).unwrap();
+        let mut rng = Rng::new(0);
+        for &split_method in &[SplitMethod::Random, SplitMethod::Sah] {
+            let options = Options{ split_method, ..Options::default() };
+            let centers_and_radii = (0..200)
+                .map(|_| (random_point_in_cube(10.0, &mut rng), 0.1 + rng.random_float_from_0_to_1()))
+                .collect::<Vec<_>>();
+            let flat = FlatBvh::new(spheres(&centers_and_radii), &time_interval, &options);
+            let tree = bvh::Node::new(spheres(&centers_and_radii), &time_interval, &options);
+
+            for _ in 0..1000 {
+                let ray = random_ray(&mut rng);
+                let expected = tree.hit(&ray, &hit_interval).map(|h| h.t);
+                let actual = flat.hit(&ray, &hit_interval).map(|h| h.t);
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn flat_bvh_with_a_single_hittable() {
+        let time_interval = Interval::new(0.0, 1.0).unwrap();
+        let sphere = Box::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, Arc::new(Dielectric::new(1.5))));
+        let bvh = FlatBvh::new(vec![sphere], &time_interval, &Options::default());
+        let hit_interval = Interval::new(0.0, 100.0).unwrap();
+        let ray = Ray::new(Point3::new(0.0, 0.0, -10.0), math::vec3(0.0, 0.0, 1.0), 0.0);
+
+        assert_eq!(bvh.hit(&ray, &hit_interval).map(|h| h.t), Some(9.0));
+        assert_eq!(bvh.stats().leaf_count, 1);
+    }
+
+    #[test]
+    fn stats_count_nodes_and_leaves() {
+        let time_interval = Interval::new(0.0, 1.0).unwrap();
+        let centers_and_radii = (0..8)
+            .map(|i| (Point3::new(3.0 * i as f32, 0.0, 0.0), 1.0))
+            .collect::<Vec<_>>();
+        let options = Options{ split_method: SplitMethod::Random, ..Options::default() };
+        let bvh = FlatBvh::new(spheres(&centers_and_radii), &time_interval, &options);
+
+        let stats = bvh.stats();
+
+        assert_eq!(stats.node_count, 1);
+        assert_eq!(stats.leaf_count, 2);
+        assert_eq!(stats.depth, 2);
+    }
+}
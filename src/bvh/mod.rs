@@ -1,3 +1,10 @@
+mod sah;
+mod stats;
+mod flat;
+
+pub use stats::Stats;
+pub use flat::FlatBvh;
+
 use crate::{
     core::{
         Ray,
@@ -11,64 +18,167 @@ use crate::{
     math::{
         Interval,
     },
-    random,
+    random::{
+        Rng,
+    },
 };
 
 type BoxedHittable = Box<dyn Hittable>;
 
+// the random split gives the same tree for every build
+const SPLIT_SEED: u64 = 0;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SplitMethod {
+    // sort along a random axis and split at the median
+    Random,
+    // binned surface area heuristic over all three axes
+    Sah,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Layout {
+    // a tree of boxed nodes, see `Node`
+    Tree,
+    // nodes stored in a single array, see `FlatBvh`
+    Flat,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Options {
+    pub split_method: SplitMethod,
+    pub layout: Layout,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            split_method: SplitMethod::Sah,
+            layout: Layout::Flat,
+        }
+    }
+}
+
+// Builds a BVH over the hittables in the layout of the options.
+// Used for the scene and for the bottom level BVHs shared by instances.
+pub fn build(hittables: Vec<BoxedHittable>, time_interval: &Interval<f32>, options: &Options) -> (BoxedHittable, Stats) {
+    match options.layout {
+        Layout::Tree => {
+            let root = Node::new(hittables, time_interval, options);
+            let stats = root.stats();
+            (Box::new(root), stats)
+        },
+        Layout::Flat => {
+            let root = FlatBvh::new(hittables, time_interval, options);
+            let stats = root.stats();
+            (Box::new(root), stats)
+        },
+    }
+}
+
+// A hittable together with its bounding box, computed once during construction.
+struct Primitive {
+    hittable: BoxedHittable,
+    bounding_box: AABB,
+}
+
+fn to_primitives(hittables: Vec<BoxedHittable>, time_interval: &Interval<f32>) -> Vec<Primitive> {
+    hittables
+        .into_iter()
+        .map(|hittable| {
+            let bounding_box = hittable.bounding_box(time_interval)
+                .expect("Hittable with no bounding box given to BVH");
+            Primitive{ hittable, bounding_box }
+        })
+        .collect()
+}
+
+enum Child {
+    Node(Box<Node>),
+    Leaf(Primitive),
+}
+
 pub struct Node {
-    left: BoxedHittable,
-    right: BoxedHittable,
+    left: Child,
+    right: Child,
     pub bounding_box: AABB,
 }
 
 impl Node {
-    pub fn new(mut hittables: Vec<BoxedHittable>, time_interval: &Interval<f32>) -> Node {
-        let axis = random::gen_range(0, 3);
-        hittables.sort_unstable_by(|a, b| box_compare(&**a, &**b, axis));
-        
-        let (left, right) = match hittables.len() {
-            0 | 1 => panic!("incorrect list"),
-            2 => (hittables.pop().unwrap(), hittables.pop().unwrap()),
-            3 => (hittables.pop().unwrap(), Box::new(Node::new(hittables, time_interval)) as BoxedHittable),
-            n => { 
-                let left : BoxedHittable = Box::new(Node::new(hittables.drain(..n / 2).collect(), time_interval));
-                let right: BoxedHittable = Box::new(Node::new(hittables, time_interval));
-                (left, right)
-            },
-        };
-        let box_left = left.bounding_box(time_interval);
-        let box_right = right.bounding_box(time_interval);
-        let bounding_box = match (box_left, box_right) {
-            (Some(x), Some(y)) => aabb::surrounding_box(&x, &y),
-            _ => panic!("Hittable with no bounding box given to BVH Node"),
-        };
-        
+    pub fn new(hittables: Vec<BoxedHittable>, time_interval: &Interval<f32>, options: &Options) -> Node {
+        if hittables.len() < 2 {
+            panic!("incorrect list");
+        }
+        let mut rng = Rng::new(SPLIT_SEED);
+        Node::build(to_primitives(hittables, time_interval), options.split_method, &mut rng)
+    }
+
+    fn build(primitives: Vec<Primitive>, split_method: SplitMethod, rng: &mut Rng) -> Node {
+        let (left, right, _) = split(primitives, split_method, rng);
+        let left = Node::make_child(left, split_method, rng);
+        let right = Node::make_child(right, split_method, rng);
+        let bounding_box = aabb::surrounding_box(left.bounding_box(), right.bounding_box());
+
         Node {
             left,
             right,
             bounding_box,
         }
     }
+
+    fn make_child(mut primitives: Vec<Primitive>, split_method: SplitMethod, rng: &mut Rng) -> Child {
+        if primitives.len() == 1 {
+            Child::Leaf(primitives.pop().unwrap())
+        }
+        else {
+            Child::Node(Box::new(Node::build(primitives, split_method, rng)))
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats::of(self)
+    }
 }
 
-fn box_compare(left: &dyn Hittable, right: &dyn Hittable, dim: usize) -> std::cmp::Ordering {
-    let interval = Interval::new(0.0, 0.3).unwrap();
-    match (left.bounding_box(&interval), right.bounding_box(&interval)) {
-        (Some(left_box), Some(right_box)) => {
-            left_box.min[dim].partial_cmp(&right_box.min[dim])
-                             .expect("NaN value in AABB")
-        },
-        _ => panic!("Hittable with no bounding box given to BVH Node"),
-    } 
+impl Child {
+    fn bounding_box(&self) -> &AABB {
+        match self {
+            Child::Node(node) => &node.bounding_box,
+            Child::Leaf(primitive) => &primitive.bounding_box,
+        }
+    }
+
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        match self {
+            Child::Node(node) => node.hit(ray, hit_interval),
+            Child::Leaf(primitive) => primitive.hittable.hit(ray, hit_interval),
+        }
+    }
+}
+
+fn split(primitives: Vec<Primitive>, split_method: SplitMethod, rng: &mut Rng) -> (Vec<Primitive>, Vec<Primitive>, usize) {
+    match split_method {
+        SplitMethod::Random => split_at_random_axis_median(primitives, rng),
+        SplitMethod::Sah => sah::split(primitives),
+    }
+}
+
+fn split_at_random_axis_median(mut primitives: Vec<Primitive>, rng: &mut Rng) -> (Vec<Primitive>, Vec<Primitive>, usize) {
+    let axis = rng.gen_range(0, 3);
+    primitives.sort_unstable_by(|a, b| {
+        a.bounding_box.min[axis].partial_cmp(&b.bounding_box.min[axis])
+                                .expect("NaN value in AABB")
+    });
+    let right = primitives.split_off(primitives.len() / 2);
+    (primitives, right, axis)
 }
 
 impl Hittable for Node {
-    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
         if !self.bounding_box.hit(ray, hit_interval) {
             return None;
         }
-        
+
         let left_interaction = self.left.hit(ray, hit_interval);
         let right_interaction = self.right.hit(ray, hit_interval);
         match (left_interaction, right_interaction) {
@@ -84,4 +194,76 @@ impl Hittable for Node {
     fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
         Some(self.bounding_box)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shapes::Sphere,
+        materials::Dielectric,
+        math::{
+            self,
+            Point3,
+        },
+    };
+    use std::sync::Arc;
+
+    fn spheres_on_a_line(count: usize) -> Vec<BoxedHittable> {
+        (0..count)
+            .map(|i| {
+                let center = Point3::new(3.0 * i as f32, 0.0, 0.0);
+                Box::new(Sphere::new(center, 1.0, Arc::new(Dielectric::new(1.5)))) as BoxedHittable
+            })
+            .collect()
+    }
+
+    fn hit_t(node: &Node, x: f32) -> Option<f32> {
+        let ray = Ray::new(Point3::new(x, 0.0, -10.0), math::vec3(0.0, 0.0, 1.0), 0.0);
+        let hit_interval = Interval::new(0.0, 100.0).unwrap();
+        node.hit(&ray, &hit_interval).map(|h| h.t)
+    }
+
+    #[test]
+    fn every_split_method_finds_all_hittables() {
+        let time_interval = Interval::new(0.0, 1.0).unwrap();
+        for &split_method in &[SplitMethod::Random, SplitMethod::Sah] {
+            let node = Node::new(spheres_on_a_line(17), &time_interval, &Options{ split_method, ..Options::default() });
+
+            for i in 0..17 {
+                assert_eq!(hit_t(&node, 3.0 * i as f32), Some(9.0));
+            }
+            assert_eq!(hit_t(&node, 1.5), None);
+        }
+    }
+
+    #[test]
+    fn stats_count_nodes_and_leaves() {
+        let time_interval = Interval::new(0.0, 1.0).unwrap();
+        let node = Node::new(spheres_on_a_line(8), &time_interval, &Options{ split_method: SplitMethod::Sah, ..Options::default() });
+
+        let stats = node.stats();
+
+        assert_eq!(stats.leaf_count, 8);
+        assert_eq!(stats.node_count, 7);
+        assert_eq!(stats.depth, 4);
+    }
+
+    #[test]
+    fn sah_separates_distant_clusters() {
+        let time_interval = Interval::new(0.0, 1.0).unwrap();
+        let mut hittables = spheres_on_a_line(3);
+        hittables.push(Box::new(Sphere::new(Point3::new(100.0, 0.0, 0.0), 1.0, Arc::new(Dielectric::new(1.5)))));
+
+        let node = Node::new(hittables, &time_interval, &Options{ split_method: SplitMethod::Sah, ..Options::default() });
+
+        let leaf_counts = [&node.left, &node.right]
+            .iter()
+            .map(|child| match child {
+                Child::Node(node) => node.stats().leaf_count,
+                Child::Leaf(_) => 1,
+            })
+            .collect::<Vec<_>>();
+        assert!(leaf_counts == [3, 1] || leaf_counts == [1, 3]);
+    }
+}
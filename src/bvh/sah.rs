@@ -0,0 +1,101 @@
+use crate::{
+    aabb::{
+        self,
+        AABB,
+    },
+    bvh::{
+        Primitive,
+    },
+};
+
+const BIN_COUNT: usize = 12;
+
+#[derive(Copy, Clone)]
+struct Bin {
+    count: usize,
+    bounding_box: Option<AABB>,
+}
+
+// Bins the primitive centroids along each axis and splits at the bin boundary
+// with the lowest surface area heuristic cost.
+// Falls back to splitting in halves if all centroids coincide.
+// Returns the two halves and the split axis.
+pub(super) fn split(mut primitives: Vec<Primitive>) -> (Vec<Primitive>, Vec<Primitive>, usize) {
+    let centroids = primitives
+        .iter()
+        .map(|p| p.bounding_box.centroid())
+        .collect::<Vec<_>>();
+    let mut min = centroids[0];
+    let mut max = centroids[0];
+    for c in &centroids[1..] {
+        for d in 0..3 {
+            min[d] = min[d].min(c[d]);
+            max[d] = max[d].max(c[d]);
+        }
+    }
+
+    let mut best: Option<(f32, usize, usize)> = None;
+    for axis in 0..3 {
+        let extent = max[axis] - min[axis];
+        if extent <= 0.0 {
+            continue;
+        }
+        let bin_index = |c: f32| (((c - min[axis]) / extent * BIN_COUNT as f32) as usize).min(BIN_COUNT - 1);
+
+        let mut bins = [Bin{ count: 0, bounding_box: None }; BIN_COUNT];
+        for (primitive, centroid) in primitives.iter().zip(&centroids) {
+            let bin = &mut bins[bin_index(centroid[axis])];
+            bin.count += 1;
+            bin.bounding_box = Some(merge(bin.bounding_box, &primitive.bounding_box));
+        }
+
+        // costs[i] is the cost of splitting after bin i
+        let mut costs = [0.0; BIN_COUNT - 1];
+        let mut count = 0;
+        let mut bounding_box = None;
+        for i in 0..BIN_COUNT - 1 {
+            count += bins[i].count;
+            bounding_box = bins[i].bounding_box.map_or(bounding_box, |b| Some(merge(bounding_box, &b)));
+            costs[i] = bounding_box.map_or(0.0, |b| b.surface_area()) * count as f32;
+        }
+        count = 0;
+        bounding_box = None;
+        for i in (1..BIN_COUNT).rev() {
+            count += bins[i].count;
+            bounding_box = bins[i].bounding_box.map_or(bounding_box, |b| Some(merge(bounding_box, &b)));
+            costs[i - 1] += bounding_box.map_or(0.0, |b| b.surface_area()) * count as f32;
+        }
+
+        let left_counts = bins.iter().scan(0, |acc, bin| { *acc += bin.count; Some(*acc) });
+        for (i, left_count) in left_counts.take(BIN_COUNT - 1).enumerate() {
+            let is_valid = left_count > 0 && left_count < primitives.len();
+            if is_valid && best.is_none_or(|(cost, _, _)| costs[i] < cost) {
+                best = Some((costs[i], axis, i));
+            }
+        }
+    }
+
+    match best {
+        Some((_, axis, split_bin)) => {
+            let extent = max[axis] - min[axis];
+            let mut centroids = centroids.into_iter();
+            let (left, right) = primitives.into_iter().partition(|_| {
+                let c = centroids.next().unwrap()[axis];
+                let bin = (((c - min[axis]) / extent * BIN_COUNT as f32) as usize).min(BIN_COUNT - 1);
+                bin <= split_bin
+            });
+            (left, right, axis)
+        },
+        None => {
+            let right = primitives.split_off(primitives.len() / 2);
+            (primitives, right, 0)
+        },
+    }
+}
+
+fn merge(a: Option<AABB>, b: &AABB) -> AABB {
+    match a {
+        Some(a) => aabb::surrounding_box(&a, b),
+        None => *b,
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{
+    bvh::{
+        Node,
+        Child,
+    },
+};
+use std::fmt;
+
+// Cost of traversing an interior node relative to intersecting a hittable.
+const TRAVERSAL_COST: f32 = 1.0;
+const INTERSECTION_COST: f32 = 1.0;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Stats {
+    pub depth: usize,
+    pub node_count: usize,
+    pub leaf_count: usize,
+    // expected cost of a ray through the root according to the surface area heuristic
+    pub cost: f32,
+}
+
+impl Stats {
+    pub(super) fn of(root: &Node) -> Self {
+        let mut stats = Stats::empty();
+        let root_area = root.bounding_box.surface_area();
+        stats.visit_node(root, 1, root_area);
+        stats
+    }
+
+    pub(crate) fn empty() -> Self {
+        Stats {
+            depth: 0,
+            node_count: 0,
+            leaf_count: 0,
+            cost: 0.0,
+        }
+    }
+
+    pub(super) fn add_node(&mut self, depth: usize, area: f32, root_area: f32) {
+        self.node_count += 1;
+        self.depth = self.depth.max(depth);
+        self.cost += TRAVERSAL_COST * relative_area(area, root_area);
+    }
+
+    pub(super) fn add_leaf(&mut self, depth: usize, area: f32, root_area: f32, primitive_count: usize) {
+        self.leaf_count += 1;
+        self.depth = self.depth.max(depth);
+        self.cost += INTERSECTION_COST * primitive_count as f32 * relative_area(area, root_area);
+    }
+
+    fn visit_node(&mut self, node: &Node, depth: usize, root_area: f32) {
+        self.add_node(depth, node.bounding_box.surface_area(), root_area);
+        for child in &[&node.left, &node.right] {
+            match child {
+                Child::Node(node) => self.visit_node(node, depth + 1, root_area),
+                Child::Leaf(primitive) => self.add_leaf(depth + 1, primitive.bounding_box.surface_area(), root_area, 1),
+            }
+        }
+    }
+}
+
+fn relative_area(area: f32, root_area: f32) -> f32 {
+    if root_area > 0.0 { area / root_area } else { 1.0 }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "depth: {}, nodes: {}, leaves: {}, estimated cost: {:.2}",
+            self.depth, self.node_count, self.leaf_count, self.cost)
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
     core::{
         Ray,
     },
+    sampling,
 };
 
 #[derive(Copy, Clone, Debug)]
@@ -44,7 +45,7 @@ impl FieldOfView {
         }
     }
 
-    pub fn to_radians(&self) -> f32 {
+    pub fn to_radians(self) -> f32 {
         self.radians
     }
 }
@@ -76,24 +77,12 @@ impl Camera {
         }
     }
     
-    pub fn make_ray(&self, (u, v): (f32, f32), time: f32) -> Ray {
-        let point = self.lens_radius * random_point_in_unit_disk();
+    // `lens` is a uniform sample which picks the point on the lens the ray starts from.
+    pub fn make_ray(&self, (u, v): (f32, f32), time: f32, lens: (f32, f32)) -> Ray {
+        let point = self.lens_radius * sampling::concentric_disk(lens);
         let offset = point.x * self.orientation.u + point.y * self.orientation.v;
         let point_in_lens = self.origin + offset;
         let direction = self.lower_left_corner + u * self.horizontal + v * self.vertical - self.origin - offset;
         Ray::new(point_in_lens, direction, time)
     }
-}
-
-fn random_point_in_unit_disk() -> Point3 {
-    use crate::math::{vec3, EuclideanSpace, InnerSpace};
-    use crate::random::random_float_from_0_to_1;
-    
-    loop {
-        let mut vec = vec3(random_float_from_0_to_1(), random_float_from_0_to_1(), 0.0);
-        vec = 2.0 * vec - vec3(1.0, 1.0, 0.0);
-        if vec.magnitude() < 1.0 {
-            return EuclideanSpace::from_vec(vec);
-        }
-    }
 }
\ No newline at end of file
@@ -2,13 +2,38 @@ use clap::{
     App,
     Arg,
 };
+use crate::{
+    bvh,
+    output,
+    sampling::SamplerKind,
+};
 
 pub struct Args {
     pub width: i32,
     pub height: i32,
     pub pixel_samples: i32,
     pub output: String,
+    // None if the format should be chosen from the output's extension
+    pub format: Option<output::Format>,
+    pub alpha: bool,
     pub scene: String,
+    pub scene_file: Option<String>,
+    pub threads: usize,
+    pub seed: u64,
+    pub sampler: SamplerKind,
+    // enables adaptive sampling, `pixel_samples` is then the minimum
+    pub noise_threshold: Option<f32>,
+    pub max_samples: u32,
+    // where to write the image of sample counts
+    pub heatmap: Option<String>,
+    // maximum depths of each kind of bounce, None keeps the scene's
+    pub max_diffuse_depth: Option<u32>,
+    pub max_glossy_depth: Option<u32>,
+    pub max_transmission_depth: Option<u32>,
+    pub max_volume_depth: Option<u32>,
+    pub bvh_split_method: bvh::SplitMethod,
+    pub bvh_layout: bvh::Layout,
+    pub print_stats: bool,
 }
 
 pub fn parse() -> Args {
@@ -23,7 +48,7 @@ pub fn parse() -> Args {
                 .required(true)
                 .takes_value(true)
                 .default_value("300")
-                .validator(&is_positive_integer_arg)
+                .validator(is_positive_integer_arg)
         )
         .arg(
             Arg::with_name("width")
@@ -33,7 +58,7 @@ pub fn parse() -> Args {
                 .required(true)
                 .takes_value(true)
                 .default_value("300")
-                .validator(&is_positive_integer_arg)
+                .validator(is_positive_integer_arg)
         )
         .arg(
             Arg::with_name("samples")
@@ -43,7 +68,7 @@ pub fn parse() -> Args {
                 .required(true)
                 .takes_value(true)
                 .default_value("32")
-                .validator(&is_positive_integer_arg)
+                .validator(is_positive_integer_arg)
         )
         .arg(
             Arg::with_name("scene")
@@ -54,6 +79,118 @@ pub fn parse() -> Args {
                 .takes_value(true)
                 .default_value("simple-light")
         )
+        .arg(
+            Arg::with_name("scene-file")
+                .long("scene-file")
+                .value_name("path to a scene description file, overrides --scene")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("threads")
+                .short("t")
+                .long("threads")
+                .value_name("number of render threads, defaults to the number of cores")
+                .takes_value(true)
+                .validator(is_positive_integer_arg)
+        )
+        .arg(
+            Arg::with_name("bvh")
+                .long("bvh")
+                .value_name("how BVH nodes are split")
+                .takes_value(true)
+                .possible_values(&["sah", "random"])
+                .default_value("sah")
+        )
+        .arg(
+            Arg::with_name("bvh-layout")
+                .long("bvh-layout")
+                .value_name("how BVH nodes are stored in memory")
+                .takes_value(true)
+                .possible_values(&["flat", "tree"])
+                .default_value("flat")
+        )
+        .arg(
+            Arg::with_name("seed")
+                .long("seed")
+                .value_name("seed of the random numbers, the same seed gives the same image")
+                .takes_value(true)
+                .default_value("0")
+                .validator(is_u64_arg)
+        )
+        .arg(
+            Arg::with_name("sampler")
+                .long("sampler")
+                .value_name("how the sample values of each pixel are generated")
+                .takes_value(true)
+                .possible_values(&["independent", "stratified", "halton", "sobol"])
+                .default_value("sobol")
+        )
+        .arg(
+            Arg::with_name("noise-threshold")
+                .long("noise-threshold")
+                .value_name("stop sampling pixels whose estimated error falls below this, --samples is then the minimum and the stratified sampler is replaced by sobol")
+                .takes_value(true)
+                .validator(is_positive_float_arg)
+        )
+        .arg(
+            Arg::with_name("max-samples")
+                .long("max-samples")
+                .value_name("most samples a pixel takes with --noise-threshold")
+                .takes_value(true)
+                .default_value("1024")
+                .validator(is_positive_integer_arg)
+        )
+        .arg(
+            Arg::with_name("heatmap")
+                .long("heatmap")
+                .value_name("path of an image of the sample count of each pixel")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("max-diffuse-depth")
+                .long("max-diffuse-depth")
+                .value_name("most diffuse bounces of a path")
+                .takes_value(true)
+                .validator(is_u32_arg)
+        )
+        .arg(
+            Arg::with_name("max-glossy-depth")
+                .long("max-glossy-depth")
+                .value_name("most glossy and mirror bounces of a path")
+                .takes_value(true)
+                .validator(is_u32_arg)
+        )
+        .arg(
+            Arg::with_name("max-transmission-depth")
+                .long("max-transmission-depth")
+                .value_name("most refractions of a path")
+                .takes_value(true)
+                .validator(is_u32_arg)
+        )
+        .arg(
+            Arg::with_name("max-volume-depth")
+                .long("max-volume-depth")
+                .value_name("most scattering events of a path inside media")
+                .takes_value(true)
+                .validator(is_u32_arg)
+        )
+        .arg(
+            Arg::with_name("stats")
+                .long("stats")
+                .help("Prints BVH statistics and the render time")
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .value_name("image format, chosen from the output extension by default")
+                .takes_value(true)
+                .possible_values(&["png", "ppm", "ppm-ascii", "pfm", "hdr"])
+        )
+        .arg(
+            Arg::with_name("alpha")
+                .long("alpha")
+                .help("Writes the fraction of camera rays which hit the scene as alpha (PNG only)")
+        )
         .arg(
             Arg::with_name("output")
                 .value_name("output")
@@ -77,16 +214,76 @@ pub fn parse() -> Args {
     let output = matches.value_of("output")
         .unwrap()
         .to_owned();
+    let format = matches.value_of("format")
+        .and_then(output::Format::from_name);
+    let alpha = matches.is_present("alpha");
     let scene = matches.value_of("scene")
         .unwrap()
         .to_owned();
+    let scene_file = matches.value_of("scene-file")
+        .map(|s| s.to_owned());
+    let threads = matches.value_of("threads")
+        .map(|t| t.parse().unwrap())
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        });
+    let bvh_split_method = match matches.value_of("bvh").unwrap() {
+        "random" => bvh::SplitMethod::Random,
+        _ => bvh::SplitMethod::Sah,
+    };
+    let bvh_layout = match matches.value_of("bvh-layout").unwrap() {
+        "tree" => bvh::Layout::Tree,
+        _ => bvh::Layout::Flat,
+    };
+    let seed = matches.value_of("seed")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let sampler = match matches.value_of("sampler").unwrap() {
+        "independent" => SamplerKind::Independent,
+        "stratified" => SamplerKind::Stratified,
+        "halton" => SamplerKind::Halton,
+        _ => SamplerKind::Sobol,
+    };
+    let noise_threshold = matches.value_of("noise-threshold")
+        .map(|t| t.parse().unwrap());
+    let max_samples = matches.value_of("max-samples")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let heatmap = matches.value_of("heatmap")
+        .map(|s| s.to_owned());
+    let depth = |name| matches.value_of(name).map(|d| d.parse().unwrap());
+    let max_diffuse_depth = depth("max-diffuse-depth");
+    let max_glossy_depth = depth("max-glossy-depth");
+    let max_transmission_depth = depth("max-transmission-depth");
+    let max_volume_depth = depth("max-volume-depth");
+    let print_stats = matches.is_present("stats");
     
     Args {
         width,
         height,
         pixel_samples,
         output,
+        format,
+        alpha,
         scene,
+        scene_file,
+        threads,
+        seed,
+        sampler,
+        noise_threshold,
+        max_samples,
+        heatmap,
+        max_diffuse_depth,
+        max_glossy_depth,
+        max_transmission_depth,
+        max_volume_depth,
+        bvh_split_method,
+        bvh_layout,
+        print_stats,
     }
 }
 
@@ -95,4 +292,23 @@ fn is_positive_integer_arg(arg: String) -> Result<(), String> {
         Ok(i) if i > 0  => Ok(()),
         _               => Err("Positive interger expected".to_owned())
     }
+}
+
+fn is_positive_float_arg(arg: String) -> Result<(), String> {
+    match arg.parse::<f32>() {
+        Ok(x) if x > 0.0 => Ok(()),
+        _                => Err("Positive number expected".to_owned())
+    }
+}
+
+fn is_u32_arg(arg: String) -> Result<(), String> {
+    arg.parse::<u32>()
+        .map(|_| ())
+        .map_err(|_| "Non-negative integer expected".to_owned())
+}
+
+fn is_u64_arg(arg: String) -> Result<(), String> {
+    arg.parse::<u64>()
+        .map(|_| ())
+        .map_err(|_| "Non-negative integer expected".to_owned())
 }
\ No newline at end of file
@@ -1,5 +1,8 @@
 use crate::{
     math::{
+        self,
+        Point3,
+        Vec3,
         Interval,
     },
     aabb::{
@@ -8,10 +11,100 @@ use crate::{
     core::{
         Ray, 
         HitRecord,
+        Span,
     },
 };
+use std::sync::Arc;
 
-pub trait Hittable {
-    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord>;
+pub trait Hittable: Send + Sync {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>>;
     fn bounding_box(&self, time_interval: &Interval<f32>) -> Option<AABB>;
-}
\ No newline at end of file
+
+    // The density with which `random` picks `direction` from `origin`.
+    // Zero for hittables which can't be sampled.
+    fn pdf_value(&self, _origin: &Point3, _direction: &Vec3) -> f32 {
+        0.0
+    }
+
+    // A random direction from `origin` towards the hittable, picked by the uniform sample `u`.
+    fn random(&self, _origin: &Point3, _u: (f32, f32)) -> Vec3 {
+        math::vec3(1.0, 0.0, 0.0)
+    }
+
+    // Whether the hittable emits light and supports `random` and `pdf_value`.
+    fn is_light(&self) -> bool {
+        false
+    }
+
+    // Every stretch of the whole line of `ray` inside the hittable, ordered by t.
+    // Only solids report them.
+    fn spans(&self, _ray: &Ray) -> Vec<Span<'_>> {
+        Vec::new()
+    }
+
+    // Whether the hittable is a closed solid with outward facing normals which supports `spans`,
+    // as needed by constructive solid geometry.
+    fn is_solid(&self) -> bool {
+        false
+    }
+}
+
+impl<T: Hittable + ?Sized> Hittable for Box<T> {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        (**self).hit(ray, hit_interval)
+    }
+
+    fn bounding_box(&self, time_interval: &Interval<f32>) -> Option<AABB> {
+        (**self).bounding_box(time_interval)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        (**self).pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point3, u: (f32, f32)) -> Vec3 {
+        (**self).random(origin, u)
+    }
+
+    fn is_light(&self) -> bool {
+        (**self).is_light()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        (**self).spans(ray)
+    }
+
+    fn is_solid(&self) -> bool {
+        (**self).is_solid()
+    }
+}
+
+impl<T: Hittable + ?Sized> Hittable for Arc<T> {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        (**self).hit(ray, hit_interval)
+    }
+
+    fn bounding_box(&self, time_interval: &Interval<f32>) -> Option<AABB> {
+        (**self).bounding_box(time_interval)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        (**self).pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point3, u: (f32, f32)) -> Vec3 {
+        (**self).random(origin, u)
+    }
+
+    fn is_light(&self) -> bool {
+        (**self).is_light()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        (**self).spans(ray)
+    }
+
+    fn is_solid(&self) -> bool {
+        (**self).is_solid()
+    }
+}
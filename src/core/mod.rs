@@ -1,7 +1,9 @@
 mod ray;
 mod hit_record;
 mod hittable;
+mod span;
 
 pub use ray::Ray;
 pub use hit_record::HitRecord;
 pub use hittable::Hittable;
+pub use span::Span;
@@ -8,6 +8,9 @@ pub struct Ray {
     pub origin: Point3,
     pub direction: Vec3,
     pub time: f32,
+    // a uniform sample in [0, 1) from which each participating medium the ray crosses derives the distance
+    // at which the ray scatters in it
+    pub medium_sample: f32,
 }
 
 impl Ray {
@@ -16,6 +19,14 @@ impl Ray {
             origin,
             direction,
             time,
+            medium_sample: 0.5,
+        }
+    }
+
+    pub fn with_medium_sample(self, medium_sample: f32) -> Ray {
+        Ray {
+            medium_sample,
+            ..self
         }
     }
 
@@ -0,0 +1,21 @@
+use crate::{
+    core::{
+        HitRecord,
+    },
+};
+
+// A stretch of a ray inside a solid, between the surfaces where it enters and exits it.
+#[derive(Copy, Clone)]
+pub struct Span<'a> {
+    pub enter: HitRecord<'a>,
+    pub exit: HitRecord<'a>,
+}
+
+impl<'a> Span<'a> {
+    pub fn map(self, f: impl Fn(HitRecord<'a>) -> HitRecord<'a>) -> Self {
+        Span {
+            enter: f(self.enter),
+            exit: f(self.exit),
+        }
+    }
+}
@@ -17,6 +17,7 @@ use crate::{
     scene::{
         Scene,
     },
+    bvh,
     camera::{
         self,
         CameraAxis,
@@ -35,39 +36,38 @@ use crate::{
         YRotation,
     },
 };
+use std::sync::Arc;
 
-pub fn scene(camera_aspect: f32, time_interval: &Interval<f32>) -> (Scene, Camera) {
-    let red = Box::new(Lambertian::new(Box::new(ConstantTexture::from_rgb(vec3(0.65, 0.05, 0.05))))) as Box<dyn Material>;
-    let green = Box::new(Lambertian::new(Box::new(ConstantTexture::from_rgb(vec3(0.12, 0.45, 0.15))))) as Box<dyn Material>;
-    let light = Box::new(DiffuseLight::new(Box::new(ConstantTexture::from_rgb(vec3(15.0, 15.0, 15.0))))) as Box<dyn Material>;
-    let diffuse_light_mat_generator = || -> Box<dyn Material> { 
-        Box::new(Lambertian::new(Box::new(ConstantTexture::from_rgb(vec3(0.73, 0.73, 0.73))))) 
-    };
+pub fn scene(camera_aspect: f32, time_interval: &Interval<f32>, bvh_options: &bvh::Options) -> (Scene, Camera) {
+    let red = Arc::new(Lambertian::new(Arc::new(ConstantTexture::from_rgb(vec3(0.65, 0.05, 0.05))))) as Arc<dyn Material>;
+    let green = Arc::new(Lambertian::new(Arc::new(ConstantTexture::from_rgb(vec3(0.12, 0.45, 0.15))))) as Arc<dyn Material>;
+    let light = Arc::new(DiffuseLight::new(Arc::new(ConstantTexture::from_rgb(vec3(15.0, 15.0, 15.0))))) as Arc<dyn Material>;
+    let white = Arc::new(Lambertian::new(Arc::new(ConstantTexture::from_rgb(vec3(0.73, 0.73, 0.73))))) as Arc<dyn Material>;
     
     let hittables: Vec<Box<dyn Hittable>> = vec![
         Box::new(FlipNormals::new(YZRectangle::new(0.0, 555.0, 0.0, 555.0, 555.0, green))),
         Box::new(YZRectangle::new(0.0, 555.0, 0.0, 555.0, 0.0, red)),
         Box::new(XZRectangle::new(213.0, 343.0, 227.0, 332.0, 554.0, light)),
-        Box::new(XZRectangle::new(0.0, 555.0, 0.0, 555.0, 0.0, diffuse_light_mat_generator())),
-        Box::new(FlipNormals::new(XYRectangle::new(0.0, 555.0, 0.0, 555.0, 555.0, diffuse_light_mat_generator()))),
-        Box::new(FlipNormals::new(XZRectangle::new(0.0, 555.0, 0.0, 555.0, 555.0, diffuse_light_mat_generator()))),
+        Box::new(XZRectangle::new(0.0, 555.0, 0.0, 555.0, 0.0, white.clone())),
+        Box::new(FlipNormals::new(XYRectangle::new(0.0, 555.0, 0.0, 555.0, 555.0, white.clone()))),
+        Box::new(FlipNormals::new(XZRectangle::new(0.0, 555.0, 0.0, 555.0, 555.0, white.clone()))),
         Box::new(Translation::on(
             Box::new(YRotation::from_degrees(
-                Box::new(Parallelepiped::new(&Point3::new(0.0, 0.0, 0.0), &Point3::new(165.0, 165.0, 165.0), diffuse_light_mat_generator)),
+                Box::new(Parallelepiped::new(&Point3::new(0.0, 0.0, 0.0), &Point3::new(165.0, 165.0, 165.0), white.clone())),
                 -18.0
             )),
             vec3(130.0, 0.0, 65.0)
         )),
         Box::new(Translation::on(
             Box::new(YRotation::from_degrees(
-                Box::new(Parallelepiped::new(&Point3::new(0.0, 0.0, 0.0), &Point3::new(165.0, 330.0, 165.0), diffuse_light_mat_generator)),
+                Box::new(Parallelepiped::new(&Point3::new(0.0, 0.0, 0.0), &Point3::new(165.0, 330.0, 165.0), white)),
                 15.0
             )),
             vec3(265.0, 0.0, 295.0)
         )),
     ];
 
-    (Scene::new(hittables, time_interval), camera(camera_aspect))
+    (Scene::new(hittables, time_interval, bvh_options), camera(camera_aspect))
 }
 
 fn camera(aspect: f32) -> Camera {
@@ -14,12 +14,14 @@ use crate::{
     scene::{
         Scene,
     },
+    bvh,
     camera::{
         self,
         CameraAxis,
         Camera,
     },
     materials::{
+        Material,
         Lambertian, 
         DiffuseLight,
     },
@@ -28,16 +30,19 @@ use crate::{
         NoiseTexture,
     },
 };
+use std::sync::Arc;
 
-pub fn scene(camera_aspect: f32, time_interval: &Interval<f32>) -> (Scene, Camera) {
-    let perlin_texture = Box::new(NoiseTexture::new(4.0));
-    let mut hittables: Vec<Box<dyn Hittable>> = Vec::with_capacity(4);
-    hittables.push(Box::new(Sphere::new(Point3::new(0.0,-1000.0, 0.0), 1000.0, Box::new(Lambertian::new(perlin_texture.clone())))));
-    hittables.push(Box::new(Sphere::new(Point3::new(0.0, 2.0, 0.0), 2.0, Box::new(Lambertian::new(perlin_texture)))));
-    hittables.push(Box::new(Sphere::new(Point3::new(0.0, 7.0, 0.0), 2.0, Box::new(DiffuseLight::new(Box::new(ConstantTexture::from_rgb(vec3(4.0, 4.0, 4.0))))))));
-    hittables.push(Box::new(XYRectangle::new(3.0, 5.0, 1.0, 3.0, -2.0, Box::new(DiffuseLight::new(Box::new(ConstantTexture::from_rgb(vec3(4.0, 4.0, 4.0))))))));
+pub fn scene(camera_aspect: f32, time_interval: &Interval<f32>, bvh_options: &bvh::Options) -> (Scene, Camera) {
+    let perlin = Arc::new(Lambertian::new(Arc::new(NoiseTexture::new(4.0)))) as Arc<dyn Material>;
+    let light = Arc::new(DiffuseLight::new(Arc::new(ConstantTexture::from_rgb(vec3(4.0, 4.0, 4.0))))) as Arc<dyn Material>;
+    let hittables: Vec<Box<dyn Hittable>> = vec![
+        Box::new(Sphere::new(Point3::new(0.0,-1000.0, 0.0), 1000.0, perlin.clone())),
+        Box::new(Sphere::new(Point3::new(0.0, 2.0, 0.0), 2.0, perlin)),
+        Box::new(Sphere::new(Point3::new(0.0, 7.0, 0.0), 2.0, light.clone())),
+        Box::new(XYRectangle::new(3.0, 5.0, 1.0, 3.0, -2.0, light)),
+    ];
    
-    (Scene::new(hittables, time_interval), camera(camera_aspect))
+    (Scene::new(hittables, time_interval, bvh_options), camera(camera_aspect))
 }
 
 fn camera(aspect: f32) -> Camera {
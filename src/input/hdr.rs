@@ -0,0 +1,139 @@
+// Radiance RGBE images with flat or run length encoded scanlines.
+
+use crate::{
+    input::{
+        self,
+        Image,
+    },
+    render::{
+        Colour,
+    },
+};
+
+pub fn read(bytes: &[u8]) -> Result<Image, String> {
+    let mut rest = bytes;
+    let mut first_line = true;
+    loop {
+        let end = rest
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or_else(|| "unexpected end of the header".to_owned())?;
+        let line = String::from_utf8_lossy(&rest[..end]).into_owned();
+        rest = &rest[end + 1..];
+        if first_line && !line.starts_with("#?") {
+            return Err("not a Radiance image".to_owned());
+        }
+        first_line = false;
+        if line.is_empty() {
+            break;
+        }
+        if let Some(format) = line.strip_prefix("FORMAT=") {
+            if format != "32-bit_rle_rgbe" {
+                return Err(format!("unsupported pixel format `{}`", format));
+            }
+        }
+    }
+
+    // only the usual orientation, rows from the top with pixels from the left
+    let (y, height, x, width) = (
+        input::next_token(&mut rest)?,
+        input::parse_dimension(input::next_token(&mut rest)?)?,
+        input::next_token(&mut rest)?,
+        input::parse_dimension(input::next_token(&mut rest)?)?,
+    );
+    if y != "-Y" || x != "+X" {
+        return Err("unsupported image orientation".to_owned());
+    }
+    if rest.first() == Some(&b'\n') {
+        rest = &rest[1..];
+    }
+
+    // checked before allocating as the header can claim any size
+    let truncated = || "the pixel data is truncated".to_owned();
+    let pixel_count = input::value_count(width, height, 1)?;
+    let min_size = min_scanline_size(width).and_then(|size| size.checked_mul(height)).ok_or_else(truncated)?;
+    if rest.len() < min_size {
+        return Err(truncated());
+    }
+
+    let mut pixels = Vec::with_capacity(pixel_count);
+    let mut scanline = vec![[0u8; 4]; width];
+    for _ in 0..height {
+        rest = read_scanline(rest, &mut scanline)?;
+        pixels.extend(scanline.iter().map(from_rgbe));
+    }
+    Ok(Image {
+        width,
+        height,
+        pixels,
+    })
+}
+
+// The fewest bytes a scanline of the width can take, with runs of up to 127 bytes in each channel when encoded.
+fn min_scanline_size(width: usize) -> Option<usize> {
+    if (8..0x8000).contains(&width) {
+        Some(4 + 4 * 2 * width.div_ceil(127))
+    }
+    else {
+        width.checked_mul(4)
+    }
+}
+
+// Reads one scanline into `rgbe` and returns the bytes after it.
+fn read_scanline<'a>(bytes: &'a [u8], rgbe: &mut [[u8; 4]]) -> Result<&'a [u8], String> {
+    let width = rgbe.len();
+    let truncated = || "the pixel data is truncated".to_owned();
+    let is_encoded = (8..0x8000).contains(&width) &&
+        bytes.len() >= 4 &&
+        bytes[0] == 2 && bytes[1] == 2 &&
+        ((bytes[2] as usize) << 8 | bytes[3] as usize) == width;
+    if !is_encoded {
+        let data = bytes.get(..4 * width).ok_or_else(truncated)?;
+        for (pixel, chunk) in rgbe.iter_mut().zip(data.chunks(4)) {
+            pixel.copy_from_slice(chunk);
+        }
+        return Ok(&bytes[4 * width..]);
+    }
+
+    let mut rest = &bytes[4..];
+    for channel in 0..4 {
+        let mut x = 0;
+        while x < width {
+            let (&count, tail) = rest.split_first().ok_or_else(truncated)?;
+            if count > 128 {
+                let count = count as usize - 128;
+                let &value = tail.first().ok_or_else(truncated)?;
+                if x + count > width {
+                    return Err("a run overflows its scanline".to_owned());
+                }
+                for pixel in &mut rgbe[x..x + count] {
+                    pixel[channel] = value;
+                }
+                x += count;
+                rest = &tail[1..];
+            }
+            else {
+                let count = count as usize;
+                if count == 0 || x + count > width {
+                    return Err("invalid run length".to_owned());
+                }
+                let values = tail.get(..count).ok_or_else(truncated)?;
+                for (pixel, &value) in rgbe[x..x + count].iter_mut().zip(values) {
+                    pixel[channel] = value;
+                }
+                x += count;
+                rest = &tail[count..];
+            }
+        }
+    }
+    Ok(rest)
+}
+
+// the mantissas are taken at the middle of their interval
+fn from_rgbe(rgbe: &[u8; 4]) -> Colour {
+    if rgbe[3] == 0 {
+        return Colour::new(0.0, 0.0, 0.0);
+    }
+    let scale = 2f32.powi(rgbe[3] as i32 - 128 - 8);
+    Colour::new(rgbe[0] as f32 + 0.5, rgbe[1] as f32 + 0.5, rgbe[2] as f32 + 0.5) * scale
+}
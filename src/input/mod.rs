@@ -0,0 +1,182 @@
+mod hdr;
+mod pfm;
+mod ppm;
+mod png;
+
+use crate::{
+    render::{
+        Colour,
+    },
+};
+use std::path::Path;
+
+// A linear RGB image.
+#[derive(Clone, Debug)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    // row by row from the top
+    pub pixels: Vec<Colour>,
+}
+
+impl Image {
+    pub fn get(&self, x: usize, row: usize) -> Colour {
+        self.pixels[row * self.width + x]
+    }
+}
+
+// Reads an image in the format given by the extension of `path`.
+pub fn read(path: &Path) -> Result<Image, String> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| format!("Couldn't read {}: {}", path.display(), e))?;
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+    let image = match extension.as_deref() {
+        Some("hdr") => hdr::read(&bytes),
+        Some("pfm") => pfm::read(&bytes),
+        Some("ppm") | Some("pnm") => ppm::read(&bytes),
+        Some("png") => png::read(&bytes),
+        _ => Err("unsupported image format, use a .hdr, .pfm, .ppm or .png file".to_owned()),
+    };
+    image.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// Splits the next whitespace separated token off the start of `bytes`.
+fn next_token<'a>(bytes: &mut &'a [u8]) -> Result<&'a str, String> {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .ok_or_else(|| "unexpected end of the header".to_owned())?;
+    let rest = &bytes[start..];
+    let length = rest
+        .iter()
+        .position(|b| b.is_ascii_whitespace())
+        .unwrap_or(rest.len());
+    let token = std::str::from_utf8(&rest[..length]).map_err(|_| "invalid header".to_owned())?;
+    *bytes = &rest[length..];
+    Ok(token)
+}
+
+// 8 and 16-bit images are taken to be sRGB encoded, as textures usually are.
+fn decode_srgb(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    }
+    else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+// The number of values in an image, an error rather than an overflow for absurd headers.
+fn value_count(width: usize, height: usize, channels: usize) -> Result<usize, String> {
+    width
+        .checked_mul(height)
+        .and_then(|n| n.checked_mul(channels))
+        .ok_or_else(|| format!("the image size {}x{} is too large", width, height))
+}
+
+fn parse_dimension(token: &str) -> Result<usize, String> {
+    match token.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("invalid image size `{}`", token)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        output,
+        render::{
+            Framebuffer,
+            Pixel,
+        },
+    };
+
+    fn framebuffer() -> Framebuffer {
+        let mut framebuffer = Framebuffer::new(9, 2);
+        for x in 0..9 {
+            framebuffer.set(x, 0, Pixel::opaque(Colour::new(15.0, 0.5, 0.0)));
+            framebuffer.set(x, 1, Pixel::opaque(Colour::new(x as f32, 0.25, 1.0)));
+        }
+        framebuffer
+    }
+
+    fn assert_matches(image: &Image, framebuffer: &Framebuffer, tolerance: f32) {
+        assert_eq!((image.width, image.height), (framebuffer.width(), framebuffer.height()));
+        // RGBE shares an exponent, so the error grows with the brightest channel
+        for (colour, pixel) in image.pixels.iter().zip(framebuffer.pixels()) {
+            let brightest = pixel.colour.x.max(pixel.colour.y).max(pixel.colour.z).max(1.0);
+            for d in 0..3 {
+                assert!((colour[d] - pixel.colour[d]).abs() <= tolerance * brightest, "{:?} {:?}", colour, pixel.colour);
+            }
+        }
+    }
+
+    #[test]
+    fn written_hdr_images_are_read_back() {
+        let framebuffer = framebuffer();
+        let mut bytes = Vec::new();
+        output::write(&mut bytes, &framebuffer, output::Format::Hdr, false).unwrap();
+
+        assert_matches(&hdr::read(&bytes).unwrap(), &framebuffer, 1.0 / 64.0);
+    }
+
+    #[test]
+    fn written_pfm_images_are_read_back() {
+        let framebuffer = framebuffer();
+        let mut bytes = Vec::new();
+        output::write(&mut bytes, &framebuffer, output::Format::Pfm, false).unwrap();
+
+        assert_matches(&pfm::read(&bytes).unwrap(), &framebuffer, 0.0);
+    }
+
+    #[test]
+    fn written_ppm_and_png_images_are_read_back() {
+        // the midtones differ between the gamma of `output` and sRGB, the ends don't
+        let mut framebuffer = Framebuffer::new(3, 2);
+        for x in 0..3 {
+            framebuffer.set(x, 0, Pixel::opaque(Colour::new(1.0, 0.0, 0.0)));
+            framebuffer.set(x, 1, Pixel::opaque(Colour::new((x % 2) as f32, 0.0, 1.0)));
+        }
+        let tolerance = 0.0;
+        for &format in &[output::Format::Ppm, output::Format::PpmAscii, output::Format::Png] {
+            let mut bytes = Vec::new();
+            output::write(&mut bytes, &framebuffer, format, false).unwrap();
+
+            let image = match format {
+                output::Format::Png => png::read(&bytes),
+                _ => ppm::read(&bytes),
+            };
+            assert_matches(&image.unwrap(), &framebuffer, tolerance);
+        }
+    }
+
+    #[test]
+    fn midtones_are_decoded_as_srgb() {
+        let image = ppm::read(b"P3\n3 1\n255\n188 188 188 10 10 10 255 0 128\n").unwrap();
+
+        assert!((image.pixels[0].x - 0.5029).abs() < 1e-4);
+        assert!((image.pixels[1].x - 10.0 / 255.0 / 12.92).abs() < 1e-6);
+        assert!((image.pixels[2].z - 0.2158).abs() < 1e-4);
+    }
+
+    #[test]
+    fn huge_sizes_in_short_files_are_errors() {
+        assert!(hdr::read(b"#?RADIANCE\n\n-Y 99999999999 +X 99999999999\n").is_err());
+        assert!(hdr::read(b"#?RADIANCE\n\n-Y 9999999 +X 100\n\x02\x02\x00\x64").is_err());
+        assert!(pfm::read(b"PF\n99999999999 99999999999\n-1.0\n").is_err());
+        assert!(pfm::read(format!("PF\n{} 2\n-1.0\n", usize::MAX / 8).as_bytes()).is_err());
+        assert!(ppm::read(b"P6\n99999999999 99999999999\n255\n").is_err());
+        assert!(ppm::read(b"P3\n99999999 9\n255\n1 2 3\n").is_err());
+    }
+
+    #[test]
+    fn ppm_headers_can_have_comments() {
+        let image = ppm::read(b"P3\n# a comment\n1 1 # another\n15\n15 0 15\n").unwrap();
+
+        assert_eq!(image.pixels, vec![Colour::new(1.0, 0.0, 1.0)]);
+    }
+}
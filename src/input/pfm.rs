@@ -0,0 +1,52 @@
+// Portable float maps with one or three channels, rows are stored from the bottom.
+
+use crate::{
+    input::{
+        self,
+        Image,
+    },
+    render::{
+        Colour,
+    },
+};
+
+pub fn read(bytes: &[u8]) -> Result<Image, String> {
+    let mut rest = bytes;
+    let channels = match input::next_token(&mut rest)? {
+        "PF" => 3,
+        "Pf" => 1,
+        _ => return Err("not a PFM image".to_owned()),
+    };
+    let width = input::parse_dimension(input::next_token(&mut rest)?)?;
+    let height = input::parse_dimension(input::next_token(&mut rest)?)?;
+    let scale = input::next_token(&mut rest)?
+        .parse::<f32>()
+        .map_err(|_| "invalid scale".to_owned())?;
+    // a single whitespace character separates the header from the data
+    let size = input::value_count(width, height, 4 * channels)?;
+    let data = rest
+        .get(1..)
+        .and_then(|data| data.get(..size))
+        .ok_or_else(|| "the pixel data is truncated".to_owned())?;
+
+    let values = data
+        .chunks(4)
+        .map(|b| {
+            let b = [b[0], b[1], b[2], b[3]];
+            // a negative scale marks little endian data
+            if scale < 0.0 { f32::from_le_bytes(b) } else { f32::from_be_bytes(b) }
+        })
+        .collect::<Vec<_>>();
+    let mut pixels = Vec::with_capacity(width * height);
+    for row in values.chunks(channels * width).rev() {
+        pixels.extend(row.chunks(channels).map(|c| match c {
+            [r, g, b] => Colour::new(*r, *g, *b),
+            _ => Colour::new(c[0], c[0], c[0]),
+        }));
+    }
+    Ok(Image {
+        width,
+        height,
+        pixels,
+    })
+}
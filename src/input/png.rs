@@ -0,0 +1,56 @@
+// PNG images of any colour type, palettes and low bit depths are expanded by the decoder.
+
+use crate::{
+    input::{
+        self,
+        Image,
+    },
+    render::{
+        Colour,
+    },
+};
+use ::png::{
+    BitDepth,
+    ColorType,
+    Decoder,
+    Transformations,
+};
+
+pub fn read(bytes: &[u8]) -> Result<Image, String> {
+    let mut decoder = Decoder::new(bytes);
+    decoder.set_transformations(Transformations::EXPAND);
+    let (info, mut reader) = decoder.read_info().map_err(|e| e.to_string())?;
+    let mut data = vec![0; info.buffer_size()];
+    reader.next_frame(&mut data).map_err(|e| e.to_string())?;
+
+    let values = match info.bit_depth {
+        BitDepth::Sixteen => data
+            .chunks(2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]) as f32 / 65535.0)
+            .collect::<Vec<_>>(),
+        _ => data
+            .iter()
+            .map(|&b| b as f32 / 255.0)
+            .collect::<Vec<_>>(),
+    };
+    // alpha is dropped
+    let channels = match info.color_type {
+        ColorType::Grayscale => 1,
+        ColorType::GrayscaleAlpha => 2,
+        ColorType::RGB => 3,
+        ColorType::RGBA => 4,
+        ColorType::Indexed => return Err("unexpanded palette".to_owned()),
+    };
+    let pixels = values
+        .chunks(channels)
+        .map(|c| {
+            let c = c.iter().map(|&v| input::decode_srgb(v)).collect::<Vec<_>>();
+            if channels < 3 { Colour::new(c[0], c[0], c[0]) } else { Colour::new(c[0], c[1], c[2]) }
+        })
+        .collect();
+    Ok(Image {
+        width: info.width as usize,
+        height: info.height as usize,
+        pixels,
+    })
+}
@@ -0,0 +1,87 @@
+// Binary P6 and plain text P3 portable pixmaps with up to 16 bits per channel.
+
+use crate::{
+    input::{
+        self,
+        Image,
+    },
+    render::{
+        Colour,
+    },
+};
+
+pub fn read(bytes: &[u8]) -> Result<Image, String> {
+    let mut rest = bytes;
+    let binary = match next_token(&mut rest)? {
+        "P6" => true,
+        "P3" => false,
+        _ => return Err("not a P3 or P6 image".to_owned()),
+    };
+    let width = input::parse_dimension(next_token(&mut rest)?)?;
+    let height = input::parse_dimension(next_token(&mut rest)?)?;
+    let max_value = match next_token(&mut rest)?.parse::<u32>() {
+        Ok(m) if m > 0 && m < 65536 => m,
+        _ => return Err("invalid maximum value".to_owned()),
+    };
+
+    let count = input::value_count(width, height, 3)?;
+    let truncated = || "the pixel data is truncated".to_owned();
+    let values = if binary {
+        // a single whitespace character separates the header from the data,
+        // channels take two big endian bytes when the maximum doesn't fit in one
+        let size = if max_value < 256 { 1 } else { 2 };
+        let data_size = input::value_count(width, height, 3 * size)?;
+        let data = rest
+            .get(1..)
+            .and_then(|data| data.get(..data_size))
+            .ok_or_else(truncated)?;
+        data.chunks(size)
+            .map(|b| b.iter().fold(0, |value, &byte| (value << 8) | byte as u32))
+            .collect::<Vec<_>>()
+    }
+    else {
+        // every value takes at least a digit and a separator
+        if rest.len() < count.saturating_mul(2) {
+            return Err(truncated());
+        }
+        let mut values = Vec::with_capacity(count);
+        for _ in 0..count {
+            let token = next_token(&mut rest)?;
+            let value = token
+                .parse::<u32>()
+                .map_err(|_| format!("invalid channel value `{}`", token))?;
+            values.push(value);
+        }
+        values
+    };
+
+    let to_linear = |v: u32| input::decode_srgb(v.min(max_value) as f32 / max_value as f32);
+    let pixels = values
+        .chunks(3)
+        .map(|c| Colour::new(to_linear(c[0]), to_linear(c[1]), to_linear(c[2])))
+        .collect();
+    Ok(Image {
+        width,
+        height,
+        pixels,
+    })
+}
+
+// The header may have comments from a `#` to the end of the line.
+fn next_token<'a>(bytes: &mut &'a [u8]) -> Result<&'a str, String> {
+    loop {
+        let start = bytes
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())
+            .unwrap_or(bytes.len());
+        *bytes = &bytes[start..];
+        if bytes.first() != Some(&b'#') {
+            return input::next_token(bytes);
+        }
+        let end = bytes
+            .iter()
+            .position(|&b| b == b'\n')
+            .unwrap_or(bytes.len());
+        *bytes = &bytes[end..];
+    }
+}
@@ -12,24 +12,25 @@ mod textures;
 mod transformations;
 mod example_scenes;
 mod volumes;
+mod render;
+mod scene_file;
+mod output;
+mod sampling;
+mod input;
+mod background;
+mod sdf;
+#[cfg(test)]
+mod test_helpers;
 
 use math::{
-    Vec3, 
     Interval,
 };
-use crate::core::{
-    Ray, 
-    Hittable,
-};
 use scene::{
     Scene,
 };
 use camera::{
     Camera,
 };
-use random::{
-    random_float_from_0_to_1,
-};
 use std::{
     fs::File,
     io::{
@@ -37,84 +38,103 @@ use std::{
         BufWriter,
     },
     path::Path,
+    time::Instant,
 };
 
 fn main() {
     let args = cmd::parse();
     let output = Path::new(&args.output);
     
-    match File::create(&output) {
-        Ok(file) => {
-            if let Err(e) = run(file, args) {
-                panic!("Error! {}", e)
+    let result = args.format
+        .map_or_else(|| output::Format::from_path(output), Ok)
+        .and_then(|format| {
+            if args.alpha && !format.supports_alpha() {
+                Err("Only PNG images can have an alpha channel".to_owned())
             }
-        },
-        Err(e) => panic!("Error! Couldn't create {}: {}", output.display(), e.to_string()),
-    };
+            else {
+                Ok(format)
+            }
+        })
+        .and_then(|format| {
+            File::create(output)
+                .map_err(|e| format!("Couldn't create {}: {}", output.display(), e))
+                .map(|file| (file, format))
+        })
+        .and_then(|(file, format)| run(file, format, args));
+    if let Err(e) = result {
+        eprintln!("Error! {}", e);
+        std::process::exit(1);
+    }
 }
 
-type Colour = Vec3;
-
-fn run(file: impl Write, args: cmd::Args) -> Result<(), String> {
-    let cmd::Args{ width, height, pixel_samples, .. } = args;
+fn run(file: impl Write, format: output::Format, args: cmd::Args) -> Result<(), String> {
+    let cmd::Args{ width, height, pixel_samples, threads, .. } = args;
     let aspect = width as f32 / height as f32;
     let time_interval = Interval::new(0.0, 1.0).unwrap();
-    let (scene, camera) = make_scene(&args.scene, aspect, &time_interval)?;
-    let (tmin, tmax) = (time_interval.min(), time_interval.max());
-    
+    let bvh_options = bvh::Options {
+        split_method: args.bvh_split_method,
+        layout: args.bvh_layout,
+    };
+    let (scene, camera, scene_max_depths) = match &args.scene_file {
+        Some(path) => scene_file::load(Path::new(path), aspect, &time_interval, &bvh_options)?,
+        None => {
+            let (scene, camera) = make_scene(&args.scene, aspect, &time_interval, &bvh_options)?;
+            (scene, camera, render::MaxDepths::default())
+        },
+    };
+    let max_depths = render::MaxDepths {
+        diffuse: args.max_diffuse_depth.unwrap_or(scene_max_depths.diffuse),
+        glossy: args.max_glossy_depth.unwrap_or(scene_max_depths.glossy),
+        transmission: args.max_transmission_depth.unwrap_or(scene_max_depths.transmission),
+        volume: args.max_volume_depth.unwrap_or(scene_max_depths.volume),
+    };
+    if args.print_stats {
+        eprintln!("BVH {}", scene.bvh_stats());
+    }
+    let settings = render::Settings {
+        width: width as usize,
+        height: height as usize,
+        pixel_samples: pixel_samples as u32,
+        threads,
+        time_interval,
+        alpha: args.alpha,
+        seed: args.seed,
+        sampler: args.sampler,
+        adaptive: args.noise_threshold.map(|noise_threshold| render::Adaptive {
+            noise_threshold,
+            max_samples: args.max_samples,
+        }),
+        max_depths,
+    };
+    let heatmap = args.heatmap
+        .as_ref()
+        .map(|path| output::Format::from_path(Path::new(path)).map(|format| (path, format)))
+        .transpose()?;
+
+    let start = Instant::now();
+    let framebuffer = render::render(&scene, &camera, &settings);
+    if args.print_stats {
+        eprintln!("Rendered in {:.2?}", start.elapsed());
+    }
     let mut file = BufWriter::new(file);
-    let _ = writeln!(file, "P3\n{} {}\n255", width, height);
-    for y in (0..height).rev() {
-        for x in 0..width {
-            let mut colour = Colour::new(0.0, 0.0, 0.0);
-            for _ in 0..pixel_samples {
-                let u = (x as f32 + random_float_from_0_to_1()) / width as f32;
-                let v = (y as f32 + random_float_from_0_to_1()) / height as f32;
-                let time = tmin + random_float_from_0_to_1()*(tmax - tmin);
-                let ray = camera.make_ray((u, v), time);
-                colour += to_colour(&ray, &scene, 0);
-            }
-            let colour = (colour / pixel_samples as f32)
-                .map(|c| c.sqrt())
-                .map(|c| (255.99 * c) as i32);
-            let _ = writeln!(file, "{} {} {}", colour[0], colour[1], colour[2]);
-        }
+    output::write(&mut file, &framebuffer, format, args.alpha)?;
+    file.flush().map_err(|e| e.to_string())?;
+
+    if let Some((path, format)) = heatmap {
+        let file = File::create(path)
+            .map_err(|e| format!("Couldn't create {}: {}", path, e))?;
+        let mut file = BufWriter::new(file);
+        output::write(&mut file, &framebuffer.sample_heatmap(settings.max_samples()), format, false)?;
+        file.flush().map_err(|e| e.to_string())?;
     }
-    file.flush().unwrap();
-    
     Ok(())
 }
 
-fn make_scene(name: &str, aspect: f32, time_interval: &Interval<f32>) -> Result<(Scene, Camera), String> {
+fn make_scene(name: &str, aspect: f32, time_interval: &Interval<f32>, bvh_options: &bvh::Options) -> Result<(Scene, Camera), String> {
     match name {
-        "cornell-box"    => Ok(example_scenes::cornell_box::scene(aspect, time_interval)),
-        "simple-light"   => Ok(example_scenes::simple_light::scene(aspect, time_interval)),
-        "random-spheres" => Ok(example_scenes::many_random_spheres::scene(aspect, time_interval)),
+        "cornell-box"    => Ok(example_scenes::cornell_box::scene(aspect, time_interval, bvh_options)),
+        "simple-light"   => Ok(example_scenes::simple_light::scene(aspect, time_interval, bvh_options)),
+        "random-spheres" => Ok(example_scenes::many_random_spheres::scene(aspect, time_interval, bvh_options)),
         _                => Err(format!("Invalid scene: `{}`", name)),
     }
 }
-
-fn to_colour(ray: &Ray, scene: &Scene, depth: i32) -> Colour {
-    use materials::ScatterResult;
-
-    let interval = Interval::new(0.001, std::f32::MAX).unwrap();
-    if let Some(hit_record) = scene.hit(ray, &interval) {
-        let scatter_result = hit_record.material.scatter(ray, &hit_record);
-        let emitted = hit_record.material.emitted(&hit_record.uv, &hit_record.hit_point);
-        if depth < 50 && scatter_result.is_some() {
-            let ScatterResult{ scattered_ray, attenuation } = scatter_result.unwrap();
-            let colour = to_colour(&scattered_ray, scene, depth + 1);
-            Colour::new(
-             attenuation[0] * colour[0],
-             attenuation[1] * colour[1],
-             attenuation[2] * colour[2])
-            + emitted
-        }
-        else {
-            emitted
-        }
-    }
-    else {
-        Colour::new(0.0, 0.0, 0.0)
-    }
-}
\ No newline at end of file
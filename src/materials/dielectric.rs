@@ -1,14 +1,16 @@
 use crate::{
-    math,
+    math::{
+        self,
+        Vec3,
+    },
     materials::{
+        BsdfSample,
+        Lobe,
         Material,
-        ScatterResult,
     },
     core::{
         HitRecord,
-        Ray,
     },
-    random,
 };
 
 #[derive(Copy, Clone)]
@@ -25,36 +27,40 @@ impl Dielectric {
 }
 
 impl Material for Dielectric {
-    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterResult> {
+    // either reflects or refracts, picked by the Fresnel reflectance
+    fn sample(&self, hit_record: &HitRecord, wo: &Vec3, u: f32, _: (f32, f32)) -> Option<BsdfSample> {
         use math::{InnerSpace, vec3};
            
-        let direction_normal_dot = math::dot(ray.direction, hit_record.normal);
+        let ray_direction = -*wo;
+        let direction_normal_dot = math::dot(ray_direction, hit_record.normal);
         let (outward_normal, ni_over_nt, cosine) = 
             if direction_normal_dot > 0.0 {
-                let cosine = self.refractive_index * direction_normal_dot / ray.direction.magnitude();
+                let cosine = self.refractive_index * direction_normal_dot;
                 (-hit_record.normal, self.refractive_index, cosine)
             }
             else {
-                let cosine = -direction_normal_dot / ray.direction.magnitude();
+                let cosine = -direction_normal_dot;
                 (hit_record.normal, 1.0 / self.refractive_index, cosine)
             };                
         let (refracted_direction, reflection_coefficient) = 
-            if let Some(refracted_direction) = math::refracted(&ray.direction, &outward_normal, ni_over_nt) {
+            if let Some(refracted_direction) = math::refracted(&ray_direction, &outward_normal, ni_over_nt) {
                 (refracted_direction, math::schlick(cosine, self.refractive_index))
             }
             else {
                 (vec3(0.0, 0.0, 0.0), 1.0)
             };
-        let direction = if random::random_float_from_0_to_1() < reflection_coefficient { 
-            math::reflected(&ray.direction, &hit_record.normal) 
+        let (direction, lobe) = if u < reflection_coefficient { 
+            (math::reflected(&ray_direction, &hit_record.normal), Lobe::Delta)
         } 
         else { 
-            refracted_direction
+            (refracted_direction, Lobe::Transmission)
         };
 
-        Some(ScatterResult{
-            scattered_ray: Ray::new(hit_record.hit_point, direction, ray.time),
-            attenuation: vec3(1.0, 1.0, 1.0),
+        Some(BsdfSample {
+            direction: direction.normalize(),
+            value: vec3(1.0, 1.0, 1.0),
+            pdf: 1.0,
+            lobe,
         })
     }
 }
\ No newline at end of file
@@ -1,25 +1,21 @@
 use crate::{
     math,
     materials::{
-        ScatterResult,
         Material,
     },
-    core::{
-        Ray,
-        HitRecord,
-    },
     textures::{
         Texture,
         TextureCoordinates,
     },
 };
+use std::sync::Arc;
 
 pub struct DiffuseLight {
-    emit: Box<dyn Texture>,
+    emit: Arc<dyn Texture>,
 }
 
 impl DiffuseLight {
-    pub fn new(emit: Box<dyn Texture>) -> Self {
+    pub fn new(emit: Arc<dyn Texture>) -> Self {
         DiffuseLight{
             emit,
         }
@@ -27,11 +23,11 @@ impl DiffuseLight {
 }
 
 impl Material for DiffuseLight {
-    fn scatter(&self, _: &Ray, _: &HitRecord) -> Option<ScatterResult> {
-        None
-    }
-
     fn emitted(&self, uv: &TextureCoordinates, p: &math::Point3) -> math::Vec3 {
         self.emit.value(uv, p)
     }
+
+    fn is_emissive(&self) -> bool {
+        true
+    }
 }
\ No newline at end of file
@@ -1,24 +1,31 @@
 use crate::{
-    math,
+    math::{
+        Vec3,
+    },
     core::{
-        Ray,
         HitRecord,
     },
     materials::{
+        BsdfSample,
+        Lobe,
         Material,
-        ScatterResult,
     },
     textures::{
         Texture,
     },
+    sampling,
+};
+use std::{
+    f32::consts::PI,
+    sync::Arc,
 };
 
 pub struct Isotropic {
-    albedo: Box<dyn Texture>,
+    albedo: Arc<dyn Texture>,
 }
 
 impl Isotropic {
-    pub fn new(albedo: Box<dyn Texture>) -> Self {
+    pub fn new(albedo: Arc<dyn Texture>) -> Self {
         Self {
             albedo,
         }
@@ -26,12 +33,23 @@ impl Isotropic {
 }
 
 impl Material for Isotropic {
-    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterResult> {
-        use math::EuclideanSpace;
+    fn eval(&self, hit_record: &HitRecord, wo: &Vec3, wi: &Vec3) -> Vec3 {
+        self.albedo.value(&hit_record.uv, &hit_record.hit_point) * self.pdf(hit_record, wo, wi)
+    }
 
-        Some(ScatterResult {
-            scattered_ray: Ray::new(hit_record.hit_point, math::random_point_from_unit_sphere().to_vec(), ray.time),
-            attenuation: self.albedo.value(&hit_record.uv, &hit_record.hit_point),
+    // directions are uniform over the unit sphere
+    fn sample(&self, hit_record: &HitRecord, wo: &Vec3, _: f32, u2: (f32, f32)) -> Option<BsdfSample> {
+        let direction = sampling::uniform_sphere(u2);
+        let pdf = self.pdf(hit_record, wo, &direction);
+        Some(BsdfSample {
+            direction,
+            value: self.eval(hit_record, wo, &direction),
+            pdf,
+            lobe: Lobe::Volume,
         })
     }
+
+    fn pdf(&self, _: &HitRecord, _: &Vec3, _: &Vec3) -> f32 {
+        1.0 / (4.0 * PI)
+    }
 }
\ No newline at end of file
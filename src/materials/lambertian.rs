@@ -1,42 +1,143 @@
 use crate::{
-    math,
+    math::{
+        self,
+        Vec3,
+    },
     materials::{
-        ScatterResult,
+        BsdfSample,
+        Lobe,
         Material,
     },
     core::{
-        Ray,
         HitRecord,
     },
     textures::{
         Texture,
-        TextureCoordinates,
     },
+    sampling,
+};
+use std::{
+    f32::consts::PI,
+    sync::Arc,
 };
 
 pub struct Lambertian {
-    albedo: Box<dyn Texture>,
+    albedo: Arc<dyn Texture>,
 }
 
 impl Lambertian {
-    pub fn new(albedo: Box<dyn Texture>) -> Lambertian {
+    pub fn new(albedo: Arc<dyn Texture>) -> Lambertian {
         Lambertian {
             albedo,
         }
     }
+
+    fn albedo(&self, hit_record: &HitRecord) -> Vec3 {
+        self.albedo.value(&hit_record.uv, &hit_record.hit_point)
+    }
+}
+
+// both sides of the surface are diffuse, so the normal is flipped towards the viewer
+fn facing_normal(hit_record: &HitRecord, wo: &Vec3) -> Vec3 {
+    if math::dot(hit_record.normal, *wo) < 0.0 { -hit_record.normal } else { hit_record.normal }
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterResult> {
-        use math::{random_point_from_unit_sphere, EuclideanSpace};
-        
-        let tangent_unit_sphere_center = hit_record.hit_point + hit_record.normal;
-        let target_point = tangent_unit_sphere_center + random_point_from_unit_sphere().to_vec();
-        let direction = target_point - hit_record.hit_point;
-        let uv = TextureCoordinates::zero();
-        Some(ScatterResult{
-            attenuation: self.albedo.value(&uv, &hit_record.hit_point),
-            scattered_ray: Ray::new(hit_record.hit_point, direction, ray.time),
+    fn eval(&self, hit_record: &HitRecord, wo: &Vec3, wi: &Vec3) -> Vec3 {
+        self.albedo(hit_record) * self.pdf(hit_record, wo, wi)
+    }
+
+    // cosine weighted directions over the hemisphere around the normal
+    fn sample(&self, hit_record: &HitRecord, wo: &Vec3, _: f32, u2: (f32, f32)) -> Option<BsdfSample> {
+        let local = sampling::cosine_hemisphere(u2);
+        let direction = math::Onb::from_w(&facing_normal(hit_record, wo)).local(&local);
+        let pdf = self.pdf(hit_record, wo, &direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(BsdfSample {
+            direction,
+            value: self.albedo(hit_record) * pdf,
+            pdf,
+            lobe: Lobe::Diffuse,
         })
     }
+
+    fn pdf(&self, hit_record: &HitRecord, wo: &Vec3, wi: &Vec3) -> f32 {
+        let cosine = math::dot(facing_normal(hit_record, wo), *wi);
+        cosine.max(0.0) / PI
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        math::{
+            Point3,
+            InnerSpace,
+        },
+        textures::{
+            ConstantTexture,
+            ImageTexture,
+            Filter,
+            TextureCoordinates,
+        },
+        input::Image,
+        random::Rng,
+    };
+
+    fn hit_record(material: &dyn Material) -> HitRecord<'_> {
+        HitRecord {
+            t: 1.0,
+            hit_point: Point3::new(0.0, 0.0, 0.0),
+            normal: math::vec3(0.0, 1.0, 0.0),
+            material,
+            uv: TextureCoordinates::zero(),
+        }
+    }
+
+    #[test]
+    fn samples_are_in_the_hemisphere_of_the_viewer() {
+        let lambertian = Lambertian::new(Arc::new(ConstantTexture::from_rgb(math::vec3(0.5, 0.5, 0.5))));
+        let hit_record = hit_record(&lambertian);
+        let wo = math::vec3(0.0, -1.0, 0.0);
+        let mut rng = Rng::new(0);
+
+        for _ in 0..100 {
+            let sample = lambertian.sample(&hit_record, &wo, 0.0, (rng.random_float_from_0_to_1(), rng.random_float_from_0_to_1())).unwrap();
+            assert!(sample.direction.y < 0.0);
+            assert!((sample.direction.magnitude() - 1.0).abs() < 1e-5);
+            assert!((sample.pdf - lambertian.pdf(&hit_record, &wo, &sample.direction)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn eval_is_albedo_times_cosine_over_pi() {
+        let lambertian = Lambertian::new(Arc::new(ConstantTexture::from_rgb(math::vec3(0.5, 0.5, 0.5))));
+        let hit_record = hit_record(&lambertian);
+        let wo = math::vec3(0.0, 1.0, 0.0);
+        let wi = math::vec3(1.0, 1.0, 0.0).normalize();
+
+        let value = lambertian.eval(&hit_record, &wo, &wi);
+
+        assert!((value.x - 0.5 * wi.y / PI).abs() < 1e-6);
+        assert_eq!(lambertian.eval(&hit_record, &wo, &-wi), math::vec3(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn albedo_is_looked_up_at_the_hit_texture_coordinates() {
+        // red on the left half of the image, blue on the right
+        let image = Image {
+            width: 2,
+            height: 1,
+            pixels: vec![math::vec3(1.0, 0.0, 0.0), math::vec3(0.0, 0.0, 1.0)],
+        };
+        let lambertian = Lambertian::new(Arc::new(ImageTexture::new(image).with_filter(Filter::Nearest)));
+        let mut hit_record = hit_record(&lambertian);
+        hit_record.uv = TextureCoordinates{ u: 0.75, v: 0.5 };
+
+        assert_eq!(lambertian.albedo(&hit_record), math::vec3(0.0, 0.0, 1.0));
+    }
 }
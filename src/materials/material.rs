@@ -6,16 +6,63 @@ use crate::{
     },
 };
 
-#[derive(Copy, Clone)]
-pub struct ScatterResult {
-    pub attenuation: math::Vec3,
-    pub scattered_ray: core::Ray,
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Lobe {
+    // scatters smoothly over the sphere of directions
+    Diffuse,
+    // reflects in a single direction, or one fuzzed around it without a density
+    Delta,
+    // passes through the surface in a single direction
+    Transmission,
+    // scatters inside a participating medium
+    Volume,
 }
 
-pub trait Material {
-    fn scatter(&self, ray: &core::Ray, hit_record: &core::HitRecord) -> Option<ScatterResult>;
+impl Lobe {
+    // Specular lobes can't be evaluated for arbitrary directions,
+    // their `eval` and `pdf` are zero and they don't use light sampling.
+    pub fn is_specular(self) -> bool {
+        match self {
+            Lobe::Diffuse | Lobe::Volume => false,
+            Lobe::Delta | Lobe::Transmission => true,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct BsdfSample {
+    // the direction of the scattered ray
+    pub direction: math::Vec3,
+    // the BSDF times the cosine to the normal, `value / pdf` is the attenuation along the scattered ray
+    pub value: math::Vec3,
+    // the density of `direction`, 1 for specular lobes
+    pub pdf: f32,
+    pub lobe: Lobe,
+}
+
+// `wo` points back along the incoming ray and `wi` along the scattered one, both are unit vectors.
+pub trait Material: Send + Sync {
+    // The BSDF times the cosine to the normal.
+    fn eval(&self, _hit_record: &core::HitRecord, _wo: &math::Vec3, _wi: &math::Vec3) -> math::Vec3 {
+        math::vec3(0.0, 0.0, 0.0)
+    }
+
+    // Picks the direction of the scattered ray, None if the ray is absorbed.
+    // `u` chooses between the kinds of scattering and `u2` the direction, both are uniform samples.
+    fn sample(&self, _hit_record: &core::HitRecord, _wo: &math::Vec3, _u: f32, _u2: (f32, f32)) -> Option<BsdfSample> {
+        None
+    }
+
+    // The density with which `sample` picks `wi`.
+    fn pdf(&self, _hit_record: &core::HitRecord, _wo: &math::Vec3, _wi: &math::Vec3) -> f32 {
+        0.0
+    }
     
     fn emitted(&self, _: &TextureCoordinates, _p: &math::Point3) -> math::Vec3 {
         math::vec3(0.0, 0.0, 0.0)
     }
-}
\ No newline at end of file
+
+    fn is_emissive(&self) -> bool {
+        false
+    }
+}
@@ -1,13 +1,17 @@
 use crate::{
-    math,
+    math::{
+        self,
+        Vec3,
+    },
     core::{
-        Ray,
         HitRecord,
     },
     materials::{
+        BsdfSample,
+        Lobe,
         Material,
-        ScatterResult,
-    }
+    },
+    sampling,
 };
 
 pub struct Metal {
@@ -26,20 +30,52 @@ impl Metal {
 }
 
 impl Material for Metal {
-    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterResult> {
-        use math::{InnerSpace, EuclideanSpace, random_point_from_unit_sphere};
+    fn sample(&self, hit_record: &HitRecord, wo: &Vec3, u: f32, u2: (f32, f32)) -> Option<BsdfSample> {
+        use math::InnerSpace;
 
-        let reflected_dir = math::reflected(&ray.direction.normalize(), &hit_record.normal);
+        let reflected_dir = math::reflected(&-*wo, &hit_record.normal);
         let is_angle_acute = math::dot(reflected_dir, hit_record.normal) > 0.0;
         if is_angle_acute {
-            let direction = reflected_dir + self.fuzz * random_point_from_unit_sphere().to_vec();
-            Some(ScatterResult{
-                scattered_ray: Ray::new(hit_record.hit_point, direction, ray.time),
-                attenuation: self.albedo,
+            let direction = reflected_dir + self.fuzz * sampling::uniform_ball(u, u2);
+            // the fuzzed direction has no density that `eval` and `pdf` could match,
+            // so rough metal is sampled like a mirror too
+            Some(BsdfSample {
+                direction: direction.normalize(),
+                value: self.albedo,
+                pdf: 1.0,
+                lobe: Lobe::Delta,
             })
         }
         else {
             None
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        math::Point3,
+        textures::TextureCoordinates,
+    };
+
+    #[test]
+    fn polished_metal_is_a_mirror() {
+        let metal = Metal::new(math::vec3(0.8, 0.8, 0.8), 0.0);
+        let hit_record = HitRecord {
+            t: 1.0,
+            hit_point: Point3::new(0.0, 0.0, 0.0),
+            normal: math::vec3(0.0, 1.0, 0.0),
+            material: &metal,
+            uv: TextureCoordinates::zero(),
+        };
+        let wo = math::vec3(0.6, 0.8, 0.0);
+        let sample = metal.sample(&hit_record, &wo, 0.5, (0.5, 0.5)).unwrap();
+
+        assert_eq!(sample.lobe, Lobe::Delta);
+        assert!((sample.direction - math::vec3(-0.6, 0.8, 0.0)).x.abs() < 1e-6);
+        assert!((sample.direction - math::vec3(-0.6, 0.8, 0.0)).y.abs() < 1e-6);
+        assert_eq!(Metal::new(math::vec3(0.8, 0.8, 0.8), 0.3).sample(&hit_record, &wo, 0.5, (0.5, 0.5)).unwrap().lobe, Lobe::Delta);
+    }
 }
\ No newline at end of file
@@ -7,7 +7,8 @@ mod isotropic;
 
 pub use material::{
     Material,
-    ScatterResult,
+    BsdfSample,
+    Lobe,
 };
 pub use dielectric::Dielectric;
 pub use lambertian::Lambertian;
@@ -1,16 +1,26 @@
 mod interval;
+mod onb;
+mod polynomial;
 
 pub type Vec3 = cgmath::Vector3<f32>;
 pub type Point3 = cgmath::Point3<f32>;
+pub type Mat4 = cgmath::Matrix4<f32>;
 
 pub use cgmath::{ 
     vec3,
     dot,
     EuclideanSpace,
+    ElementWise,
     prelude::InnerSpace,
-    prelude::VectorSpace,
+    Matrix,
+    SquareMatrix,
 };
 pub use interval::Interval;
+pub use onb::Onb;
+pub use polynomial::{
+    solve_quadratic,
+    solve_quartic,
+};
 
 pub fn reflected(v: &Vec3, normal: &Vec3) -> Vec3 {
     v - 2.0 * dot(*v, *normal) * normal
@@ -35,17 +45,3 @@ pub fn schlick(cosine: f32, refractive_index: f32) -> f32 {
     };
     r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
 }
-
-pub fn random_point_from_unit_sphere() -> Point3 {
-    use crate::random::random_float_from_0_to_1;
-
-    loop {
-        let vec = 
-            vec3(random_float_from_0_to_1(), random_float_from_0_to_1(), random_float_from_0_to_1())
-            .map(|c| 2.0 * c)
-            .map(|c| c - 1.0);
-        if vec.magnitude() < 1.0 {
-            return EuclideanSpace::from_vec(vec);
-        }
-    }
-}
\ No newline at end of file
@@ -0,0 +1,49 @@
+use crate::math::{
+    Vec3,
+    vec3,
+    InnerSpace,
+};
+
+// An orthonormal basis with `w` along a given direction.
+#[derive(Copy, Clone, Debug)]
+pub struct Onb {
+    pub u: Vec3,
+    pub v: Vec3,
+    pub w: Vec3,
+}
+
+impl Onb {
+    pub fn from_w(direction: &Vec3) -> Self {
+        let w = direction.normalize();
+        let a = if w.x.abs() > 0.9 { vec3(0.0, 1.0, 0.0) } else { vec3(1.0, 0.0, 0.0) };
+        let v = w.cross(a).normalize();
+        let u = w.cross(v);
+        Self {
+            u,
+            v,
+            w,
+        }
+    }
+
+    // converts coordinates relative to the basis to world coordinates
+    pub fn local(&self, a: &Vec3) -> Vec3 {
+        a.x * self.u + a.y * self.v + a.z * self.w
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::dot;
+
+    #[test]
+    fn basis_is_orthonormal() {
+        let onb = Onb::from_w(&vec3(1.0, 2.0, -3.0));
+
+        assert!((onb.u.magnitude() - 1.0).abs() < 1e-6);
+        assert!((onb.v.magnitude() - 1.0).abs() < 1e-6);
+        assert!(dot(onb.u, onb.v).abs() < 1e-6);
+        assert!(dot(onb.u, onb.w).abs() < 1e-6);
+        assert!(dot(onb.v, onb.w).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,154 @@
+// Real roots of low degree polynomials, the coefficients are given from the highest degree down.
+
+// The roots of a t^2 + b t + c in increasing order, a single root is returned twice.
+// Computed without the cancellation of the textbook formula when b^2 is much larger than 4 a c.
+pub fn solve_quadratic(a: f32, b: f32, c: f32) -> Option<(f32, f32)> {
+    let (a, b, c) = (a as f64, b as f64, c as f64);
+    if a == 0.0 {
+        if b == 0.0 {
+            return None;
+        }
+        let t = (-c / b) as f32;
+        return Some((t, t));
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    if q == 0.0 {
+        // b and c are both zero
+        return Some((0.0, 0.0));
+    }
+    let (t0, t1) = (q / a, c / q);
+    if t0 < t1 { Some((t0 as f32, t1 as f32)) } else { Some((t1 as f32, t0 as f32)) }
+}
+
+// The largest real root of t^3 + a t^2 + b t + c.
+fn largest_cubic_root(a: f64, b: f64, c: f64) -> f64 {
+    // substituting t = x - a / 3 gives x^3 + p x + q
+    let p = b - a * a / 3.0;
+    let q = 2.0 * a * a * a / 27.0 - a * b / 3.0 + c;
+    let discriminant = q * q / 4.0 + p * p * p / 27.0;
+    let x = if discriminant > 0.0 {
+        let d_sqrt = discriminant.sqrt();
+        (-q / 2.0 + d_sqrt).cbrt() + (-q / 2.0 - d_sqrt).cbrt()
+    }
+    else if p == 0.0 {
+        0.0
+    }
+    else {
+        // three real roots, of which the first is the largest
+        let angle = (1.5 * q / p * (-3.0 / p).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+        2.0 * (-p / 3.0).sqrt() * angle.cos()
+    };
+    x - a / 3.0
+}
+
+// The real roots of a t^4 + b t^3 + c t^2 + d t + e in increasing order by Ferrari's method,
+// polished with Newton's method. `a` must not be zero.
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+
+    // substituting t = y - b / 4 gives y^4 + p y^2 + q y + r
+    let shift = b / 4.0;
+    let p = c - 6.0 * shift * shift;
+    let q = d - 2.0 * c * shift + 8.0 * shift * shift * shift;
+    let r = e - d * shift + c * shift * shift - 3.0 * shift * shift * shift * shift;
+
+    let mut roots = Vec::with_capacity(4);
+    let mut push_quadratic_roots = |b: f64, c: f64| {
+        let discriminant = b * b - 4.0 * c;
+        if discriminant >= 0.0 {
+            let d_sqrt = discriminant.sqrt();
+            roots.push(0.5 * (-b - d_sqrt));
+            roots.push(0.5 * (-b + d_sqrt));
+        }
+    };
+    if q.abs() < 1e-12 {
+        // y^4 + p y^2 + r is a quadratic in y^2
+        let discriminant = p * p - 4.0 * r;
+        if discriminant >= 0.0 {
+            let d_sqrt = discriminant.sqrt();
+            for z in &[0.5 * (-p - d_sqrt), 0.5 * (-p + d_sqrt)] {
+                if *z >= 0.0 {
+                    push_quadratic_roots(0.0, -z);
+                }
+            }
+        }
+    }
+    else {
+        // (y^2 + p / 2 + m)^2 = 2 m y^2 - q y + m^2 + m p + p^2 / 4 - r has a square on the right
+        // for a positive root m of the resolvent cubic, then the quartic splits into two quadratics
+        let m = largest_cubic_root(p, p * p / 4.0 - r, -q * q / 8.0);
+        if m <= 0.0 {
+            return roots;
+        }
+        let s = (2.0 * m).sqrt();
+        push_quadratic_roots(-s, p / 2.0 + m + q / (2.0 * s));
+        push_quadratic_roots(s, p / 2.0 + m - q / (2.0 * s));
+    }
+
+    let polynomial = |t: f64| (((t + b) * t + c) * t + d) * t + e;
+    let derivative = |t: f64| ((4.0 * t + 3.0 * b) * t + 2.0 * c) * t + d;
+    for root in roots.iter_mut() {
+        *root -= shift;
+        for _ in 0..2 {
+            let slope = derivative(*root);
+            if slope != 0.0 {
+                *root -= polynomial(*root) / slope;
+            }
+        }
+    }
+    roots.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quadratic_roots_are_accurate_when_b_dominates() {
+        let (t0, t1) = solve_quadratic(1.0, 1e4, 1.0).unwrap();
+
+        assert!((t0 + 1e4).abs() < 1e-2);
+        assert!((t1 + 1e-4).abs() / 1e-4 < 1e-4);
+        assert_eq!(solve_quadratic(0.0, 2.0, -4.0), Some((2.0, 2.0)));
+        assert_eq!(solve_quadratic(1.0, 0.0, 1.0), None);
+    }
+
+    #[test]
+    fn quartic_with_four_real_roots() {
+        // (t - 1)(t - 2)(t - 3)(t - 4)
+        let roots = solve_quartic(2.0, -20.0, 70.0, -100.0, 48.0);
+
+        assert_eq!(roots.len(), 4);
+        for (root, expected) in roots.iter().zip(&[1.0, 2.0, 3.0, 4.0]) {
+            assert!((root - expected).abs() < 1e-9, "{:?}", roots);
+        }
+    }
+
+    #[test]
+    fn quartic_with_complex_roots() {
+        // (t^2 + 1)(t - 1)(t + 2)
+        let roots = solve_quartic(1.0, 1.0, -1.0, 1.0, -2.0);
+        assert_eq!(roots.len(), 2);
+        assert!((roots[0] + 2.0).abs() < 1e-9 && (roots[1] - 1.0).abs() < 1e-9, "{:?}", roots);
+
+        // (t^2 + 1)(t^2 + 4)
+        assert!(solve_quartic(1.0, 0.0, 5.0, 0.0, 4.0).is_empty());
+    }
+
+    #[test]
+    fn biquadratic_quartic() {
+        // (t^2 - 1)(t^2 - 9)
+        let roots = solve_quartic(1.0, 0.0, -10.0, 0.0, 9.0);
+
+        assert_eq!(roots.len(), 4);
+        for (root, expected) in roots.iter().zip(&[-3.0, -1.0, 1.0, 3.0]) {
+            assert!((root - expected).abs() < 1e-9, "{:?}", roots);
+        }
+    }
+}
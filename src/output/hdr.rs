@@ -0,0 +1,128 @@
+// Radiance RGBE images with run length encoded scanlines.
+
+use crate::{
+    render::{
+        Colour,
+        Framebuffer,
+    },
+};
+use std::io::Write;
+
+pub fn write(mut output: impl Write, framebuffer: &Framebuffer) -> Result<(), String> {
+    let (width, height) = (framebuffer.width(), framebuffer.height());
+    let mut bytes = format!("#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n", height, width).into_bytes();
+    for row in framebuffer.pixels().chunks(width) {
+        let rgbe = row.iter().map(|p| to_rgbe(&p.colour)).collect::<Vec<_>>();
+        write_scanline(&mut bytes, &rgbe);
+    }
+    output.write_all(&bytes).map_err(|e| e.to_string())
+}
+
+// A shared exponent and three 8-bit mantissas. Negative values are clamped to zero and
+// infinite or huge ones to the largest encodable value, whose exponent still fits in a byte.
+fn to_rgbe(colour: &Colour) -> [u8; 4] {
+    let largest = 255.0 / 256.0 * 2f32.powi(127);
+    let clamp = |c: f32| c.max(0.0).min(largest);
+    let (r, g, b) = (clamp(colour.x), clamp(colour.y), clamp(colour.z));
+    let max = r.max(g).max(b);
+    if max < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+    // max = mantissa * 2^exponent with mantissa in [0.5, 1)
+    let mut exponent = max.log2().floor() as i32 + 1;
+    if max / 2f32.powi(exponent) >= 1.0 {
+        exponent += 1;
+    }
+    let scale = 256.0 / 2f32.powi(exponent);
+    [(r * scale) as u8, (g * scale) as u8, (b * scale) as u8, (exponent + 128) as u8]
+}
+
+// Scanlines of unusual widths can't be encoded and are stored flat.
+fn write_scanline(bytes: &mut Vec<u8>, rgbe: &[[u8; 4]]) {
+    let width = rgbe.len();
+    if !(8..0x8000).contains(&width) {
+        for pixel in rgbe {
+            bytes.extend_from_slice(pixel);
+        }
+        return;
+    }
+
+    bytes.extend_from_slice(&[2, 2, (width >> 8) as u8, (width & 0xff) as u8]);
+    for channel in 0..4 {
+        let values = rgbe.iter().map(|p| p[channel]).collect::<Vec<_>>();
+        encode_runs(bytes, &values);
+    }
+}
+
+// Runs are a count above 128 followed by the repeated byte,
+// literals are a count up to 128 followed by the bytes.
+fn encode_runs(bytes: &mut Vec<u8>, values: &[u8]) {
+    const MIN_RUN: usize = 3;
+
+    let run_length = |start: usize| {
+        values[start..]
+            .iter()
+            .take(127)
+            .take_while(|&&v| v == values[start])
+            .count()
+    };
+    let mut i = 0;
+    while i < values.len() {
+        let run = run_length(i);
+        if run >= MIN_RUN {
+            bytes.extend_from_slice(&[128 + run as u8, values[i]]);
+            i += run;
+            continue;
+        }
+        let start = i;
+        while i < values.len() && i - start < 128 && run_length(i) < MIN_RUN {
+            i += 1;
+        }
+        bytes.push((i - start) as u8);
+        bytes.extend_from_slice(&values[start..i]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_rgbe(rgbe: [u8; 4]) -> Colour {
+        let scale = 2f32.powi(rgbe[3] as i32 - 128 - 8);
+        Colour::new(rgbe[0] as f32, rgbe[1] as f32, rgbe[2] as f32) * scale
+    }
+
+    #[test]
+    fn rgbe_keeps_values_above_one() {
+        let colour = Colour::new(15.0, 0.5, 0.0);
+
+        let decoded = from_rgbe(to_rgbe(&colour));
+
+        assert!((decoded.x - 15.0).abs() < 15.0 / 128.0);
+        assert!((decoded.y - 0.5).abs() < 15.0 / 128.0);
+        assert_eq!(decoded.z, 0.0);
+        assert_eq!(to_rgbe(&Colour::new(0.0, 0.0, 0.0)), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn infinite_and_huge_values_are_clamped() {
+        let largest = [255, 0, 0, 255];
+
+        assert_eq!(to_rgbe(&Colour::new(f32::INFINITY, 0.0, 0.0)), largest);
+        assert_eq!(to_rgbe(&Colour::new(f32::MAX, f32::NAN, -1.0)), largest);
+        // 1e38 still has an exponent of 127 rather than wrapping around to black
+        let decoded = from_rgbe(to_rgbe(&Colour::new(1e38, 0.0, 0.0)));
+        assert!((decoded.x - 1e38).abs() < 1e38 / 128.0);
+        let decoded = from_rgbe(to_rgbe(&Colour::new(f32::INFINITY, 1e38, 1.0)));
+        assert!(decoded.x > 1.6e38 && (decoded.y - 1e38).abs() < 1e36 * 2.0 && decoded.z == 0.0);
+    }
+
+    #[test]
+    fn runs_and_literals_are_encoded() {
+        let mut bytes = Vec::new();
+
+        encode_runs(&mut bytes, &[7, 7, 7, 7, 1, 2, 3, 3]);
+
+        assert_eq!(bytes, vec![132, 7, 4, 1, 2, 3, 3]);
+    }
+}
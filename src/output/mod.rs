@@ -0,0 +1,113 @@
+mod ppm;
+mod png;
+mod pfm;
+mod hdr;
+
+use crate::{
+    render::{
+        Framebuffer,
+    },
+};
+use std::{
+    io::Write,
+    path::Path,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Format {
+    Png,
+    // binary P6
+    Ppm,
+    // plain text P3
+    PpmAscii,
+    // linear floats
+    Pfm,
+    // linear Radiance RGBE
+    Hdr,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "png" => Some(Format::Png),
+            "ppm" => Some(Format::Ppm),
+            "ppm-ascii" => Some(Format::PpmAscii),
+            "pfm" => Some(Format::Pfm),
+            "hdr" => Some(Format::Hdr),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Result<Format, String> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase());
+        match extension.as_deref() {
+            Some("png") => Ok(Format::Png),
+            Some("ppm") | Some("pnm") => Ok(Format::Ppm),
+            Some("pfm") => Ok(Format::Pfm),
+            Some("hdr") => Ok(Format::Hdr),
+            _ => Err(format!("Can't tell the image format of {}, use a .png, .ppm, .pfm or .hdr extension or pass --format", path.display())),
+        }
+    }
+
+    pub fn supports_alpha(self) -> bool {
+        self == Format::Png
+    }
+}
+
+// `alpha` must only be set for formats which support it
+pub fn write(output: impl Write, framebuffer: &Framebuffer, format: Format, alpha: bool) -> Result<(), String> {
+    match format {
+        Format::Png => png::write(output, framebuffer, alpha),
+        Format::Ppm => ppm::write_binary(output, framebuffer),
+        Format::PpmAscii => ppm::write_ascii(output, framebuffer),
+        Format::Pfm => pfm::write(output, framebuffer),
+        Format::Hdr => hdr::write(output, framebuffer),
+    }
+}
+
+// Gamma corrected 8-bit channels of every pixel, row by row from the top.
+// Colours are clamped to [0, 1] before the conversion.
+fn to_8_bit(framebuffer: &Framebuffer, alpha: bool) -> Vec<u8> {
+    let channels = if alpha { 4 } else { 3 };
+    let to_byte = |c: f32| (255.99 * c.clamp(0.0, 1.0)) as u8;
+    let mut bytes = Vec::with_capacity(channels * framebuffer.pixels().len());
+    for pixel in framebuffer.pixels() {
+        let colour = pixel.colour.map(|c| c.max(0.0).sqrt());
+        bytes.extend_from_slice(&[to_byte(colour.x), to_byte(colour.y), to_byte(colour.z)]);
+        if alpha {
+            bytes.push(to_byte(pixel.alpha));
+        }
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{
+        Colour,
+        Pixel,
+    };
+
+    #[test]
+    fn format_is_chosen_from_the_extension() {
+        assert_eq!(Format::from_path(Path::new("out.png")), Ok(Format::Png));
+        assert_eq!(Format::from_path(Path::new("renders/out.PPM")), Ok(Format::Ppm));
+        assert_eq!(Format::from_path(Path::new("out.hdr")), Ok(Format::Hdr));
+        assert!(Format::from_path(Path::new("out.txt")).is_err());
+        assert!(Format::from_path(Path::new("out")).is_err());
+    }
+
+    #[test]
+    fn colours_are_gamma_corrected_and_clamped() {
+        let mut framebuffer = Framebuffer::new(2, 1);
+        framebuffer.set(0, 0, Pixel{ alpha: 0.5, ..Pixel::opaque(Colour::new(0.25, 1.0, 15.0)) });
+        framebuffer.set(1, 0, Pixel::opaque(Colour::new(-1.0, 0.0, 0.0)));
+
+        assert_eq!(to_8_bit(&framebuffer, true), vec![127, 255, 255, 127, 0, 0, 0, 255]);
+        assert_eq!(to_8_bit(&framebuffer, false), vec![127, 255, 255, 0, 0, 0]);
+    }
+}
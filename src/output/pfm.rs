@@ -0,0 +1,49 @@
+// Portable float map, three little endian floats per pixel with rows from the bottom.
+
+use crate::{
+    render::{
+        Framebuffer,
+    },
+};
+use std::io::Write;
+
+pub fn write(mut output: impl Write, framebuffer: &Framebuffer) -> Result<(), String> {
+    let (width, height) = (framebuffer.width(), framebuffer.height());
+    // a negative scale marks little endian data
+    let mut bytes = format!("PF\n{} {}\n-1.0\n", width, height).into_bytes();
+    bytes.reserve(12 * width * height);
+    for row in framebuffer.pixels().chunks(width).rev() {
+        for pixel in row {
+            for c in &[pixel.colour.x, pixel.colour.y, pixel.colour.z] {
+                bytes.extend_from_slice(&c.to_le_bytes());
+            }
+        }
+    }
+    output.write_all(&bytes).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{
+        Colour,
+        Pixel,
+    };
+
+    #[test]
+    fn rows_are_written_bottom_to_top() {
+        let mut framebuffer = Framebuffer::new(1, 2);
+        framebuffer.set(0, 0, Pixel::opaque(Colour::new(15.0, 0.0, 0.0)));
+        framebuffer.set(0, 1, Pixel::opaque(Colour::new(0.5, 0.0, 0.0)));
+        let mut bytes = Vec::new();
+
+        write(&mut bytes, &framebuffer).unwrap();
+
+        let header = b"PF\n1 2\n-1.0\n";
+        assert_eq!(&bytes[..header.len()], header);
+        let data = &bytes[header.len()..];
+        assert_eq!(data.len(), 24);
+        assert_eq!(data[0..4], 0.5f32.to_le_bytes());
+        assert_eq!(data[12..16], 15.0f32.to_le_bytes());
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{
+    render::{
+        Framebuffer,
+    },
+    output,
+};
+use std::io::Write;
+
+// 8-bit RGB or RGBA
+pub fn write(output: impl Write, framebuffer: &Framebuffer, alpha: bool) -> Result<(), String> {
+    let mut encoder = ::png::Encoder::new(output, framebuffer.width() as u32, framebuffer.height() as u32);
+    encoder.set_color(if alpha { ::png::ColorType::RGBA } else { ::png::ColorType::RGB });
+    encoder.set_depth(::png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&output::to_8_bit(framebuffer, alpha)))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{
+        Colour,
+        Pixel,
+    };
+
+    #[test]
+    fn written_image_can_be_decoded() {
+        let mut framebuffer = Framebuffer::new(2, 1);
+        framebuffer.set(0, 0, Pixel{ alpha: 0.0, ..Pixel::opaque(Colour::new(1.0, 0.0, 0.0)) });
+        framebuffer.set(1, 0, Pixel::opaque(Colour::new(0.0, 0.0, 1.0)));
+        let mut bytes = Vec::new();
+
+        write(&mut bytes, &framebuffer, true).unwrap();
+
+        let (info, mut reader) = ::png::Decoder::new(bytes.as_slice()).read_info().unwrap();
+        let mut decoded = vec![0; info.buffer_size()];
+        reader.next_frame(&mut decoded).unwrap();
+        assert_eq!((info.width, info.height), (2, 1));
+        assert_eq!(info.color_type, ::png::ColorType::RGBA);
+        assert_eq!(decoded, vec![255, 0, 0, 0, 0, 0, 255, 255]);
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{
+    render::{
+        Framebuffer,
+    },
+    output,
+};
+use std::io::Write;
+
+pub fn write_binary(mut output: impl Write, framebuffer: &Framebuffer) -> Result<(), String> {
+    let mut bytes = format!("P6\n{} {}\n255\n", framebuffer.width(), framebuffer.height()).into_bytes();
+    bytes.extend(output::to_8_bit(framebuffer, false));
+    output.write_all(&bytes).map_err(|e| e.to_string())
+}
+
+pub fn write_ascii(mut output: impl Write, framebuffer: &Framebuffer) -> Result<(), String> {
+    let mut text = format!("P3\n{} {}\n255\n", framebuffer.width(), framebuffer.height());
+    for rgb in output::to_8_bit(framebuffer, false).chunks(3) {
+        text += &format!("{} {} {}\n", rgb[0], rgb[1], rgb[2]);
+    }
+    output.write_all(text.as_bytes()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{
+        Colour,
+        Pixel,
+    };
+
+    #[test]
+    fn binary_ppm_has_a_header_and_raw_bytes() {
+        let mut framebuffer = Framebuffer::new(1, 1);
+        framebuffer.set(0, 0, Pixel::opaque(Colour::new(1.0, 0.0, 1.0)));
+        let mut bytes = Vec::new();
+
+        write_binary(&mut bytes, &framebuffer).unwrap();
+
+        assert_eq!(bytes, b"P6\n1 1\n255\n\xff\x00\xff".to_vec());
+    }
+}
@@ -1,10 +1,126 @@
-use rand::Rng;
+use rand::{
+    RngCore,
+    distributions::uniform::SampleUniform,
+};
 
-pub fn random_float_from_0_to_1() -> f32 {
-    gen_range(0.0, 1.0)
+// A PCG32 generator. It's always seeded explicitly so that renders can be reproduced.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+    increment: u64,
 }
 
-pub fn gen_range<T>(from: T, to: T) -> T 
-    where T: rand::distributions::uniform::SampleUniform {
-    rand::thread_rng().gen_range(from, to)
-}
\ No newline at end of file
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self::with_stream(seed, 0)
+    }
+
+    // Generators with the same seed and different streams give independent sequences.
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut rng = Rng {
+            state: 0,
+            increment: (mix(stream) << 1) | 1,
+        };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(mix(seed ^ stream));
+        rng.next_u32();
+        rng
+    }
+
+    pub fn random_float_from_0_to_1(&mut self) -> f32 {
+        to_float(self.next_u32())
+    }
+
+    pub fn gen_range<T: SampleUniform>(&mut self, from: T, to: T) -> T {
+        rand::Rng::gen_range(self, from, to)
+    }
+}
+
+impl RngCore for Rng {
+    fn next_u32(&mut self) -> u32 {
+        let old_state = self.state;
+        self.state = old_state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(self.increment);
+        let xor_shifted = (((old_state >> 18) ^ old_state) >> 27) as u32;
+        let rotation = (old_state >> 59) as u32;
+        xor_shifted.rotate_right(rotation)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (u64::from(self.next_u32()) << 32) | u64::from(self.next_u32())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+// A well mixed 64 bit hash of the given values.
+pub fn hash(values: &[u64]) -> u64 {
+    values
+        .iter()
+        .fold(0x9e37_79b9_7f4a_7c15, |hash: u64, &v| mix(hash ^ v))
+}
+
+// A uniform float in [0, 1) derived from the given values, for code which has no generator at hand.
+pub fn hash_to_float(values: &[u64]) -> f32 {
+    to_float((hash(values) >> 32) as u32)
+}
+
+// the 24 high bits fill the mantissa exactly
+fn to_float(bits: u32) -> f32 {
+    (bits >> 8) as f32 / (1u32 << 24) as f32
+}
+
+// SplitMix64 finalizer
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_the_same_sequence() {
+        let mut a = Rng::with_stream(42, 7);
+        let mut b = Rng::with_stream(42, 7);
+
+        for _ in 0..100 {
+            assert_eq!(a.next_u32(), b.next_u32());
+        }
+    }
+
+    #[test]
+    fn different_streams_give_different_sequences() {
+        let mut a = Rng::with_stream(42, 1);
+        let mut b = Rng::with_stream(42, 2);
+
+        let a = (0..8).map(|_| a.next_u32()).collect::<Vec<_>>();
+        let b = (0..8).map(|_| b.next_u32()).collect::<Vec<_>>();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn floats_are_in_the_unit_interval() {
+        let mut rng = Rng::new(3);
+
+        for _ in 0..1000 {
+            let x = rng.random_float_from_0_to_1();
+            assert!((0.0..1.0).contains(&x));
+        }
+        assert!((0.0..1.0).contains(&hash_to_float(&[u64::MAX, 0])));
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{
+    render::{
+        self,
+        Colour,
+    },
+};
+
+// Settings of adaptive sampling, pixels keep taking samples until their estimated error is small enough.
+#[derive(Copy, Clone, Debug)]
+pub struct Adaptive {
+    // the largest error of a pixel's displayed value that is accepted
+    pub noise_threshold: f32,
+    pub max_samples: u32,
+}
+
+// Running mean and variance of the luminance of a pixel's samples (Welford's algorithm).
+pub struct PixelStatistics {
+    count: u32,
+    mean: f32,
+    m2: f32,
+}
+
+impl PixelStatistics {
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    pub fn add(&mut self, colour: &Colour) {
+        let value = render::luminance(colour);
+        if !value.is_finite() {
+            return;
+        }
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f32;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    // The estimated standard error of the pixel as displayed.
+    // Images are written with a gamma of 2, so an error e of the mean
+    // changes the displayed value by about e / (2 sqrt(mean)).
+    pub fn error(&self) -> f32 {
+        if self.count < 2 {
+            return f32::INFINITY;
+        }
+        let variance = self.m2 / (self.count - 1) as f32;
+        let standard_error = (variance / self.count as f32).sqrt();
+        standard_error / (2.0 * self.mean.max(1e-4).sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics_match_the_sample_mean_and_variance() {
+        let mut statistics = PixelStatistics::new();
+        for &value in &[1.0, 3.0, 5.0, 7.0] {
+            statistics.add(&Colour::new(value, value, value));
+        }
+
+        assert!((statistics.mean - 4.0).abs() < 1e-5);
+        // variance 20 / 3 over 4 samples
+        let expected = (20.0f32 / 3.0 / 4.0).sqrt() / (2.0 * 2.0);
+        assert!((statistics.error() - expected).abs() < 1e-5);
+    }
+
+    #[test]
+    fn constant_samples_have_no_error() {
+        let mut statistics = PixelStatistics::new();
+        statistics.add(&Colour::new(0.0, 0.0, 0.0));
+
+        assert_eq!(statistics.error(), f32::INFINITY);
+        statistics.add(&Colour::new(0.0, 0.0, 0.0));
+        assert_eq!(statistics.error(), 0.0);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    render::{
+        Colour,
+        Tile,
+    },
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pixel {
+    pub colour: Colour,
+    // the fraction of camera rays which hit the scene
+    pub alpha: f32,
+    // the number of samples averaged into the pixel
+    pub samples: u32,
+}
+
+impl Pixel {
+    pub fn opaque(colour: Colour) -> Self {
+        Self {
+            colour,
+            alpha: 1.0,
+            samples: 0,
+        }
+    }
+}
+
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<Pixel>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Pixel::opaque(Colour::new(0.0, 0.0, 0.0)); width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // rows are stored top to bottom, as they appear in the image
+    #[cfg(test)]
+    pub fn get(&self, x: usize, row: usize) -> Pixel {
+        self.pixels[row * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, row: usize, pixel: Pixel) {
+        self.pixels[row * self.width + x] = pixel;
+    }
+
+    // all pixels, row by row from the top
+    pub fn pixels(&self) -> &[Pixel] {
+        &self.pixels
+    }
+
+    // `pixels` holds the tile's pixels row by row
+    pub fn write_tile(&mut self, tile: &Tile, pixels: &[Pixel]) {
+        for (i, pixel) in pixels.iter().enumerate() {
+            let x = tile.x + i % tile.width;
+            let row = tile.row + i / tile.width;
+            self.set(x, row, *pixel);
+        }
+    }
+
+    // An image of the number of samples each pixel took, going from blue for none
+    // through green to red for `max_samples`.
+    pub fn sample_heatmap(&self, max_samples: u32) -> Framebuffer {
+        let blue = Colour::new(0.0, 0.0, 1.0);
+        let green = Colour::new(0.0, 1.0, 0.0);
+        let red = Colour::new(1.0, 0.0, 0.0);
+        let pixels = self.pixels
+            .iter()
+            .map(|pixel| {
+                let t = (pixel.samples as f32 / max_samples.max(1) as f32).min(1.0);
+                let colour = if t < 0.5 {
+                    blue + (green - blue) * (2.0 * t)
+                }
+                else {
+                    green + (red - green) * (2.0 * t - 1.0)
+                };
+                Pixel::opaque(colour)
+            })
+            .collect();
+        Framebuffer {
+            width: self.width,
+            height: self.height,
+            pixels,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_tile_places_pixels_inside_the_tile() {
+        let mut framebuffer = Framebuffer::new(4, 3);
+        let tile = Tile{ x: 1, row: 1, width: 2, height: 2 };
+        let pixels = [
+            Colour::new(1.0, 0.0, 0.0), Colour::new(2.0, 0.0, 0.0),
+            Colour::new(3.0, 0.0, 0.0), Colour::new(4.0, 0.0, 0.0),
+        ].iter().map(|c| Pixel::opaque(*c)).collect::<Vec<_>>();
+
+        framebuffer.write_tile(&tile, &pixels);
+
+        assert_eq!(framebuffer.get(0, 0).colour, Colour::new(0.0, 0.0, 0.0));
+        assert_eq!(framebuffer.get(1, 1).colour, Colour::new(1.0, 0.0, 0.0));
+        assert_eq!(framebuffer.get(2, 1).colour, Colour::new(2.0, 0.0, 0.0));
+        assert_eq!(framebuffer.get(1, 2).colour, Colour::new(3.0, 0.0, 0.0));
+        assert_eq!(framebuffer.get(2, 2).colour, Colour::new(4.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn heatmap_goes_from_blue_to_red() {
+        let mut framebuffer = Framebuffer::new(3, 1);
+        for (x, &samples) in [0, 8, 16].iter().enumerate() {
+            framebuffer.set(x, 0, Pixel{ samples, ..Pixel::opaque(Colour::new(0.0, 0.0, 0.0)) });
+        }
+
+        let heatmap = framebuffer.sample_heatmap(16);
+
+        assert_eq!(heatmap.get(0, 0).colour, Colour::new(0.0, 0.0, 1.0));
+        assert_eq!(heatmap.get(1, 0).colour, Colour::new(0.0, 1.0, 0.0));
+        assert_eq!(heatmap.get(2, 0).colour, Colour::new(1.0, 0.0, 0.0));
+    }
+}
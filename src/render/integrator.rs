@@ -0,0 +1,239 @@
+use crate::{
+    math::{
+        ElementWise,
+        InnerSpace,
+        Point3,
+        Vec3,
+        Interval,
+    },
+    core::{
+        Ray,
+        Hittable,
+        HitRecord,
+    },
+    render::{
+        Colour,
+    },
+    materials::{
+        Lobe,
+    },
+    scene::{
+        Scene,
+    },
+    sampling::{
+        Sampler,
+    },
+};
+
+// Paths are terminated at random after this many bounces, with a chance depending on their throughput.
+const MIN_ROULETTE_DEPTH: u32 = 3;
+
+// The most bounces of each kind a path can take.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MaxDepths {
+    pub diffuse: u32,
+    // glossy and mirror reflections
+    pub glossy: u32,
+    pub transmission: u32,
+    pub volume: u32,
+}
+
+impl Default for MaxDepths {
+    fn default() -> Self {
+        Self {
+            diffuse: 16,
+            glossy: 16,
+            transmission: 32,
+            volume: 64,
+        }
+    }
+}
+
+// The bounces of each kind a path has taken so far.
+#[derive(Default)]
+struct Bounces {
+    diffuse: u32,
+    glossy: u32,
+    transmission: u32,
+    volume: u32,
+}
+
+impl Bounces {
+    // Counts a bounce of the given lobe, false if that exceeds its maximum depth.
+    fn add(&mut self, lobe: Lobe, max_depths: &MaxDepths) -> bool {
+        let (count, max) = match lobe {
+            Lobe::Diffuse => (&mut self.diffuse, max_depths.diffuse),
+            Lobe::Delta => (&mut self.glossy, max_depths.glossy),
+            Lobe::Transmission => (&mut self.transmission, max_depths.transmission),
+            Lobe::Volume => (&mut self.volume, max_depths.volume),
+        };
+        *count += 1;
+        *count <= max
+    }
+}
+
+// The colour seen along the camera ray and whether the ray hit anything, which gives the pixel's coverage.
+pub fn to_colour(ray: &Ray, scene: &Scene, max_depths: &MaxDepths, sampler: &mut dyn Sampler) -> (Colour, bool) {
+    let mut ray = *ray;
+    let mut colour = Colour::new(0.0, 0.0, 0.0);
+    let mut throughput = Colour::new(1.0, 1.0, 1.0);
+    // the density with which the previous bounce picked the direction of `ray`, None after specular bounces.
+    // Light found this way is weighted against the chance of finding it by light sampling.
+    let mut scattering_pdf = None;
+    let mut bounces = Bounces::default();
+    let mut depth = 0;
+    loop {
+        ray = ray.with_medium_sample(sampler.get_1d());
+        let hit_record = match scene.hit(&ray, &hit_interval()) {
+            Some(hit_record) => hit_record,
+            None => {
+                let mut background = scene.background().value(&ray.direction);
+                // environment maps are lights which could have been sampled directly
+                if let Some(pdf) = scattering_pdf {
+                    background *= power_heuristic(pdf, lights_pdf(scene, &ray.origin, &ray.direction));
+                }
+                colour += throughput.mul_element_wise(background);
+                // only the camera ray misses before the first bounce
+                return (colour, depth > 0);
+            },
+        };
+
+        let mut emitted = hit_record.material.emitted(&hit_record.uv, &hit_record.hit_point);
+        if let Some(pdf) = scattering_pdf {
+            emitted *= power_heuristic(pdf, lights_pdf(scene, &ray.origin, &ray.direction));
+        }
+        colour += throughput.mul_element_wise(emitted);
+
+        let wo = -ray.direction.normalize();
+        // every bounce draws the same dimensions, whichever of them it ends up using
+        let light_samples = (sampler.get_1d(), sampler.get_2d(), sampler.get_1d());
+        let (u, u2) = (sampler.get_1d(), sampler.get_2d());
+        let roulette = sampler.get_1d();
+        let sample = match hit_record.material.sample(&hit_record, &wo, u, u2) {
+            Some(sample) if bounces.add(sample.lobe, max_depths) => sample,
+            _ => break,
+        };
+        if sample.lobe.is_specular() {
+            scattering_pdf = None;
+        }
+        else {
+            let direct = sample_lights(&ray, &hit_record, &wo, scene, light_samples);
+            colour += throughput.mul_element_wise(direct);
+            scattering_pdf = Some(sample.pdf);
+        }
+        throughput = throughput.mul_element_wise(sample.value / sample.pdf);
+
+        depth += 1;
+        if depth >= MIN_ROULETTE_DEPTH {
+            throughput = match russian_roulette(throughput, roulette) {
+                Some(throughput) => throughput,
+                None => break,
+            };
+        }
+        ray = Ray::new(hit_record.hit_point, sample.direction, ray.time);
+    }
+    (colour, true)
+}
+
+// Ends paths with a chance that grows as their throughput drops, the survivors are weighted up
+// by the chance of surviving so that the expected throughput stays the same.
+fn russian_roulette(throughput: Colour, u: f32) -> Option<Colour> {
+    let survival = throughput.x.max(throughput.y).max(throughput.z).min(1.0);
+    if u >= survival {
+        return None;
+    }
+    Some(throughput / survival)
+}
+
+fn hit_interval() -> Interval<f32> {
+    Interval::new(0.001, f32::MAX).unwrap()
+}
+
+// Estimates the light arriving directly from a randomly chosen light with a shadow ray.
+// The first sample picks the light, the second a direction towards it and the third is the medium sample of the shadow ray.
+fn sample_lights(ray: &Ray, hit_record: &HitRecord, wo: &Vec3, scene: &Scene, (u, u2, medium_sample): (f32, (f32, f32), f32)) -> Colour {
+    let black = Colour::new(0.0, 0.0, 0.0);
+    let lights = scene.lights();
+    if lights.is_empty() {
+        return black;
+    }
+
+    let index = ((u * lights.len() as f32) as usize).min(lights.len() - 1);
+    let direction = lights[index].random(&hit_record.hit_point, u2).normalize();
+    let light_pdf = lights_pdf(scene, &hit_record.hit_point, &direction);
+    let scattering_pdf = hit_record.material.pdf(hit_record, wo, &direction);
+    if light_pdf <= 0.0 || scattering_pdf <= 0.0 {
+        return black;
+    }
+
+    let shadow_ray = Ray::new(hit_record.hit_point, direction, ray.time).with_medium_sample(medium_sample);
+    // shadow rays which escape the scene see the background, which may be an environment map light
+    let emitted = match scene.hit(&shadow_ray, &hit_interval()) {
+        Some(light_hit) => light_hit.material.emitted(&light_hit.uv, &light_hit.hit_point),
+        None => scene.background().value(&direction),
+    };
+    let bsdf = hit_record.material.eval(hit_record, wo, &direction);
+    let weight = power_heuristic(light_pdf, scattering_pdf) / light_pdf;
+    bsdf.mul_element_wise(emitted) * weight
+}
+
+// The density of sampling `direction` by picking a uniformly random light and sampling it.
+fn lights_pdf(scene: &Scene, origin: &Point3, direction: &Vec3) -> f32 {
+    let lights = scene.lights();
+    if lights.is_empty() {
+        return 0.0;
+    }
+    let sum = lights
+        .iter()
+        .map(|light| light.pdf_value(origin, direction))
+        .sum::<f32>();
+    sum / lights.len() as f32
+}
+
+// Multiple importance sampling weight of a sample with density `pdf` against a second strategy.
+fn power_heuristic(pdf: f32, other_pdf: f32) -> f32 {
+    let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+    if a + b > 0.0 { a / (a + b) } else { 1.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn power_heuristic_weights_sum_to_one() {
+        let (a, b) = (0.3, 1.7);
+
+        assert!((power_heuristic(a, b) + power_heuristic(b, a) - 1.0).abs() < 1e-6);
+        assert_eq!(power_heuristic(1.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn bounces_are_limited_per_lobe() {
+        let max_depths = MaxDepths{ diffuse: 1, glossy: 2, transmission: 0, volume: 1 };
+        let mut bounces = Bounces::default();
+
+        assert!(bounces.add(Lobe::Diffuse, &max_depths));
+        assert!(!bounces.add(Lobe::Diffuse, &max_depths));
+        assert!(bounces.add(Lobe::Delta, &max_depths));
+        assert!(bounces.add(Lobe::Delta, &max_depths));
+        assert!(!bounces.add(Lobe::Delta, &max_depths));
+        assert!(!bounces.add(Lobe::Transmission, &max_depths));
+        assert!(bounces.add(Lobe::Volume, &max_depths));
+    }
+
+    #[test]
+    fn russian_roulette_keeps_the_expected_throughput() {
+        let count = 1000;
+        for &throughput in &[Colour::new(0.3, 0.1, 0.05), Colour::new(0.8, 0.8, 0.8), Colour::new(2.0, 0.5, 0.0)] {
+            let sum = (0..count)
+                .filter_map(|i| russian_roulette(throughput, (i as f32 + 0.5) / count as f32))
+                .fold(Colour::new(0.0, 0.0, 0.0), |sum, t| sum + t);
+            let mean = sum / count as f32;
+
+            assert!((mean - throughput).magnitude() < 1e-3, "{:?} != {:?}", mean, throughput);
+        }
+        // paths without throughput are always ended
+        assert!(russian_roulette(Colour::new(0.0, 0.0, 0.0), 0.0).is_none());
+    }
+}
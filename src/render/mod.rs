@@ -0,0 +1,249 @@
+mod framebuffer;
+mod tile;
+mod integrator;
+mod adaptive;
+
+pub use framebuffer::{
+    Framebuffer,
+    Pixel,
+};
+pub use tile::Tile;
+pub use adaptive::Adaptive;
+pub use integrator::MaxDepths;
+
+use adaptive::PixelStatistics;
+
+use crate::{
+    math::{
+        Vec3,
+        Interval,
+    },
+    scene::{
+        Scene,
+    },
+    camera::{
+        Camera,
+    },
+    sampling::{
+        SamplerKind,
+    },
+};
+use std::{
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+    thread,
+};
+
+pub type Colour = Vec3;
+
+// the Rec. 709 luminance of a linear colour
+pub fn luminance(colour: &Colour) -> f32 {
+    0.2126 * colour.x + 0.7152 * colour.y + 0.0722 * colour.z
+}
+
+const TILE_SIZE: usize = 32;
+
+pub struct Settings {
+    pub width: usize,
+    pub height: usize,
+    pub pixel_samples: u32,
+    pub threads: usize,
+    pub time_interval: Interval<f32>,
+    // whether to compute the coverage of each pixel, otherwise pixels are opaque
+    pub alpha: bool,
+    pub seed: u64,
+    pub sampler: SamplerKind,
+    // None if every pixel takes `pixel_samples` samples, otherwise that's the minimum
+    pub adaptive: Option<Adaptive>,
+    pub max_depths: MaxDepths,
+}
+
+impl Settings {
+    // the most samples a pixel can take
+    pub fn max_samples(&self) -> u32 {
+        self.adaptive.map_or(self.pixel_samples, |a| a.max_samples.max(self.pixel_samples))
+    }
+
+    // The sampler the pixels use. Stratified samples are only spread well over all of the strata,
+    // so pixels which stop early would miss some of them and adaptive sampling uses Sobol instead.
+    pub fn sampler(&self) -> SamplerKind {
+        match (self.sampler, self.adaptive) {
+            (SamplerKind::Stratified, Some(_)) => SamplerKind::Sobol,
+            (sampler, _) => sampler,
+        }
+    }
+}
+
+// Renders the scene into a framebuffer of averaged, linear colours.
+// The image is split into tiles which are handed out to `settings.threads` workers,
+// each pixel is computed independently of the tile and the thread that renders it,
+// with its own sampler derived from the seed and its position.
+pub fn render(scene: &Scene, camera: &Camera, settings: &Settings) -> Framebuffer {
+    let tiles = tile::split_into_tiles(settings.width, settings.height, TILE_SIZE);
+    let next_tile = AtomicUsize::new(0);
+    let worker = || {
+        let mut rendered = Vec::new();
+        loop {
+            let index = next_tile.fetch_add(1, Ordering::Relaxed);
+            match tiles.get(index) {
+                Some(tile) => rendered.push((*tile, render_tile(tile, scene, camera, settings))),
+                None => return rendered,
+            }
+        }
+    };
+
+    let mut framebuffer = Framebuffer::new(settings.width, settings.height);
+    thread::scope(|s| {
+        let workers = (0..settings.threads.max(1))
+            .map(|_| s.spawn(worker))
+            .collect::<Vec<_>>();
+        for w in workers {
+            for (tile, pixels) in w.join().expect("render worker panicked") {
+                framebuffer.write_tile(&tile, &pixels);
+            }
+        }
+    });
+
+    framebuffer
+}
+
+fn render_tile(tile: &Tile, scene: &Scene, camera: &Camera, settings: &Settings) -> Vec<Pixel> {
+    let mut pixels = Vec::with_capacity(tile.pixel_count());
+    for row in tile.row..tile.row + tile.height {
+        for x in tile.x..tile.x + tile.width {
+            pixels.push(render_pixel(x, row, scene, camera, settings));
+        }
+    }
+    pixels
+}
+
+fn render_pixel(x: usize, row: usize, scene: &Scene, camera: &Camera, settings: &Settings) -> Pixel {
+    let Settings{ width, height, pixel_samples, time_interval, .. } = settings;
+    let (tmin, tmax) = (time_interval.min(), time_interval.max());
+    let y = height - 1 - row;
+    let max_samples = settings.max_samples();
+    let mut sampler = settings.sampler().for_pixel(x, row, max_samples, settings.seed);
+
+    let mut colour = Colour::new(0.0, 0.0, 0.0);
+    let mut statistics = PixelStatistics::new();
+    let mut hits = 0;
+    let mut samples = 0;
+    while samples < max_samples {
+        sampler.start_sample(samples);
+        let (dx, dy) = sampler.get_2d();
+        let u = (x as f32 + dx) / *width as f32;
+        let v = (y as f32 + dy) / *height as f32;
+        let lens = sampler.get_2d();
+        let time = tmin + sampler.get_1d()*(tmax - tmin);
+        let ray = camera.make_ray((u, v), time, lens);
+        let (sample, hit) = integrator::to_colour(&ray, scene, &settings.max_depths, sampler.as_mut());
+        colour += sample;
+        statistics.add(&sample);
+        if hit {
+            hits += 1;
+        }
+        samples += 1;
+        if let Some(adaptive) = settings.adaptive {
+            if samples >= *pixel_samples && statistics.error() < adaptive.noise_threshold {
+                break;
+            }
+        }
+    }
+    Pixel {
+        colour: colour / samples as f32,
+        alpha: if settings.alpha { hits as f32 / samples as f32 } else { 1.0 },
+        samples,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        example_scenes::cornell_box,
+        scene_file,
+        bvh,
+    };
+
+    fn render_cornell_box(threads: usize, seed: u64, sampler: SamplerKind) -> Vec<Pixel> {
+        render_cornell_box_with(threads, seed, sampler, None)
+    }
+
+    fn render_cornell_box_with(threads: usize, seed: u64, sampler: SamplerKind, adaptive: Option<Adaptive>) -> Vec<Pixel> {
+        let time_interval = Interval::new(0.0, 1.0).unwrap();
+        let (scene, camera) = cornell_box::scene(1.0, &time_interval, &bvh::Options::default());
+        let settings = Settings {
+            width: 40,
+            height: 40,
+            pixel_samples: 2,
+            threads,
+            time_interval,
+            alpha: false,
+            seed,
+            sampler,
+            adaptive,
+            max_depths: MaxDepths::default(),
+        };
+        render(&scene, &camera, &settings).pixels().to_vec()
+    }
+
+    #[test]
+    fn same_seed_gives_identical_images_for_any_thread_count() {
+        for &sampler in &[SamplerKind::Independent, SamplerKind::Stratified, SamplerKind::Halton, SamplerKind::Sobol] {
+            let image = render_cornell_box(1, 7, sampler);
+
+            assert!(image == render_cornell_box(1, 7, sampler));
+            assert!(image == render_cornell_box(3, 7, sampler));
+            assert!(image != render_cornell_box(1, 8, sampler));
+        }
+    }
+
+    #[test]
+    fn adaptive_sampling_stops_early_in_smooth_pixels() {
+        let adaptive = Adaptive{ noise_threshold: 0.05, max_samples: 16 };
+        let image = render_cornell_box_with(1, 7, SamplerKind::Sobol, Some(adaptive));
+
+        assert!(image.iter().all(|p| p.samples >= 2 && p.samples <= 16));
+        assert!(image.iter().any(|p| p.samples < 16));
+        assert!(image.iter().any(|p| p.samples == 16));
+        assert!(image == render_cornell_box_with(3, 7, SamplerKind::Sobol, Some(adaptive)));
+    }
+
+    #[test]
+    fn adaptive_sampling_replaces_the_stratified_sampler() {
+        let adaptive = Adaptive{ noise_threshold: 0.05, max_samples: 16 };
+        let image = render_cornell_box_with(1, 7, SamplerKind::Stratified, Some(adaptive));
+
+        assert!(image == render_cornell_box_with(1, 7, SamplerKind::Sobol, Some(adaptive)));
+        assert!(render_cornell_box(1, 7, SamplerKind::Stratified) != render_cornell_box(1, 7, SamplerKind::Sobol));
+    }
+
+    #[test]
+    fn alpha_is_the_coverage_of_the_camera_rays() {
+        let time_interval = Interval::new(0.0, 1.0).unwrap();
+        let source = "
+            camera { look_from = [0, 0, -10], look_at = [0, 0, 0], fov = 20 }
+            object sphere { center = [0, 0, 0], radius = 1, material = dielectric { refractive_index = 1.5 } }
+        ";
+        let (scene, camera, max_depths) = scene_file::from_str(source, 1.0, &time_interval, &bvh::Options::default()).unwrap();
+        let settings = Settings {
+            width: 9,
+            height: 9,
+            pixel_samples: 4,
+            threads: 1,
+            time_interval,
+            alpha: true,
+            seed: 0,
+            sampler: SamplerKind::Stratified,
+            adaptive: None,
+            max_depths,
+        };
+        let framebuffer = render(&scene, &camera, &settings);
+
+        // rays refracted out of the glass sphere miss everything but still count as covered
+        assert_eq!(framebuffer.pixels()[4 * 9 + 4].alpha, 1.0);
+        assert_eq!(framebuffer.pixels()[0].alpha, 0.0);
+    }
+}
@@ -0,0 +1,51 @@
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tile {
+    pub x: usize,
+    pub row: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Tile {
+    pub fn pixel_count(&self) -> usize {
+        self.width * self.height
+    }
+}
+
+// splits a width x height image into tiles of at most tile_size x tile_size pixels,
+// ordered row by row from the top left corner
+pub fn split_into_tiles(width: usize, height: usize, tile_size: usize) -> Vec<Tile> {
+    let mut tiles = Vec::new();
+    for row in (0..height).step_by(tile_size) {
+        for x in (0..width).step_by(tile_size) {
+            tiles.push(Tile {
+                x,
+                row,
+                width: usize::min(tile_size, width - x),
+                height: usize::min(tile_size, height - row),
+            });
+        }
+    }
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiles_cover_the_whole_image() {
+        let tiles = split_into_tiles(70, 33, 32);
+
+        assert_eq!(tiles.len(), 6);
+        assert_eq!(tiles.iter().map(|t| t.pixel_count()).sum::<usize>(), 70 * 33);
+    }
+
+    #[test]
+    fn border_tiles_are_clipped_to_the_image() {
+        let tiles = split_into_tiles(70, 33, 32);
+
+        assert_eq!(tiles[2], Tile{ x: 64, row: 0, width: 6, height: 32 });
+        assert_eq!(tiles[5], Tile{ x: 64, row: 32, width: 6, height: 1 });
+    }
+}
@@ -0,0 +1,132 @@
+// Piecewise constant distributions, sampled by inverting their cumulative distribution.
+
+// A distribution over [0, 1) proportional to a function with `function.len()` equally wide pieces.
+// Functions which are zero everywhere give the uniform distribution.
+pub struct Distribution1D {
+    function: Vec<f32>,
+    cdf: Vec<f32>,
+    integral: f32,
+}
+
+impl Distribution1D {
+    pub fn new(function: Vec<f32>) -> Self {
+        assert!(!function.is_empty(), "a distribution needs at least one piece");
+        let n = function.len();
+        let mut cdf = vec![0.0; n + 1];
+        for i in 0..n {
+            cdf[i + 1] = cdf[i] + function[i].max(0.0) / n as f32;
+        }
+        let integral = cdf[n];
+        for (i, c) in cdf.iter_mut().enumerate() {
+            *c = if integral > 0.0 { *c / integral } else { i as f32 / n as f32 };
+        }
+        Self {
+            function,
+            cdf,
+            integral,
+        }
+    }
+
+    pub fn integral(&self) -> f32 {
+        self.integral
+    }
+
+    // A point with its density and the index of its piece, `u` is a uniform sample.
+    pub fn sample(&self, u: f32) -> (f32, f32, usize) {
+        let n = self.function.len();
+        let index = self.cdf
+            .partition_point(|&c| c <= u)
+            .saturating_sub(1)
+            .min(n - 1);
+        let width = self.cdf[index + 1] - self.cdf[index];
+        let offset = if width > 0.0 { (u - self.cdf[index]) / width } else { 0.0 };
+        let x = ((index as f32 + offset.clamp(0.0, 1.0)) / n as f32).min(super::ONE_MINUS_EPSILON);
+        (x, self.piece_pdf(index), index)
+    }
+
+    pub fn pdf(&self, x: f32) -> f32 {
+        let n = self.function.len();
+        self.piece_pdf(((x * n as f32).max(0.0) as usize).min(n - 1))
+    }
+
+    fn piece_pdf(&self, index: usize) -> f32 {
+        if self.integral > 0.0 { self.function[index].max(0.0) / self.integral } else { 1.0 }
+    }
+}
+
+// A distribution over [0, 1)^2 proportional to a function given row by row.
+// The row (the second coordinate) is picked first, then the column within it.
+pub struct Distribution2D {
+    conditional: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    pub fn new(function: &[f32], width: usize, height: usize) -> Self {
+        assert_eq!(function.len(), width * height, "the function doesn't have width * height values");
+        let conditional = function
+            .chunks(width)
+            .map(|row| Distribution1D::new(row.to_vec()))
+            .collect::<Vec<_>>();
+        let marginal = Distribution1D::new(conditional.iter().map(|d| d.integral()).collect());
+        Self {
+            conditional,
+            marginal,
+        }
+    }
+
+    // A point and its density, `u` is a uniform sample.
+    pub fn sample(&self, u: (f32, f32)) -> ((f32, f32), f32) {
+        let (v, v_pdf, row) = self.marginal.sample(u.1);
+        let (u, u_pdf, _) = self.conditional[row].sample(u.0);
+        ((u, v), u_pdf * v_pdf)
+    }
+
+    pub fn pdf(&self, (u, v): (f32, f32)) -> f32 {
+        let rows = self.conditional.len();
+        let row = ((v * rows as f32).max(0.0) as usize).min(rows - 1);
+        self.conditional[row].pdf(u) * self.marginal.pdf(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pieces_are_picked_in_proportion_to_the_function() {
+        let distribution = Distribution1D::new(vec![1.0, 0.0, 3.0]);
+
+        let (x, pdf, index) = distribution.sample(0.1);
+        assert_eq!(index, 0);
+        assert!((pdf - 0.75).abs() < 1e-6);
+        assert!((x - 0.4 / 3.0).abs() < 1e-6);
+        assert_eq!(distribution.sample(0.5).2, 2);
+        assert_eq!(distribution.pdf(0.5), 0.0);
+        assert!((distribution.pdf(0.9) - 2.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn zero_function_gives_the_uniform_distribution() {
+        let distribution = Distribution1D::new(vec![0.0, 0.0]);
+
+        assert_eq!(distribution.sample(0.75), (0.75, 1.0, 1));
+    }
+
+    #[test]
+    fn sampled_points_have_the_density_of_pdf() {
+        let function = [0.0, 1.0, 2.0, 5.0, 1.0, 0.5];
+        let distribution = Distribution2D::new(&function, 3, 2);
+
+        for i in 0..10 {
+            for j in 0..10 {
+                let u = (i as f32 / 10.0 + 0.05, j as f32 / 10.0 + 0.05);
+                let (point, pdf) = distribution.sample(u);
+                assert!(pdf > 0.0);
+                assert!((distribution.pdf(point) - pdf).abs() < 1e-5);
+            }
+        }
+        // the function integrates to 9.5 / 6
+        assert!((distribution.pdf((0.5, 0.25)) - 6.0 / 9.5).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{
+    sampling::{
+        self,
+        Sampler,
+    },
+    random,
+};
+
+const PRIMES: [u32; 32] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53,
+    59, 61, 67, 71, 73, 79, 83, 89, 97, 101, 103, 107, 109, 113, 127, 131,
+];
+
+// The Halton sequence with a prime base per dimension.
+// Each pixel shifts every dimension by a random offset (Cranley-Patterson rotation)
+// so neighbouring pixels don't repeat the same pattern.
+// Dimensions past the prime table use independent random values.
+pub struct Halton {
+    seed: u64,
+    index: u32,
+    dimension: usize,
+}
+
+impl Halton {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            index: 0,
+            dimension: 0,
+        }
+    }
+}
+
+impl Sampler for Halton {
+    fn start_sample(&mut self, index: u32) {
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let dimension = self.dimension;
+        self.dimension += 1;
+        let offset = random::hash_to_float(&[self.seed, dimension as u64]);
+        match PRIMES.get(dimension) {
+            Some(&base) => {
+                let value = radical_inverse(self.index, base) + offset;
+                (value - value.floor()).min(sampling::ONE_MINUS_EPSILON)
+            },
+            None => random::hash_to_float(&[self.seed, dimension as u64, self.index as u64]),
+        }
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        (self.get_1d(), self.get_1d())
+    }
+}
+
+// Mirrors the digits of `index` in the given base around the radix point.
+fn radical_inverse(mut index: u32, base: u32) -> f32 {
+    let inverse_base = 1.0 / base as f64;
+    let mut reversed = 0u64;
+    let mut inverse_base_power = 1.0;
+    while index > 0 {
+        let next = index / base;
+        let digit = index - next * base;
+        reversed = reversed * base as u64 + digit as u64;
+        inverse_base_power *= inverse_base;
+        index = next;
+    }
+    ((reversed as f64 * inverse_base_power) as f32).min(sampling::ONE_MINUS_EPSILON)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radical_inverse_mirrors_the_digits() {
+        assert_eq!(radical_inverse(0, 2), 0.0);
+        assert_eq!(radical_inverse(1, 2), 0.5);
+        assert_eq!(radical_inverse(6, 2), 0.375);
+        assert!((radical_inverse(5, 3) - 7.0 / 9.0).abs() < 1e-6);
+    }
+}
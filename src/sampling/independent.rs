@@ -0,0 +1,33 @@
+use crate::{
+    sampling::{
+        Sampler,
+    },
+    random::{
+        Rng,
+    },
+};
+
+// Uniform random values, each drawn independently of the others.
+pub struct Independent {
+    rng: Rng,
+}
+
+impl Independent {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Sampler for Independent {
+    fn start_sample(&mut self, _: u32) {}
+
+    fn get_1d(&mut self) -> f32 {
+        self.rng.random_float_from_0_to_1()
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        (self.rng.random_float_from_0_to_1(), self.rng.random_float_from_0_to_1())
+    }
+}
@@ -0,0 +1,147 @@
+//! Generators of the sample values a camera ray and its path consume.
+//!
+//! Every value is one dimension of the pixel sample. The renderer draws the dimensions in a
+//! fixed order (pixel position, lens, time, then the same pattern at every bounce),
+//! so samplers which distribute each dimension well over a pixel's samples reduce noise.
+
+mod independent;
+mod stratified;
+mod halton;
+mod sobol;
+mod warp;
+mod distribution;
+
+pub use independent::Independent;
+pub use stratified::Stratified;
+pub use halton::Halton;
+pub use sobol::Sobol;
+pub use distribution::Distribution2D;
+pub use warp::{
+    concentric_disk,
+    cosine_hemisphere,
+    uniform_sphere,
+    uniform_ball,
+};
+
+use crate::random;
+
+pub trait Sampler {
+    // Starts the sample with the given index within the pixel, dimensions start over from zero.
+    fn start_sample(&mut self, index: u32);
+
+    fn get_1d(&mut self) -> f32;
+
+    fn get_2d(&mut self) -> (f32, f32);
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SamplerKind {
+    Independent,
+    Stratified,
+    Halton,
+    Sobol,
+}
+
+impl SamplerKind {
+    // A sampler for the pixel at (x, row) which is going to take up to `samples_per_pixel` samples.
+    pub fn for_pixel(self, x: usize, row: usize, samples_per_pixel: u32, seed: u64) -> Box<dyn Sampler> {
+        let pixel_seed = random::hash(&[seed, x as u64, row as u64]);
+        match self {
+            SamplerKind::Independent => Box::new(Independent::new(pixel_seed)),
+            SamplerKind::Stratified => Box::new(Stratified::new(samples_per_pixel, pixel_seed)),
+            SamplerKind::Halton => Box::new(Halton::new(pixel_seed)),
+            SamplerKind::Sobol => Box::new(Sobol::new(pixel_seed)),
+        }
+    }
+}
+
+// The largest float below one.
+const ONE_MINUS_EPSILON: f32 = 1.0 - f32::EPSILON / 2.0;
+
+// Converts the bits of a fixed point number in [0, 1) to a float.
+fn bits_to_float(bits: u32) -> f32 {
+    (bits as f32 * (1.0 / 4_294_967_296.0)).min(ONE_MINUS_EPSILON)
+}
+
+// The element at `index` of a random permutation of 0..length picked by `seed`,
+// computed without storing the permutation (Kensler, "Correlated Multi-Jittered Sampling").
+fn permutation_element(index: u32, length: u32, seed: u32) -> u32 {
+    let mut w = length.max(1) - 1;
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+    let mut i = index;
+    loop {
+        i ^= seed;
+        i = i.wrapping_mul(0xe170_893d);
+        i ^= seed >> 16;
+        i ^= (i & w) >> 4;
+        i ^= seed >> 8;
+        i = i.wrapping_mul(0x0929_eb3f);
+        i ^= seed >> 23;
+        i ^= (i & w) >> 1;
+        i = i.wrapping_mul(1 | seed >> 27);
+        i = i.wrapping_mul(0x6935_fa69);
+        i ^= (i & w) >> 11;
+        i = i.wrapping_mul(0x74dc_b303);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0x9e50_1cc3);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0xc860_a3df);
+        i &= w;
+        i ^= i >> 5;
+        if i < length.max(1) {
+            break;
+        }
+    }
+    i.wrapping_add(seed) % length.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permutation_element_is_a_permutation() {
+        for &length in &[1, 7, 16, 100] {
+            let mut elements = (0..length)
+                .map(|i| permutation_element(i, length, 12345))
+                .collect::<Vec<_>>();
+            elements.sort_unstable();
+
+            assert_eq!(elements, (0..length).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn every_sampler_gives_values_in_the_unit_interval() {
+        let kinds = [SamplerKind::Independent, SamplerKind::Stratified, SamplerKind::Halton, SamplerKind::Sobol];
+        for &kind in &kinds {
+            let mut sampler = kind.for_pixel(3, 5, 16, 0);
+            for index in 0..16 {
+                sampler.start_sample(index);
+                for _ in 0..40 {
+                    let x = sampler.get_1d();
+                    let (y, z) = sampler.get_2d();
+                    assert!([x, y, z].iter().all(|v| (0.0..1.0).contains(v)), "{:?}", kind);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn samplers_are_deterministic() {
+        let kinds = [SamplerKind::Independent, SamplerKind::Stratified, SamplerKind::Halton, SamplerKind::Sobol];
+        for &kind in &kinds {
+            let mut a = kind.for_pixel(1, 2, 8, 42);
+            let mut b = kind.for_pixel(1, 2, 8, 42);
+            a.start_sample(3);
+            b.start_sample(3);
+
+            assert_eq!(a.get_2d(), b.get_2d());
+            assert_eq!(a.get_1d(), b.get_1d());
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{
+    sampling::{
+        self,
+        Sampler,
+    },
+    random,
+};
+
+// The first two dimensions of the Sobol sequence, used in pairs for every dimension of a pixel.
+// Each dimension shuffles the sample indices and Owen-scrambles the values with its own seed,
+// which keeps the stratification of the sequence while decorrelating the dimensions.
+// The shuffle maps every aligned block of 2^k indices to another one, so any
+// power of two prefix of the samples is still well stratified (Burley, "Practical Hash-based Owen Scrambling").
+pub struct Sobol {
+    seed: u64,
+    index: u32,
+    dimension: u64,
+}
+
+impl Sobol {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            index: 0,
+            dimension: 0,
+        }
+    }
+
+    // the shuffled index and the scrambling seeds of the next dimension
+    fn next_dimension(&mut self) -> (u32, u64) {
+        let hash = random::hash(&[self.seed, self.dimension]);
+        self.dimension += 1;
+        (owen_scramble(self.index, (hash >> 16) as u32), hash)
+    }
+}
+
+impl Sampler for Sobol {
+    fn start_sample(&mut self, index: u32) {
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let (index, hash) = self.next_dimension();
+        sampling::bits_to_float(owen_scramble(index.reverse_bits(), (hash >> 32) as u32))
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        let (index, hash) = self.next_dimension();
+        (
+            sampling::bits_to_float(owen_scramble(index.reverse_bits(), (hash >> 32) as u32)),
+            sampling::bits_to_float(owen_scramble(second_dimension(index), hash as u32)),
+        )
+    }
+}
+
+// The second Sobol dimension, its generator matrix columns are v_k = v_(k-1) ^ (v_(k-1) >> 1).
+fn second_dimension(mut index: u32) -> u32 {
+    let mut result = 0;
+    let mut column = 1 << 31;
+    while index != 0 {
+        if index & 1 != 0 {
+            result ^= column;
+        }
+        column ^= column >> 1;
+        index >>= 1;
+    }
+    result
+}
+
+// Hash based nested uniform scrambling of the bits of a fixed point number
+// (Laine and Karras, as in pbrt-v4's FastOwenScrambler).
+fn owen_scramble(value: u32, seed: u32) -> u32 {
+    let mut v = value.reverse_bits();
+    v ^= v.wrapping_mul(0x3d20_adea);
+    v = v.wrapping_add(seed);
+    v = v.wrapping_mul((seed >> 16) | 1);
+    v ^= v.wrapping_mul(0x0552_6c56);
+    v ^= v.wrapping_mul(0x53a2_2864);
+    v.reverse_bits()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_dimension_matches_the_sequence() {
+        let expected = [0.0, 0.5, 0.75, 0.25, 0.625, 0.125, 0.375, 0.875];
+        for (i, &value) in expected.iter().enumerate() {
+            assert_eq!(sampling::bits_to_float(second_dimension(i as u32)), value);
+        }
+    }
+
+    #[test]
+    fn scrambled_points_stay_stratified() {
+        let n = 16;
+        let mut sampler = Sobol::new(99);
+        let points = (0..n)
+            .map(|i| {
+                sampler.start_sample(i);
+                sampler.get_1d();
+                sampler.get_2d()
+            })
+            .collect::<Vec<_>>();
+
+        // every 1/4 x 1/4 cell holds exactly one point
+        let mut cells = points
+            .iter()
+            .map(|&(x, y)| (x * 4.0) as u32 * 4 + (y * 4.0) as u32)
+            .collect::<Vec<_>>();
+        cells.sort_unstable();
+        assert_eq!(cells, (0..n).collect::<Vec<_>>());
+    }
+}
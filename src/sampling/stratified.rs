@@ -0,0 +1,86 @@
+use crate::{
+    sampling::{
+        self,
+        Sampler,
+    },
+    random,
+};
+
+// Splits every dimension of a pixel into as many strata as there are samples
+// and jitters a value inside a different stratum for each sample.
+// 2D values are stratified over a grid, the strata are shuffled independently per dimension.
+pub struct Stratified {
+    samples_per_pixel: u32,
+    seed: u64,
+    index: u32,
+    dimension: u32,
+}
+
+impl Stratified {
+    pub fn new(samples_per_pixel: u32, seed: u64) -> Self {
+        Self {
+            samples_per_pixel: samples_per_pixel.max(1),
+            seed,
+            index: 0,
+            dimension: 0,
+        }
+    }
+
+    fn next_hash(&mut self) -> u64 {
+        let hash = random::hash(&[self.seed, self.dimension as u64]);
+        self.dimension += 1;
+        hash
+    }
+
+    fn jitter(&self, hash: u64, k: u64) -> f32 {
+        random::hash_to_float(&[hash, self.index as u64, k])
+    }
+}
+
+impl Sampler for Stratified {
+    fn start_sample(&mut self, index: u32) {
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn get_1d(&mut self) -> f32 {
+        let hash = self.next_hash();
+        let n = self.samples_per_pixel;
+        let stratum = sampling::permutation_element(self.index % n, n, hash as u32);
+        ((stratum as f32 + self.jitter(hash, 0)) / n as f32).min(sampling::ONE_MINUS_EPSILON)
+    }
+
+    fn get_2d(&mut self) -> (f32, f32) {
+        let hash = self.next_hash();
+        let n = self.samples_per_pixel;
+        let columns = ((n as f32).sqrt() as u32).max(1);
+        let rows = n.div_ceil(columns);
+        let cell = sampling::permutation_element(self.index % n, columns * rows, hash as u32);
+        let x = (cell % columns) as f32 + self.jitter(hash, 0);
+        let y = (cell / columns) as f32 + self.jitter(hash, 1);
+        (
+            (x / columns as f32).min(sampling::ONE_MINUS_EPSILON),
+            (y / rows as f32).min(sampling::ONE_MINUS_EPSILON),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_stratum_gets_one_sample() {
+        let n = 16;
+        let mut sampler = Stratified::new(n, 7);
+        let mut strata = (0..n)
+            .map(|i| {
+                sampler.start_sample(i);
+                (sampler.get_1d() * n as f32) as u32
+            })
+            .collect::<Vec<_>>();
+        strata.sort_unstable();
+
+        assert_eq!(strata, (0..n).collect::<Vec<_>>());
+    }
+}
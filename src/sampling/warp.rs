@@ -0,0 +1,65 @@
+// Mappings of uniform samples in [0, 1)^2 to other domains.
+// Unlike rejection sampling they use a fixed number of sample dimensions.
+
+use crate::{
+    math::{
+        self,
+        Vec3,
+    },
+};
+use std::f32::consts::PI;
+
+// Shirley-Chiu concentric mapping onto the unit disk in the xy plane.
+pub fn concentric_disk((u, v): (f32, f32)) -> Vec3 {
+    let (a, b) = (2.0 * u - 1.0, 2.0 * v - 1.0);
+    if a == 0.0 && b == 0.0 {
+        return math::vec3(0.0, 0.0, 0.0);
+    }
+    let (r, theta) = if a.abs() > b.abs() {
+        (a, PI / 4.0 * (b / a))
+    }
+    else {
+        (b, PI / 2.0 - PI / 4.0 * (a / b))
+    };
+    math::vec3(r * theta.cos(), r * theta.sin(), 0.0)
+}
+
+// Directions around +z with density cos(theta) / pi.
+pub fn cosine_hemisphere(u: (f32, f32)) -> Vec3 {
+    let d = concentric_disk(u);
+    let z = (1.0 - d.x * d.x - d.y * d.y).max(0.0).sqrt();
+    math::vec3(d.x, d.y, z)
+}
+
+// Directions with density 1 / (4 pi).
+pub fn uniform_sphere((u, v): (f32, f32)) -> Vec3 {
+    let z = 1.0 - 2.0 * u;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * PI * v;
+    math::vec3(r * phi.cos(), r * phi.sin(), z)
+}
+
+// Points uniformly distributed inside the unit ball.
+pub fn uniform_ball(u: f32, direction: (f32, f32)) -> Vec3 {
+    u.cbrt() * uniform_sphere(direction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::InnerSpace;
+
+    #[test]
+    fn mapped_points_lie_in_their_domains() {
+        for i in 0..10 {
+            for j in 0..10 {
+                let u = (i as f32 / 10.0, j as f32 / 10.0);
+                assert!(concentric_disk(u).magnitude() <= 1.0 + 1e-6);
+                assert!(cosine_hemisphere(u).z >= 0.0);
+                assert!((cosine_hemisphere(u).magnitude() - 1.0).abs() < 1e-5);
+                assert!((uniform_sphere(u).magnitude() - 1.0).abs() < 1e-5);
+                assert!(uniform_ball(0.5, u).magnitude() < 1.0);
+            }
+        }
+    }
+}
@@ -10,27 +10,166 @@ use crate::{
     aabb::{
         AABB,
     },
+    background::{
+        Background,
+    },
     bvh,
 };
+use std::sync::Arc;
 
 pub struct Scene {
-    root: bvh::Node,
+    // the BVH, or the only bounded hittable, absent when there are none
+    root: Option<Box<dyn Hittable>>,
+    // hittables without a bounding box, like infinite planes, tested by every ray
+    unbounded: Vec<Box<dyn Hittable>>,
+    bvh_stats: bvh::Stats,
+    lights: Vec<Arc<dyn Hittable>>,
+    background: Background,
 }
 
 impl Scene {
-    pub fn new(hittables: Vec<Box<dyn Hittable>>, time_interval: &Interval<f32>) -> Self {
+    pub fn new(hittables: Vec<Box<dyn Hittable>>, time_interval: &Interval<f32>, bvh_options: &bvh::Options) -> Self {
+        // lights are shared between the BVH and the light list
+        let mut lights = Vec::new();
+        let (mut bounded, unbounded): (Vec<Box<dyn Hittable>>, Vec<_>) = hittables
+            .into_iter()
+            .map(|hittable| {
+                if hittable.is_light() {
+                    let light: Arc<dyn Hittable> = hittable.into();
+                    lights.push(light.clone());
+                    Box::new(light) as Box<dyn Hittable>
+                }
+                else {
+                    hittable
+                }
+            })
+            .partition(|hittable| hittable.bounding_box(time_interval).is_some());
+        // the top level BVH when the hittables are instances of shared bottom level ones
+        let (root, bvh_stats) = match bounded.len() {
+            0 => (None, bvh::Stats::empty()),
+            1 => (bounded.pop(), bvh::Stats::empty()),
+            _ => {
+                let (root, stats) = bvh::build(bounded, time_interval, bvh_options);
+                (Some(root), stats)
+            },
+        };
         Scene {
-            root: bvh::Node::new(hittables, time_interval),
+            root,
+            unbounded,
+            bvh_stats,
+            lights,
+            background: Background::black(),
+        }
+    }
+
+    pub fn with_background(self, background: Background) -> Self {
+        let mut lights = self.lights;
+        // an environment map being replaced is no longer a light
+        if let Background::EnvironmentMap(map) = &self.background {
+            let old = Arc::as_ptr(map) as *const ();
+            lights.retain(|light| Arc::as_ptr(light) as *const () != old);
         }
+        if let Background::EnvironmentMap(map) = &background {
+            lights.push(map.clone());
+        }
+        Self {
+            lights,
+            background,
+            ..self
+        }
+    }
+
+    // what rays which miss every hittable see
+    pub fn background(&self) -> &Background {
+        &self.background
+    }
+
+    // the hittables which emit light and can be sampled directly
+    pub fn lights(&self) -> &[Arc<dyn Hittable>] {
+        &self.lights
+    }
+
+    pub fn bvh_stats(&self) -> bvh::Stats {
+        self.bvh_stats
     }
 }
 
 impl Hittable for Scene {
-    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord> {
-        self.root.hit(ray, hit_interval)
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let mut interval = *hit_interval;
+        let mut closest = None;
+        for hittable in self.root.iter().chain(&self.unbounded) {
+            if let Some(hit_record) = hittable.hit(ray, &interval) {
+                interval = interval.with_max(hit_record.t).expect("invalid interval");
+                closest = Some(hit_record);
+            }
+        }
+        closest
     }
 
     fn bounding_box(&self, time_interval: &Interval<f32>) -> Option<AABB> {
-        self.root.bounding_box(time_interval)
+        if self.unbounded.is_empty() {
+            self.root.as_ref().and_then(|root| root.bounding_box(time_interval))
+        }
+        else {
+            None
+        }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shapes::{
+            Sphere,
+            Plane,
+        },
+        materials::Dielectric,
+        math::{
+            self,
+            Point3,
+        },
+    };
+
+    fn scene(hittables: Vec<Box<dyn Hittable>>) -> Scene {
+        Scene::new(hittables, &Interval::new(0.0, 1.0).unwrap(), &bvh::Options::default())
+    }
+
+    fn sphere(x: f32) -> Box<dyn Hittable> {
+        Box::new(Sphere::new(Point3::new(x, 0.0, 0.0), 1.0, Arc::new(Dielectric::new(1.5))))
+    }
+
+    fn hit_t(scene: &Scene, origin: Point3) -> Option<f32> {
+        let ray = Ray::new(origin, math::vec3(0.0, -1.0, 0.0), 0.0);
+        scene.hit(&ray, &Interval::new(0.001, f32::MAX).unwrap()).map(|h| h.t)
+    }
+
+    #[test]
+    fn empty_and_single_hittable_scenes() {
+        let empty = scene(Vec::new());
+        assert_eq!(hit_t(&empty, Point3::new(0.0, 5.0, 0.0)), None);
+        assert!(empty.bounding_box(&Interval::new(0.0, 1.0).unwrap()).is_none());
+
+        let single = scene(vec![sphere(0.0)]);
+        assert_eq!(hit_t(&single, Point3::new(0.0, 5.0, 0.0)), Some(4.0));
+        assert!(single.bounding_box(&Interval::new(0.0, 1.0).unwrap()).is_some());
+    }
+
+    #[test]
+    fn unbounded_hittables_are_tested_with_the_bvh() {
+        let ground = Box::new(Plane::new(Point3::new(0.0, 0.5, 0.0), math::vec3(0.0, 1.0, 0.0), Arc::new(Dielectric::new(1.5))));
+        let scene = scene(vec![sphere(0.0), ground, sphere(10.0)]);
+        let assert_hit_at = |x: f32, expected: f32| {
+            let t = hit_t(&scene, Point3::new(x, 5.0, 0.0)).unwrap();
+            assert!((t - expected).abs() < 1e-4, "{} != {}", t, expected);
+        };
+
+        // the plane cuts through the spheres, whose tops are in front of it
+        assert_hit_at(0.0, 4.0);
+        assert_hit_at(10.0, 4.0);
+        // nearer their edges the plane is in front of the spheres
+        assert_hit_at(0.9, 4.5);
+        assert_hit_at(100.0, 4.5);
+        assert!(scene.bounding_box(&Interval::new(0.0, 1.0).unwrap()).is_none());
+    }
+}
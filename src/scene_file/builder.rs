@@ -0,0 +1,1140 @@
+use crate::{
+    math::{
+        InnerSpace,
+        Interval,
+        Mat4,
+        Matrix,
+        Vec3,
+        vec3,
+    },
+    core::{
+        Hittable,
+    },
+    scene::{
+        Scene,
+    },
+    bvh,
+    render::{
+        MaxDepths,
+    },
+    background::{
+        Background,
+        EnvironmentMap,
+        Gradient,
+    },
+    camera::{
+        Camera,
+        CameraAxis,
+        FieldOfView,
+    },
+    materials::{
+        Material,
+        Lambertian,
+        Metal,
+        Dielectric,
+        DiffuseLight,
+        Isotropic,
+    },
+    textures::{
+        Texture,
+        TextureCoordinates,
+        ConstantTexture,
+        CheckerTexture,
+        NoiseTexture,
+        ImageTexture,
+        Filter,
+        Wrap,
+    },
+    shapes::{
+        Sphere,
+        MovingSphere,
+        moving_sphere::Centers,
+        XYRectangle,
+        XZRectangle,
+        YZRectangle,
+        FlipNormals,
+        Parallelepiped,
+        Triangle,
+        Quad,
+        Disk,
+        Plane,
+        Cylinder,
+        Cone,
+        Paraboloid,
+        Torus,
+        TriangleMesh,
+        Csg,
+        Operation,
+        SdfShape,
+    },
+    sdf::{
+        self,
+        Sdf,
+    },
+    aabb::{
+        AABB,
+    },
+    transformations::{
+        Translation,
+        YRotation,
+        Transform,
+        MotionTransform,
+        Keyframe,
+        Instance,
+    },
+    volumes::{
+        ConstantMedium,
+    },
+    scene_file::{
+        Error,
+        parser::{
+            Block,
+            Definition,
+            Statement,
+            Value,
+            ValueKind,
+        },
+    },
+};
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::Arc,
+};
+
+pub fn build(statements: &[Statement], directory: &Path, aspect: f32, time_interval: &Interval<f32>, bvh_options: &bvh::Options) -> Result<(Scene, Camera, MaxDepths), Error> {
+    let mut builder = Builder::new(directory);
+    let mut camera = None;
+    let mut max_depths = None;
+    let mut background = None;
+    let mut hittables = Vec::new();
+
+    for statement in statements {
+        match statement {
+            Statement::Camera(block) => {
+                if camera.is_some() {
+                    return Err(Error::at(block.position, "the camera is already defined".to_owned()));
+                }
+                camera = Some(make_camera(block, aspect)?);
+            },
+            Statement::Settings(block) => {
+                if max_depths.is_some() {
+                    return Err(Error::at(block.position, "the settings are already defined".to_owned()));
+                }
+                max_depths = Some(make_max_depths(block)?);
+            },
+            Statement::Background(value) => {
+                if background.is_some() {
+                    return Err(Error::at(value.position, "the background is already defined".to_owned()));
+                }
+                background = Some(make_background(value, directory)?);
+            },
+            Statement::Texture(definition) => builder.define_texture(definition)?,
+            Statement::Material(definition) => builder.define_material(definition)?,
+            Statement::Geometry(definition) => builder.define_geometry(definition, time_interval, bvh_options)?,
+            Statement::Object(value) => hittables.push(builder.object(value)?),
+        }
+    }
+
+    let camera = camera.ok_or_else(|| Error::new("the scene has no camera".to_owned()))?;
+    let scene = Scene::new(hittables, time_interval, bvh_options)
+        .with_background(background.unwrap_or_else(Background::black));
+    Ok((scene, camera, max_depths.unwrap_or_default()))
+}
+
+fn define<'a, T>(definitions: &mut HashMap<&'a str, T>, definition: &'a Definition, value: T) -> Result<(), Error> {
+    if definitions.insert(&definition.name, value).is_some() {
+        Err(Error::at(definition.position, format!("`{}` is already defined", definition.name)))
+    }
+    else {
+        Ok(())
+    }
+}
+
+fn make_camera(block: &Block, aspect: f32) -> Result<Camera, Error> {
+    block.check_fields(&["look_from", "look_at", "up", "fov", "aperture", "focus_distance"])?;
+    let look_at = block.field("look_at")?;
+    let axis = CameraAxis {
+        look_from: block.field("look_from")?.as_point3()?,
+        look_at: look_at.as_point3()?,
+    };
+    let direction = axis.look_at - axis.look_from;
+    if direction.magnitude2() == 0.0 {
+        return Err(Error::at(look_at.position, "`look_at` must differ from `look_from`".to_owned()));
+    }
+    // without an `up` field the view must not be vertical
+    let (vector_up, up_position) = match block.optional_field("up") {
+        Some(v) => (v.as_vec3()?, v.position),
+        None => (vec3(0.0, 1.0, 0.0), look_at.position),
+    };
+    if vector_up.cross(direction).magnitude2() <= 1e-12 * vector_up.magnitude2() * direction.magnitude2() {
+        return Err(Error::at(up_position, "`up` must not be parallel to the view direction".to_owned()));
+    }
+    let fov = block.field("fov")?;
+    let degrees = fov.as_number()?;
+    if !(degrees > 0.0 && degrees < 180.0) {
+        return Err(Error::at(fov.position, "`fov` must be between 0 and 180 degrees".to_owned()));
+    }
+    let aperture = optional_number(block, "aperture", 0.0)?;
+    let focus_distance = optional_number(block, "focus_distance", direction.magnitude())?;
+    Ok(Camera::new(axis, vector_up, FieldOfView::from_degrees(degrees), aspect, aperture, focus_distance))
+}
+
+fn make_background(value: &Value, directory: &Path) -> Result<Background, Error> {
+    let block = match &value.kind {
+        ValueKind::Block(block) => block,
+        _ => return Err(Error::at(value.position, "expected a background".to_owned())),
+    };
+    match block.name.as_str() {
+        "constant" => {
+            block.check_fields(&["colour"])?;
+            Ok(Background::Constant(block.field("colour")?.as_vec3()?))
+        },
+        "gradient" => {
+            block.check_fields(&["bottom", "top"])?;
+            let sky = Gradient::default();
+            let colour = |name, default| block.optional_field(name).map_or(Ok(default), |v| v.as_vec3());
+            Ok(Background::Gradient(Gradient {
+                bottom: colour("bottom", sky.bottom)?,
+                top: colour("top", sky.top)?,
+            }))
+        },
+        "environment_map" => {
+            block.check_fields(&["path", "rotation", "intensity"])?;
+            let path = block.field("path")?;
+            let rotation = optional_number(block, "rotation", 0.0)?;
+            let intensity = optional_number(block, "intensity", 1.0)?;
+            EnvironmentMap::from_file(&directory.join(path.as_string()?), rotation, intensity)
+                .map(|map| Background::EnvironmentMap(Arc::new(map)))
+                .map_err(|e| Error::at(path.position, e))
+        },
+        name => Err(Error::at(block.position, format!("unknown background type `{}`", name))),
+    }
+}
+
+fn make_max_depths(block: &Block) -> Result<MaxDepths, Error> {
+    block.check_fields(&["diffuse_depth", "glossy_depth", "transmission_depth", "volume_depth"])?;
+    let defaults = MaxDepths::default();
+    let optional_depth = |name, default| block.optional_field(name).map_or(Ok(default), depth);
+    Ok(MaxDepths {
+        diffuse: optional_depth("diffuse_depth", defaults.diffuse)?,
+        glossy: optional_depth("glossy_depth", defaults.glossy)?,
+        transmission: optional_depth("transmission_depth", defaults.transmission)?,
+        volume: optional_depth("volume_depth", defaults.volume)?,
+    })
+}
+
+fn depth(value: &Value) -> Result<u32, Error> {
+    match value.as_number()? {
+        n if n >= 0.0 && n.fract() == 0.0 => Ok(n as u32),
+        _ => Err(Error::at(value.position, "expected a non-negative integer".to_owned())),
+    }
+}
+
+fn make_sdf(value: &Value) -> Result<Box<dyn Sdf>, Error> {
+    let block = match &value.kind {
+        ValueKind::Block(block) => block,
+        _ => return Err(Error::at(value.position, "expected a distance field".to_owned())),
+    };
+    match block.name.as_str() {
+        "sphere" => {
+            block.check_fields(&["radius"])?;
+            Ok(Box::new(sdf::Sphere {
+                radius: positive_number(block.field("radius")?)?,
+            }))
+        },
+        "torus" => {
+            block.check_fields(&["major_radius", "minor_radius"])?;
+            Ok(Box::new(sdf::Torus {
+                major_radius: positive_number(block.field("major_radius")?)?,
+                minor_radius: positive_number(block.field("minor_radius")?)?,
+            }))
+        },
+        "rounded_box" => {
+            block.check_fields(&["half_extents", "radius"])?;
+            Ok(Box::new(sdf::RoundedBox {
+                half_extents: block.field("half_extents")?.as_vec3()?,
+                radius: optional_number(block, "radius", 0.0)?,
+            }))
+        },
+        "capsule" => {
+            block.check_fields(&["a", "b", "radius"])?;
+            Ok(Box::new(sdf::Capsule {
+                a: block.field("a")?.as_point3()?,
+                b: block.field("b")?.as_point3()?,
+                radius: positive_number(block.field("radius")?)?,
+            }))
+        },
+        "cylinder" => {
+            block.check_fields(&["radius", "half_height"])?;
+            Ok(Box::new(sdf::Cylinder {
+                radius: positive_number(block.field("radius")?)?,
+                half_height: positive_number(block.field("half_height")?)?,
+            }))
+        },
+        "translate" => {
+            block.check_fields(&["field", "offset"])?;
+            Ok(Box::new(sdf::Translate {
+                sdf: make_sdf(block.field("field")?)?,
+                offset: block.field("offset")?.as_vec3()?,
+            }))
+        },
+        "smooth_union" => {
+            block.check_fields(&["a", "b", "smoothness"])?;
+            Ok(Box::new(sdf::SmoothUnion {
+                a: make_sdf(block.field("a")?)?,
+                b: make_sdf(block.field("b")?)?,
+                smoothness: block.field("smoothness")?.as_number()?,
+            }))
+        },
+        "subtraction" => {
+            block.check_fields(&["a", "b"])?;
+            Ok(Box::new(sdf::Subtraction {
+                a: make_sdf(block.field("a")?)?,
+                b: make_sdf(block.field("b")?)?,
+            }))
+        },
+        "repetition" => {
+            block.check_fields(&["field", "period"])?;
+            Ok(Box::new(sdf::Repetition {
+                sdf: make_sdf(block.field("field")?)?,
+                period: block.field("period")?.as_vec3()?,
+            }))
+        },
+        "twist" => {
+            block.check_fields(&["field", "rate"])?;
+            Ok(Box::new(sdf::Twist {
+                sdf: make_sdf(block.field("field")?)?,
+                rate: block.field("rate")?.as_number()?,
+            }))
+        },
+        name => Err(Error::at(block.position, format!("unknown distance field type `{}`", name))),
+    }
+}
+
+// `keyframe { time = 0, scale = 2, rotate = [[0, 1, 0], 90], translate = [0, 1, 0] }`
+fn make_keyframe(value: &Value) -> Result<Keyframe, Error> {
+    let block = match &value.kind {
+        ValueKind::Block(block) if block.name == "keyframe" => block,
+        _ => return Err(Error::at(value.position, "expected a keyframe".to_owned())),
+    };
+    block.check_fields(&["time", "scale", "rotate", "translate"])?;
+    let mut keyframe = Keyframe::new(block.field("time")?.as_number()?);
+    if let Some(scale) = block.optional_field("scale") {
+        keyframe = keyframe.with_scale(scale_factors(scale)?);
+    }
+    if let Some(rotation) = block.optional_field("rotate") {
+        let (axis, degrees) = axis_and_degrees(rotation)?;
+        keyframe = keyframe.with_rotation(axis, degrees);
+    }
+    if let Some(translation) = block.optional_field("translate") {
+        keyframe = keyframe.with_translation(translation.as_vec3()?);
+    }
+    Ok(keyframe)
+}
+
+// a single number or one for each axis
+fn scale_factors(value: &Value) -> Result<Vec3, Error> {
+    let factors = match value.as_number() {
+        Ok(factor) => vec3(factor, factor, factor),
+        Err(_) => value.as_vec3()?,
+    };
+    if factors.x == 0.0 || factors.y == 0.0 || factors.z == 0.0 {
+        return Err(Error::at(value.position, "scale factors must not be zero".to_owned()));
+    }
+    Ok(factors)
+}
+
+// `[axis, degrees]`
+fn axis_and_degrees(value: &Value) -> Result<(Vec3, f32), Error> {
+    let rotation = value.as_list(2)?;
+    let axis = rotation[0].as_vec3()?;
+    if axis.magnitude2() == 0.0 {
+        return Err(Error::at(rotation[0].position, "the axis must not be zero".to_owned()));
+    }
+    Ok((axis, rotation[1].as_number()?))
+}
+
+fn optional_number(block: &Block, name: &str, default: f32) -> Result<f32, Error> {
+    block.optional_field(name)
+        .map_or(Ok(default), |v| v.as_number())
+}
+
+fn positive_number(value: &Value) -> Result<f32, Error> {
+    match value.as_number()? {
+        n if n > 0.0 => Ok(n),
+        _ => Err(Error::at(value.position, "expected a positive number".to_owned())),
+    }
+}
+
+// Applies the transformation fields of the block in their order, the other fields are skipped.
+fn transformed<H: Hittable>(mut transform: Transform<H>, block: &Block) -> Result<Transform<H>, Error> {
+    for field in &block.fields {
+        let value = &field.value;
+        transform = match field.name.as_str() {
+            "matrix" => {
+                let rows = value.as_list(4)?
+                    .iter()
+                    .map(|row| row.as_numbers(4))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let matrix = Mat4::new(
+                    rows[0][0], rows[0][1], rows[0][2], rows[0][3],
+                    rows[1][0], rows[1][1], rows[1][2], rows[1][3],
+                    rows[2][0], rows[2][1], rows[2][2], rows[2][3],
+                    rows[3][0], rows[3][1], rows[3][2], rows[3][3],
+                ).transpose();
+                transform.then_matrix(matrix).map_err(|e| Error::at(value.position, e))?
+            },
+            "scale" => transform.scale(scale_factors(value)?),
+            "rotate" => {
+                let (axis, degrees) = axis_and_degrees(value)?;
+                transform.rotate(axis, degrees)
+            },
+            "translate" => transform.translate(value.as_vec3()?),
+            "look_at" => {
+                let points = value.as_list(3)?;
+                transform
+                    .look_at(points[0].as_point3()?, points[1].as_point3()?, points[2].as_vec3()?)
+                    .map_err(|e| Error::at(value.position, e))?
+            },
+            _ => transform,
+        };
+    }
+    Ok(transform)
+}
+
+// The optional `sweep` in degrees and `heights` range of a surface of revolution,
+// the heights must overlap those the surface spans.
+fn revolution_limits(block: &Block, y_min: f32, y_max: f32) -> Result<(f32, (f32, f32)), Error> {
+    let sweep = match block.optional_field("sweep") {
+        Some(value) => match value.as_number()? {
+            degrees if degrees > 0.0 && degrees <= 360.0 => degrees,
+            _ => return Err(Error::at(value.position, "expected an angle above 0 and up to 360 degrees".to_owned())),
+        },
+        None => 360.0,
+    };
+    match block.optional_field("heights") {
+        Some(value) => {
+            let (low, high) = value.as_range()?;
+            if low >= y_max || high <= y_min {
+                return Err(Error::at(value.position, format!("expected heights overlapping [{}, {}]", y_min, y_max)));
+            }
+            Ok((sweep, (low, high)))
+        },
+        None => Ok((sweep, (y_min, y_max))),
+    }
+}
+
+// Named textures, materials and geometries are built once where they are defined
+// and shared by every object which refers to them.
+struct Builder<'a> {
+    textures: HashMap<&'a str, Arc<dyn Texture>>,
+    materials: HashMap<&'a str, Arc<dyn Material>>,
+    geometries: HashMap<&'a str, Arc<dyn Hittable>>,
+    directory: &'a Path,
+}
+
+impl<'a> Builder<'a> {
+    fn new(directory: &'a Path) -> Self {
+        Self {
+            textures: HashMap::new(),
+            materials: HashMap::new(),
+            geometries: HashMap::new(),
+            directory,
+        }
+    }
+
+    fn define_texture(&mut self, definition: &'a Definition) -> Result<(), Error> {
+        let texture = self.texture(&definition.value)?;
+        define(&mut self.textures, definition, texture)
+    }
+
+    fn define_material(&mut self, definition: &'a Definition) -> Result<(), Error> {
+        let material = self.material(&definition.value)?;
+        define(&mut self.materials, definition, material)
+    }
+
+    // A geometry is an object or a list of them in a bottom level BVH, placed by instances.
+    fn define_geometry(&mut self, definition: &'a Definition, time_interval: &Interval<f32>, bvh_options: &bvh::Options) -> Result<(), Error> {
+        let value = &definition.value;
+        let geometry: Arc<dyn Hittable> = match &value.kind {
+            ValueKind::List(values) if values.is_empty() => {
+                return Err(Error::at(value.position, "expected at least one object".to_owned()));
+            },
+            ValueKind::List(values) if values.len() > 1 => {
+                let mut objects = Vec::with_capacity(values.len());
+                for v in values {
+                    let object = self.object(v)?;
+                    if object.bounding_box(time_interval).is_none() {
+                        return Err(Error::at(v.position, "an unbounded object must be the only one of its geometry".to_owned()));
+                    }
+                    objects.push(object);
+                }
+                let (blas, _) = bvh::build(objects, time_interval, bvh_options);
+                Arc::from(blas)
+            },
+            ValueKind::List(values) => Arc::from(self.object(&values[0])?),
+            _ => Arc::from(self.object(value)?),
+        };
+        define(&mut self.geometries, definition, geometry)
+    }
+
+    fn texture(&self, value: &Value) -> Result<Arc<dyn Texture>, Error> {
+        let block = match &value.kind {
+            ValueKind::Identifier(name) => {
+                return match self.textures.get(name.as_str()) {
+                    Some(texture) => Ok(texture.clone()),
+                    None => Err(Error::at(value.position, format!("unknown texture `{}`", name))),
+                }
+            },
+            ValueKind::List(_) => return Ok(Arc::new(ConstantTexture::from_rgb(value.as_vec3()?))),
+            ValueKind::Block(block) => block,
+            _ => return Err(Error::at(value.position, "expected a texture".to_owned())),
+        };
+
+        match block.name.as_str() {
+            "constant" => {
+                block.check_fields(&["colour"])?;
+                Ok(Arc::new(ConstantTexture::from_rgb(block.field("colour")?.as_vec3()?)))
+            },
+            "checker" => {
+                block.check_fields(&["even", "odd"])?;
+                Ok(Arc::new(CheckerTexture {
+                    even: self.texture(block.field("even")?)?,
+                    odd: self.texture(block.field("odd")?)?,
+                }))
+            },
+            "noise" => {
+                block.check_fields(&["scale"])?;
+                Ok(Arc::new(NoiseTexture::new(block.field("scale")?.as_number()?)))
+            },
+            "image" => {
+                block.check_fields(&["path", "filter", "wrap"])?;
+                let path = block.field("path")?;
+                let filter = match block.optional_field("filter") {
+                    None => Filter::Bilinear,
+                    Some(value) => match value.as_string()? {
+                        "nearest" => Filter::Nearest,
+                        "bilinear" => Filter::Bilinear,
+                        _ => return Err(Error::at(value.position, "expected \"nearest\" or \"bilinear\"".to_owned())),
+                    },
+                };
+                let wrap = match block.optional_field("wrap") {
+                    None => Wrap::Repeat,
+                    Some(value) => match value.as_string()? {
+                        "repeat" => Wrap::Repeat,
+                        "clamp" => Wrap::Clamp,
+                        "mirror" => Wrap::Mirror,
+                        _ => return Err(Error::at(value.position, "expected \"repeat\", \"clamp\" or \"mirror\"".to_owned())),
+                    },
+                };
+                ImageTexture::from_file(&self.directory.join(path.as_string()?))
+                    .map(|texture| Arc::new(texture.with_filter(filter).with_wrap(wrap)) as Arc<dyn Texture>)
+                    .map_err(|e| Error::at(path.position, e))
+            },
+            name => Err(Error::at(block.position, format!("unknown texture type `{}`", name))),
+        }
+    }
+
+    fn material(&self, value: &Value) -> Result<Arc<dyn Material>, Error> {
+        let block = match &value.kind {
+            ValueKind::Identifier(name) => {
+                return match self.materials.get(name.as_str()) {
+                    Some(material) => Ok(material.clone()),
+                    None => Err(Error::at(value.position, format!("unknown material `{}`", name))),
+                }
+            },
+            ValueKind::Block(block) => block,
+            _ => return Err(Error::at(value.position, "expected a material".to_owned())),
+        };
+
+        match block.name.as_str() {
+            "lambertian" => {
+                block.check_fields(&["albedo"])?;
+                Ok(Arc::new(Lambertian::new(self.texture(block.field("albedo")?)?)))
+            },
+            "metal" => {
+                block.check_fields(&["albedo", "fuzz"])?;
+                let albedo = block.field("albedo")?.as_vec3()?;
+                let fuzz = optional_number(block, "fuzz", 0.0)?;
+                Ok(Arc::new(Metal::new(albedo, fuzz)))
+            },
+            "dielectric" => {
+                block.check_fields(&["refractive_index"])?;
+                Ok(Arc::new(Dielectric::new(positive_number(block.field("refractive_index")?)?)))
+            },
+            "diffuse_light" => {
+                block.check_fields(&["emit"])?;
+                Ok(Arc::new(DiffuseLight::new(self.texture(block.field("emit")?)?)))
+            },
+            "isotropic" => {
+                block.check_fields(&["albedo"])?;
+                Ok(Arc::new(Isotropic::new(self.texture(block.field("albedo")?)?)))
+            },
+            name => Err(Error::at(block.position, format!("unknown material type `{}`", name))),
+        }
+    }
+
+    fn object(&self, value: &Value) -> Result<Box<dyn Hittable>, Error> {
+        let block = match &value.kind {
+            ValueKind::Block(block) => block,
+            _ => return Err(Error::at(value.position, "expected an object".to_owned())),
+        };
+
+        match block.name.as_str() {
+            "sphere" => {
+                block.check_fields(&["center", "radius", "material"])?;
+                Ok(Box::new(Sphere::new(
+                    block.field("center")?.as_point3()?,
+                    positive_number(block.field("radius")?)?,
+                    self.material(block.field("material")?)?,
+                )))
+            },
+            "moving_sphere" => {
+                block.check_fields(&["start", "end", "time", "radius", "material"])?;
+                let centers = Centers {
+                    starting: block.field("start")?.as_point3()?,
+                    ending: block.field("end")?.as_point3()?,
+                };
+                let movement_time_interval = match block.optional_field("time") {
+                    Some(v) => {
+                        let (t0, t1) = v.as_range()?;
+                        Interval::new(t0, t1).unwrap()
+                    },
+                    None => Interval::new(0.0, 1.0).unwrap(),
+                };
+                Ok(Box::new(MovingSphere::new(
+                    centers,
+                    positive_number(block.field("radius")?)?,
+                    movement_time_interval,
+                    self.material(block.field("material")?)?,
+                )))
+            },
+            "xy_rectangle" => {
+                block.check_fields(&["x", "y", "z", "material"])?;
+                let (x0, x1) = block.field("x")?.as_range()?;
+                let (y0, y1) = block.field("y")?.as_range()?;
+                let z = block.field("z")?.as_number()?;
+                Ok(Box::new(XYRectangle::new(x0, x1, y0, y1, z, self.material(block.field("material")?)?)))
+            },
+            "xz_rectangle" => {
+                block.check_fields(&["x", "z", "y", "material"])?;
+                let (x0, x1) = block.field("x")?.as_range()?;
+                let (z0, z1) = block.field("z")?.as_range()?;
+                let y = block.field("y")?.as_number()?;
+                Ok(Box::new(XZRectangle::new(x0, x1, z0, z1, y, self.material(block.field("material")?)?)))
+            },
+            "yz_rectangle" => {
+                block.check_fields(&["y", "z", "x", "material"])?;
+                let (y0, y1) = block.field("y")?.as_range()?;
+                let (z0, z1) = block.field("z")?.as_range()?;
+                let x = block.field("x")?.as_number()?;
+                Ok(Box::new(YZRectangle::new(y0, y1, z0, z1, x, self.material(block.field("material")?)?)))
+            },
+            "parallelepiped" => {
+                block.check_fields(&["min", "max", "material"])?;
+                let min = block.field("min")?.as_point3()?;
+                let max = block.field("max")?.as_point3()?;
+                if (0..3).any(|d| min[d] >= max[d]) {
+                    return Err(Error::at(block.position, "`min` must be less than `max` in every dimension".to_owned()));
+                }
+                Ok(Box::new(Parallelepiped::new(&min, &max, self.material(block.field("material")?)?)))
+            },
+            "triangle" => {
+                block.check_fields(&["vertices", "normals", "uvs", "material"])?;
+                let vertices = block.field("vertices")?.as_list(3)?;
+                let vertices = [vertices[0].as_point3()?, vertices[1].as_point3()?, vertices[2].as_point3()?];
+                let mut triangle = Triangle::new(vertices, self.material(block.field("material")?)?);
+                if let Some(normals) = block.optional_field("normals") {
+                    let n = normals.as_list(3)?;
+                    triangle = triangle.with_normals([n[0].as_vec3()?, n[1].as_vec3()?, n[2].as_vec3()?]);
+                }
+                if let Some(uvs) = block.optional_field("uvs") {
+                    let uvs = uvs.as_list(3)?
+                        .iter()
+                        .map(|uv| uv.as_numbers(2).map(|c| TextureCoordinates{ u: c[0], v: c[1] }))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    triangle = triangle.with_uvs([uvs[0], uvs[1], uvs[2]]);
+                }
+                Ok(Box::new(triangle))
+            },
+            "quad" => {
+                block.check_fields(&["corner", "u", "v", "material"])?;
+                let u = block.field("u")?.as_vec3()?;
+                let v = block.field("v")?.as_vec3()?;
+                if u.cross(v).magnitude2() == 0.0 {
+                    return Err(Error::at(block.position, "`u` and `v` must not be parallel".to_owned()));
+                }
+                Ok(Box::new(Quad::new(block.field("corner")?.as_point3()?, u, v, self.material(block.field("material")?)?)))
+            },
+            "disk" => {
+                block.check_fields(&["center", "normal", "radius", "material"])?;
+                let normal = block.field("normal")?;
+                let direction = normal.as_vec3()?;
+                if direction.magnitude2() == 0.0 {
+                    return Err(Error::at(normal.position, "the normal must not be zero".to_owned()));
+                }
+                Ok(Box::new(Disk::new(
+                    block.field("center")?.as_point3()?,
+                    direction,
+                    positive_number(block.field("radius")?)?,
+                    self.material(block.field("material")?)?,
+                )))
+            },
+            "plane" => {
+                block.check_fields(&["point", "normal", "material"])?;
+                let normal = block.field("normal")?;
+                let direction = normal.as_vec3()?;
+                if direction.magnitude2() == 0.0 {
+                    return Err(Error::at(normal.position, "the normal must not be zero".to_owned()));
+                }
+                Ok(Box::new(Plane::new(block.field("point")?.as_point3()?, direction, self.material(block.field("material")?)?)))
+            },
+            "cylinder" => {
+                block.check_fields(&["radius", "height", "sweep", "heights", "ends", "material"])?;
+                let height = positive_number(block.field("height")?)?;
+                let (sweep, (y_min, y_max)) = revolution_limits(block, 0.0, height)?;
+                let cylinder = Cylinder::new(positive_number(block.field("radius")?)?, height, self.material(block.field("material")?)?)
+                    .with_sweep(sweep)
+                    .with_height_limits(y_min, y_max);
+                match block.optional_field("ends") {
+                    None => Ok(Box::new(cylinder)),
+                    Some(ends) => match ends.as_string()? {
+                        "open" => Ok(Box::new(cylinder)),
+                        "closed" => Ok(Box::new(cylinder.with_caps())),
+                        _ => Err(Error::at(ends.position, "expected `open` or `closed`".to_owned())),
+                    },
+                }
+            },
+            "cone" | "paraboloid" => {
+                block.check_fields(&["radius", "height", "sweep", "heights", "material"])?;
+                let radius = positive_number(block.field("radius")?)?;
+                let height = positive_number(block.field("height")?)?;
+                let (sweep, (y_min, y_max)) = revolution_limits(block, 0.0, height)?;
+                let material = self.material(block.field("material")?)?;
+                if block.name == "cone" {
+                    Ok(Box::new(Cone::new(radius, height, material).with_sweep(sweep).with_height_limits(y_min, y_max)))
+                }
+                else {
+                    Ok(Box::new(Paraboloid::new(radius, height, material).with_sweep(sweep).with_height_limits(y_min, y_max)))
+                }
+            },
+            "torus" => {
+                block.check_fields(&["major_radius", "minor_radius", "sweep", "heights", "material"])?;
+                let minor_radius = positive_number(block.field("minor_radius")?)?;
+                let (sweep, (y_min, y_max)) = revolution_limits(block, -minor_radius, minor_radius)?;
+                Ok(Box::new(Torus::new(
+                    positive_number(block.field("major_radius")?)?,
+                    minor_radius,
+                    self.material(block.field("material")?)?,
+                ).with_sweep(sweep).with_height_limits(y_min, y_max)))
+            },
+            "mesh" => {
+                block.check_fields(&["path", "material"])?;
+                let path = block.field("path")?;
+                let material = self.material(block.field("material")?)?;
+                TriangleMesh::from_obj_file(&self.directory.join(path.as_string()?), material)
+                    .map(|mesh| Box::new(mesh) as Box<dyn Hittable>)
+                    .map_err(|e| Error::at(path.position, e))
+            },
+            "constant_medium" => {
+                block.check_fields(&["density", "boundary", "albedo"])?;
+                Ok(Box::new(ConstantMedium::new(
+                    positive_number(block.field("density")?)?,
+                    self.object(block.field("boundary")?)?,
+                    self.texture(block.field("albedo")?)?,
+                )))
+            },
+            "translate" => {
+                block.check_fields(&["offset", "object"])?;
+                let offset = block.field("offset")?.as_vec3()?;
+                Ok(Box::new(Translation::on(self.object(block.field("object")?)?, offset)))
+            },
+            "rotate_y" => {
+                block.check_fields(&["degrees", "object"])?;
+                let degrees = block.field("degrees")?.as_number()?;
+                Ok(Box::new(YRotation::from_degrees(self.object(block.field("object")?)?, degrees)))
+            },
+            "transform" => {
+                block.check_fields(&["object", "matrix", "scale", "rotate", "translate", "look_at"])?;
+                let transform = transformed(Transform::new(self.object(block.field("object")?)?), block)?;
+                Ok(Box::new(transform))
+            },
+            "instance" => {
+                block.check_fields(&["geometry", "material", "matrix", "scale", "rotate", "translate", "look_at"])?;
+                let geometry = block.field("geometry")?;
+                let shared = match &geometry.kind {
+                    ValueKind::Identifier(name) => match self.geometries.get(name.as_str()) {
+                        Some(shared) => shared.clone(),
+                        None => return Err(Error::at(geometry.position, format!("unknown geometry `{}`", name))),
+                    },
+                    _ => return Err(Error::at(geometry.position, "expected the name of a geometry".to_owned())),
+                };
+                let instance = Instance::new(transformed(Transform::new(shared), block)?);
+                match block.optional_field("material") {
+                    Some(material) => Ok(Box::new(instance.with_material(self.material(material)?))),
+                    None => Ok(Box::new(instance)),
+                }
+            },
+            "motion" => {
+                block.check_fields(&["object", "keyframes"])?;
+                let keyframes = block.field("keyframes")?;
+                let keyframe_values = match &keyframes.kind {
+                    ValueKind::List(values) => values,
+                    _ => return Err(Error::at(keyframes.position, "expected a list of keyframes".to_owned())),
+                };
+                let keyframe_list = keyframe_values
+                    .iter()
+                    .map(make_keyframe)
+                    .collect::<Result<Vec<_>, _>>()?;
+                MotionTransform::new(self.object(block.field("object")?)?, keyframe_list)
+                    .map(|motion| Box::new(motion) as Box<dyn Hittable>)
+                    .map_err(|e| Error::at(keyframes.position, e))
+            },
+            "sdf" => {
+                block.check_fields(&["field", "min", "max", "lipschitz_bound", "material"])?;
+                let bbox = AABB {
+                    min: block.field("min")?.as_point3()?,
+                    max: block.field("max")?.as_point3()?,
+                };
+                if (0..3).any(|d| bbox.min[d] > bbox.max[d]) {
+                    return Err(Error::at(block.position, "`min` must not exceed `max` in any dimension".to_owned()));
+                }
+                let lipschitz_bound = optional_number(block, "lipschitz_bound", 1.0)?;
+                let shape = SdfShape::new(make_sdf(block.field("field")?)?, bbox, self.material(block.field("material")?)?)
+                    .with_lipschitz_bound(lipschitz_bound);
+                Ok(Box::new(shape))
+            },
+            "union" | "intersection" | "difference" => {
+                block.check_fields(&["left", "right"])?;
+                let operation = match block.name.as_str() {
+                    "union" => Operation::Union,
+                    "intersection" => Operation::Intersection,
+                    _ => Operation::Difference,
+                };
+                let left = block.field("left")?;
+                let right = block.field("right")?;
+                let solid = |value: &Value| {
+                    let object = self.object(value)?;
+                    if object.is_solid() {
+                        Ok(object)
+                    }
+                    else {
+                        Err(Error::at(value.position, "expected a solid, a sphere, parallelepiped or a transformed or combined one".to_owned()))
+                    }
+                };
+                let csg = Csg::new(operation, solid(left)?, solid(right)?)
+                    .map_err(|e| Error::at(block.position, e))?;
+                Ok(Box::new(csg))
+            },
+            "flip_normals" => {
+                block.check_fields(&["object"])?;
+                Ok(Box::new(FlipNormals::new(self.object(block.field("object")?)?)))
+            },
+            name => Err(Error::at(block.position, format!("unknown object type `{}`", name))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        scene_file::{
+            self,
+            Position,
+            parser,
+        },
+        bvh,
+        math::{
+            Point3,
+            Interval,
+            vec3,
+        },
+        core::{
+            Ray,
+            Hittable,
+        },
+        render::MaxDepths,
+    };
+
+    const CAMERA: &str = "camera { look_from = [0, 0, -10], look_at = [0, 0, 0], fov = 40 }\n";
+
+    fn load(objects: &str) -> Result<(), scene_file::Error> {
+        let time_interval = Interval::new(0.0, 1.0).unwrap();
+        scene_file::from_str(&format!("{}{}", CAMERA, objects), 1.0, &time_interval, &bvh::Options::default()).map(|_| ())
+    }
+
+    #[test]
+    fn every_object_type_can_be_built() {
+        let source = "
+            texture white = constant { colour = [0.73, 0.73, 0.73] }
+            material white = lambertian { albedo = white }
+            object sphere { center = [0, 0, 0], radius = 1, material = white }
+            object moving_sphere { start = [0, 0, 0], end = [0, 1, 0], time = [0, 1], radius = 1, material = white }
+            object xy_rectangle { x = [0, 1], y = [0, 1], z = 0, material = metal { albedo = [1, 1, 1], fuzz = 0.5 } }
+            object flip_normals { object = xz_rectangle { x = [0, 1], z = [0, 1], y = 0, material = dielectric { refractive_index = 1.5 } } }
+            object yz_rectangle { y = [0, 1], z = [0, 1], x = 0, material = diffuse_light { emit = [4, 4, 4] } }
+            object translate {
+                offset = [1, 0, 0]
+                object = rotate_y { degrees = 15, object = parallelepiped { min = [0, 0, 0], max = [1, 1, 1], material = white } }
+            }
+            object transform {
+                object = sphere { center = [0, 0, 0], radius = 1, material = white }
+                scale = [2, 1, 1]
+                rotate = [[1, 0, 1], 30]
+                translate = [0, 3, 0]
+                look_at = [[1, 1, 1], [0, 0, 0], [0, 1, 0]]
+                matrix = [[1, 0, 0, 2], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 0, 1]]
+            }
+            object difference {
+                left = parallelepiped { min = [0, 0, 0], max = [1, 1, 1], material = white }
+                right = intersection {
+                    left = sphere { center = [0, 0, 0], radius = 1, material = white }
+                    right = transform { object = sphere { center = [0, 0, 0], radius = 1, material = white }, translate = [1, 0, 0] }
+                }
+            }
+            object union {
+                left = translate { offset = [0, 1, 0], object = sphere { center = [0, 0, 0], radius = 1, material = white } }
+                right = rotate_y { degrees = 45, object = parallelepiped { min = [0, 0, 0], max = [1, 1, 1], material = white } }
+            }
+            object sdf {
+                field = twist {
+                    rate = 1
+                    field = smooth_union {
+                        a = subtraction { a = rounded_box { half_extents = [1, 1, 1], radius = 0.1 }, b = sphere { radius = 1.2 } }
+                        b = translate { offset = [0, 1, 0], field = repetition { period = [1, 0, 0], field = capsule { a = [0, 0, 0], b = [0, 1, 0], radius = 0.2 } } }
+                        smoothness = 0.2
+                    }
+                }
+                min = [-2, -2, -2]
+                max = [2, 2, 2]
+                lipschitz_bound = 2
+                material = white
+            }
+            object sdf { field = torus { major_radius = 1, minor_radius = 0.2 }, min = [-1.2, -0.2, -1.2], max = [1.2, 0.2, 1.2], material = white }
+            object sdf { field = cylinder { radius = 1, half_height = 0.5 }, min = [-1, -0.5, -1], max = [1, 0.5, 1], material = white }
+            object motion {
+                object = parallelepiped { min = [0, 0, 0], max = [1, 1, 1], material = white }
+                keyframes = [
+                    keyframe { time = 0 },
+                    keyframe { time = 1, scale = 2, rotate = [[0, 1, 0], 90], translate = [0, 1, 0] }
+                ]
+            }
+            object triangle {
+                vertices = [[0, 0, 0], [1, 0, 0], [0, 1, 0]]
+                normals = [[0, 0, 1], [0, 0, 1], [0, 0, 1]]
+                uvs = [[0, 0], [1, 0], [0, 1]]
+                material = white
+            }
+            object cylinder { radius = 1, height = 2, sweep = 270, heights = [0.5, 1.5], ends = \"closed\", material = white }
+            object cone { radius = 1, height = 2, heights = [0, 1], material = white }
+            object paraboloid { radius = 1, height = 1, sweep = 180, material = white }
+            object torus { major_radius = 1, minor_radius = 0.25, sweep = 90, material = white }
+            geometry pair = [sphere { center = [0, 0, 0], radius = 1, material = white }, parallelepiped { min = [1, 0, 0], max = [2, 1, 1], material = white }]
+            geometry ball = sphere { center = [0, 0, 0], radius = 1, material = white }
+            object instance { geometry = pair, translate = [0, 2, 0] }
+            object instance { geometry = pair, rotate = [[0, 1, 0], 45], scale = 2, material = metal { albedo = [1, 1, 1], fuzz = 0 } }
+            object instance { geometry = ball, matrix = [[1, 0, 0, 2], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 0, 1]] }
+            object quad { corner = [0, 0, 0], u = [1, 0, 0], v = [0, 1, 1], material = white }
+            object plane { point = [0, -1, 0], normal = [0, 1, 0], material = white }
+            object disk { center = [0, 0, 0], normal = [0, 1, 0], radius = 1, material = diffuse_light { emit = [4, 4, 4] } }
+            object constant_medium {
+                density = 0.01
+                boundary = sphere { center = [0, 0, 0], radius = 1, material = white }
+                albedo = checker { even = white, odd = noise { scale = 4 } }
+            }
+        ";
+
+        assert_eq!(load(source), Ok(()));
+    }
+
+    #[test]
+    fn built_scene_can_be_hit() {
+        let source = "
+            material white = lambertian { albedo = [1, 1, 1] }
+            object sphere { center = [0, 0, 0], radius = 1, material = white }
+            object sphere { center = [5, 0, 0], radius = 1, material = white }
+        ";
+        let time_interval = Interval::new(0.0, 1.0).unwrap();
+        let (scene, _, _) = scene_file::from_str(&format!("{}{}", CAMERA, source), 1.0, &time_interval, &bvh::Options::default()).unwrap();
+        let ray = Ray::new(Point3::new(0.0, 0.0, -10.0), vec3(0.0, 0.0, 1.0), 0.0);
+        let hit_interval = Interval::new(0.0, 100.0).unwrap();
+
+        assert!(scene.hit(&ray, &hit_interval).is_some());
+    }
+
+    #[test]
+    fn unknown_material_is_reported_with_its_position() {
+        let error = load("object sphere { center = [0, 0, 0], radius = 1, material = chrome }").unwrap_err();
+
+        assert_eq!(error.position, Some(Position{ line: 2, column: 60 }));
+    }
+
+    #[test]
+    fn missing_field_is_reported_at_the_block() {
+        let error = load("\nobject sphere { center = [0, 0, 0], material = dielectric { refractive_index = 1.5 } }").unwrap_err();
+
+        assert_eq!(error.position, Some(Position{ line: 3, column: 8 }));
+    }
+
+    #[test]
+    fn unknown_field_is_an_error() {
+        assert!(load("object sphere { centre = [0, 0, 0], radius = 1, material = dielectric { refractive_index = 1.5 } }").is_err());
+    }
+
+    #[test]
+    fn named_materials_are_built_once_and_shared() {
+        let source = "
+            material white = lambertian { albedo = [1, 1, 1] }
+            object sphere { center = [0, 0, 0], radius = 1, material = white }
+        ";
+        let statements = parser::parse(source).unwrap();
+        let mut builder = Builder::new(Path::new(""));
+        let reference = match (&statements[0], &statements[1]) {
+            (Statement::Material(definition), Statement::Object(Value{ kind: ValueKind::Block(block), .. })) => {
+                builder.define_material(definition).unwrap();
+                block.field("material").unwrap()
+            },
+            _ => unreachable!(),
+        };
+
+        assert!(Arc::ptr_eq(&builder.material(reference).unwrap(), &builder.material(reference).unwrap()));
+    }
+
+    #[test]
+    fn instances_refer_to_defined_geometries() {
+        let error = load("object instance { geometry = tree }").unwrap_err();
+        assert_eq!(error.position, Some(Position{ line: 2, column: 30 }));
+
+        assert!(load("geometry empty = []").is_err());
+        let source = "
+            geometry ball = sphere { center = [0, 0, 0], radius = 1, material = dielectric { refractive_index = 1.5 } }
+            geometry ball = sphere { center = [0, 0, 0], radius = 2, material = dielectric { refractive_index = 1.5 } }
+        ";
+        assert!(load(source).is_err());
+    }
+
+    #[test]
+    fn limits_of_surfaces_of_revolution_are_checked() {
+        assert!(load("object torus { major_radius = 1, minor_radius = 0.25, heights = [0.5, 1], material = dielectric { refractive_index = 1.5 } }").is_err());
+        assert!(load("object cone { radius = 1, height = 1, sweep = 400, material = dielectric { refractive_index = 1.5 } }").is_err());
+        assert!(load("object cylinder { radius = 1, height = 1, ends = \"capped\", material = dielectric { refractive_index = 1.5 } }").is_err());
+    }
+
+    #[test]
+    fn boxes_of_distance_fields_must_not_be_inverted() {
+        let sdf = |max: &str| load(&format!("object sdf {{ field = sphere {{ radius = 1 }}, min = [-1, -1, -1], max = {}, material = dielectric {{ refractive_index = 1.5 }} }}", max));
+
+        assert!(sdf("[1, 1, 1]").is_ok());
+        let error = sdf("[1, -2, 1]").unwrap_err();
+        assert_eq!(error.position, Some(Position{ line: 2, column: 8 }));
+    }
+
+    #[test]
+    fn missing_mesh_file_is_reported_at_its_path() {
+        let error = load("object mesh { path = \"missing.obj\", material = dielectric { refractive_index = 1.5 } }").unwrap_err();
+
+        assert_eq!(error.position, Some(Position{ line: 2, column: 22 }));
+    }
+
+    #[test]
+    fn settings_give_the_max_depths() {
+        let source = "
+            settings { diffuse_depth = 2, transmission_depth = 0 }
+            object sphere { center = [0, 0, 0], radius = 1, material = dielectric { refractive_index = 1.5 } }
+            object sphere { center = [5, 0, 0], radius = 1, material = dielectric { refractive_index = 1.5 } }
+        ";
+        let time_interval = Interval::new(0.0, 1.0).unwrap();
+        let (_, _, max_depths) = scene_file::from_str(&format!("{}{}", CAMERA, source), 1.0, &time_interval, &bvh::Options::default()).unwrap();
+
+        assert_eq!(max_depths.diffuse, 2);
+        assert_eq!(max_depths.transmission, 0);
+        assert_eq!(max_depths.glossy, MaxDepths::default().glossy);
+        assert!(load("settings { diffuse_depth = 1.5 }").is_err());
+    }
+
+    #[test]
+    fn background_is_seen_by_rays_which_miss() {
+        let source = "
+            background constant { colour = [0.1, 0.2, 0.3] }
+            object sphere { center = [0, 0, 0], radius = 1, material = dielectric { refractive_index = 1.5 } }
+            object sphere { center = [5, 0, 0], radius = 1, material = dielectric { refractive_index = 1.5 } }
+        ";
+        let time_interval = Interval::new(0.0, 1.0).unwrap();
+        let (scene, _, _) = scene_file::from_str(&format!("{}{}", CAMERA, source), 1.0, &time_interval, &bvh::Options::default()).unwrap();
+
+        assert_eq!(scene.background().value(&vec3(0.0, 1.0, 0.0)), vec3(0.1, 0.2, 0.3));
+        let error = load("background environment_map { path = \"missing.hdr\" }").unwrap_err();
+        assert_eq!(error.position, Some(Position{ line: 2, column: 37 }));
+    }
+
+    #[test]
+    fn only_solids_can_be_combined() {
+        let error = load("object union { left = sphere { center = [0, 0, 0], radius = 1, material = dielectric { refractive_index = 1.5 } }, right = xy_rectangle { x = [0, 1], y = [0, 1], z = 0, material = dielectric { refractive_index = 1.5 } } }").unwrap_err();
+
+        assert_eq!(error.position, Some(Position{ line: 2, column: 124 }));
+    }
+
+    #[test]
+    fn image_texture_errors_are_reported_at_their_values() {
+        let error = load("texture earth = image { path = \"missing.png\" }").unwrap_err();
+        assert_eq!(error.position, Some(Position{ line: 2, column: 32 }));
+
+        let error = load("texture earth = image { path = \"missing.png\", filter = \"cubic\" }").unwrap_err();
+        assert_eq!(error.position, Some(Position{ line: 2, column: 56 }));
+    }
+
+    #[test]
+    fn scenes_need_not_have_two_objects() {
+        assert!(load("").is_ok());
+        assert!(load("object plane { point = [0, 0, 0], normal = [0, 1, 0], material = dielectric { refractive_index = 1.5 } }").is_ok());
+
+        let source = "
+            material glass = dielectric { refractive_index = 1.5 }
+            geometry ground = [sphere { center = [0, 0, 0], radius = 1, material = glass }, plane { point = [0, 0, 0], normal = [0, 1, 0], material = glass }]
+        ";
+        let error = load(source).unwrap_err();
+        assert_eq!(error.position, Some(Position{ line: 4, column: 93 }));
+    }
+
+    #[test]
+    fn degenerate_transformations_are_errors() {
+        let transform = |field: &str| load(&format!("object transform {{ object = sphere {{ center = [0, 0, 0], radius = 1, material = dielectric {{ refractive_index = 1.5 }} }}, {} }}", field));
+
+        assert!(transform("look_at = [[0, 0, 0], [0, 0, 1], [0, 1, 0]]").is_ok());
+        let error = transform("look_at = [[0, 0, 0], [0, 0, 0], [0, 1, 0]]").unwrap_err();
+        assert_eq!(error.position, Some(Position{ line: 2, column: 132 }));
+        assert!(transform("look_at = [[0, 0, 0], [0, 3, 0], [0, 1, 0]]").is_err());
+        assert!(transform("matrix = [[1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 0, 0], [0, 0, 0, 1]]").is_err());
+        let error = transform("matrix = [[1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 1, 1]]").unwrap_err();
+        assert_eq!(error.position, Some(Position{ line: 2, column: 131 }));
+    }
+
+    #[test]
+    fn degenerate_cameras_are_errors() {
+        let camera = |fields: &str| {
+            let time_interval = Interval::new(0.0, 1.0).unwrap();
+            let source = format!("camera {{ {} }}\nobject sphere {{ center = [0, 0, 0], radius = 1, material = dielectric {{ refractive_index = 1.5 }} }}", fields);
+            scene_file::from_str(&source, 1.0, &time_interval, &bvh::Options::default()).map(|_| ())
+        };
+
+        assert!(camera("look_from = [0, 0, -10], look_at = [0, 0, 0], fov = 40").is_ok());
+        let error = camera("look_from = [0, 0, 0], look_at = [0, 0, 0], fov = 40").unwrap_err();
+        assert_eq!(error.position, Some(Position{ line: 1, column: 43 }));
+        let error = camera("look_from = [0, 0, -10], look_at = [0, 0, 0], up = [0, 0, 2], fov = 40").unwrap_err();
+        assert_eq!(error.position, Some(Position{ line: 1, column: 61 }));
+        let error = camera("look_from = [0, 10, 0], look_at = [0, 0, 0], fov = 40").unwrap_err();
+        assert_eq!(error.position, Some(Position{ line: 1, column: 44 }));
+        for fov in &["0", "180", "-30"] {
+            let error = camera(&format!("look_from = [0, 0, -10], look_at = [0, 0, 0], fov = {}", fov)).unwrap_err();
+            assert_eq!(error.position, Some(Position{ line: 1, column: 62 }));
+        }
+    }
+
+    #[test]
+    fn scene_without_camera_is_an_error() {
+        let time_interval = Interval::new(0.0, 1.0).unwrap();
+        assert!(scene_file::from_str("", 1.0, &time_interval, &bvh::Options::default()).is_err());
+    }
+}
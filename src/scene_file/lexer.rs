@@ -0,0 +1,209 @@
+use crate::{
+    scene_file::{
+        Error,
+        Position,
+    },
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind {
+    Identifier(String),
+    Number(f32),
+    String(String),
+    Equals,
+    Comma,
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    End,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub position: Position,
+}
+
+pub fn tokenize(source: &str) -> Result<Vec<Token>, Error> {
+    let mut lexer = Lexer {
+        chars: source.chars().collect(),
+        index: 0,
+        position: Position{ line: 1, column: 1 },
+    };
+    let mut tokens = Vec::new();
+    loop {
+        let token = lexer.next_token()?;
+        let is_end = token.kind == TokenKind::End;
+        tokens.push(token);
+        if is_end {
+            return Ok(tokens);
+        }
+    }
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    index: usize,
+    position: Position,
+}
+
+impl Lexer {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.index += 1;
+        if c == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        }
+        else {
+            self.position.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '#' {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.advance();
+                }
+            }
+            else if c.is_whitespace() {
+                self.advance();
+            }
+            else {
+                return;
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Token, Error> {
+        self.skip_whitespace_and_comments();
+        let position = self.position;
+        let make = |kind| Ok(Token{ kind, position });
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return make(TokenKind::End),
+        };
+
+        let single = match c {
+            '=' => Some(TokenKind::Equals),
+            ',' => Some(TokenKind::Comma),
+            '{' => Some(TokenKind::LeftBrace),
+            '}' => Some(TokenKind::RightBrace),
+            '[' => Some(TokenKind::LeftBracket),
+            ']' => Some(TokenKind::RightBracket),
+            _ => None,
+        };
+        if let Some(kind) = single {
+            self.advance();
+            return make(kind);
+        }
+
+        if c == '"' {
+            self.string().and_then(|s| make(TokenKind::String(s)))
+        }
+        else if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' {
+            self.number().and_then(|n| make(TokenKind::Number(n)))
+        }
+        else if c.is_alphabetic() || c == '_' {
+            let mut identifier = String::new();
+            while let Some(c) = self.peek().filter(|c| c.is_alphanumeric() || *c == '_') {
+                identifier.push(c);
+                self.advance();
+            }
+            make(TokenKind::Identifier(identifier))
+        }
+        else {
+            Err(Error::at(position, format!("unexpected character `{}`", c)))
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        let start = self.position;
+        self.advance();
+        let mut result = String::new();
+        loop {
+            match self.advance() {
+                Some('"') => return Ok(result),
+                Some('\\') => match self.advance() {
+                    Some(c @ '"') | Some(c @ '\\') => result.push(c),
+                    _ => return Err(Error::at(start, "invalid escape sequence in string".to_owned())),
+                },
+                Some('\n') | None => return Err(Error::at(start, "unterminated string".to_owned())),
+                Some(c) => result.push(c),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<f32, Error> {
+        let start = self.position;
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            let is_exponent_sign = (c == '-' || c == '+') && text.ends_with(['e', 'E']);
+            if c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || is_exponent_sign || text.is_empty() {
+                text.push(c);
+                self.advance();
+            }
+            else {
+                break;
+            }
+        }
+        text.parse()
+            .map_err(|_| Error::at(start, format!("invalid number `{}`", text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(source: &str) -> Vec<TokenKind> {
+        tokenize(source).unwrap()
+            .into_iter()
+            .map(|t| t.kind)
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_recognizes_all_token_kinds() {
+        assert_eq!(kinds("a = [1, -2.5e1] { \"s\" } # comment"), vec![
+            TokenKind::Identifier("a".to_owned()),
+            TokenKind::Equals,
+            TokenKind::LeftBracket,
+            TokenKind::Number(1.0),
+            TokenKind::Comma,
+            TokenKind::Number(-25.0),
+            TokenKind::RightBracket,
+            TokenKind::LeftBrace,
+            TokenKind::String("s".to_owned()),
+            TokenKind::RightBrace,
+            TokenKind::End,
+        ]);
+    }
+
+    #[test]
+    fn tokens_know_their_position() {
+        let tokens = tokenize("a\n  = 1").unwrap();
+
+        assert_eq!(tokens[1].position, Position{ line: 2, column: 3 });
+        assert_eq!(tokens[2].position, Position{ line: 2, column: 5 });
+    }
+
+    #[test]
+    fn invalid_number_is_reported_with_its_position() {
+        let error = tokenize("x = 1.2.3").unwrap_err();
+
+        assert_eq!(error.position, Some(Position{ line: 1, column: 5 }));
+    }
+
+    #[test]
+    fn unterminated_string_is_an_error() {
+        assert!(tokenize("path = \"abc\n").is_err());
+    }
+}
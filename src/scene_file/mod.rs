@@ -0,0 +1,128 @@
+//! A small declarative format for describing scenes.
+//!
+//! A scene file is a sequence of statements:
+//!
+//! ```text
+//! # comments run to the end of the line
+//! camera { look_from = [278, 278, -800], look_at = [278, 278, 0], fov = 40 }
+//! settings { diffuse_depth = 8, glossy_depth = 8, transmission_depth = 16, volume_depth = 32 }
+//! background environment_map { path = "sky.hdr", rotation = 90, intensity = 1.5 }
+//! texture grey = constant { colour = [0.73, 0.73, 0.73] }
+//! material white = lambertian { albedo = grey }
+//! object sphere { center = [0, 1, 0], radius = 1, material = white }
+//! object translate { offset = [0, 2, 0], object = parallelepiped { min = [0, 0, 0], max = [1, 1, 1], material = white } }
+//! ```
+//!
+//! Values are numbers, strings, lists (`[1, 2, 3]`), typed blocks (`name { field = value ... }`)
+//! or references to previously defined textures and materials, which are built once and shared.
+//! The optional `settings` statement gives the maximum number of bounces of each kind,
+//! command line options take precedence over it.
+//! The `background` is seen by rays which miss every object, it is black by default and can be
+//! `constant { colour }`, `gradient { bottom, top }` (the sky of the books without fields)
+//! or an `environment_map` read from a latitude-longitude `.hdr` or `.pfm` image.
+//! Textures can be read from `.png`, `.ppm`, `.hdr` or `.pfm` files with
+//! `image { path = "earth.png", filter = "bilinear", wrap = "repeat" }`, the filter can also be
+//! `"nearest"` and the wrap `"clamp"` or `"mirror"`. `.png` and `.ppm` colours are decoded as sRGB, `.hdr` and `.pfm` ones are linear.
+//! A `transform { object = ..., scale = [2, 1, 1], rotate = [[1, 0, 0], 30], translate = [0, 1, 0] }`
+//! applies its fields in order (nest transforms to repeat one), `scale` can also be a single number and
+//! `look_at = [from, to, up]` or a row by row affine 4x4 `matrix`, with a bottom row of `[0, 0, 0, 1]`, can be used as well.
+//! Besides the axis aligned rectangles there are `quad { corner, u, v, material }`, the parallelogram spanned
+//! by the edges `u` and `v` from `corner`, `disk { center, normal, radius, material }` and
+//! `triangle { vertices, normals, uvs, material }`, all of which can be sampled as lights.
+//! The infinite `plane { point, normal, material }` repeats its texture every unit and is tested by every ray.
+//! The surfaces of revolution around the y axis are `cylinder { radius, height, ends = "open", material }`,
+//! which can also have `"closed"` ends, `cone { radius, height, material }` with its apex at the height,
+//! the bowl `paraboloid { radius, height, material }` and `torus { major_radius, minor_radius, material }`.
+//! Each can be cut down to a `sweep` in degrees around the axis from x towards z and to a range of `heights`.
+//! Geometry placed many times is defined once, as an object or a list of objects in its own BVH, with
+//! `geometry tree = [cylinder { ... }, cone { ... }]` and placed by `instance { geometry = tree, translate = [4, 0, 2] }`,
+//! which takes the transformation fields of `transform` and an optional `material` replacing the geometry's own.
+//! Any object can be motion blurred with
+//! `motion { object = ..., keyframes = [keyframe { time = 0 }, keyframe { time = 1, scale = 2, rotate = [[0, 1, 0], 90], translate = [0, 1, 0] }] }`,
+//! the poses between keyframes are interpolated over the time of the rays.
+//! Solids (spheres, parallelepipeds, and transformed or combined solids) can be combined with
+//! `union { left = ..., right = ... }`, `intersection` or `difference`, which cuts `right` out of `left`.
+//! Signed distance fields are sphere traced inside a declared box with
+//! `sdf { field = torus { major_radius = 1, minor_radius = 0.2 }, min = [-1.2, -0.2, -1.2], max = [1.2, 0.2, 1.2], material = white }`.
+//! The fields are `sphere`, `torus`, `rounded_box`, `capsule` and `cylinder` combined with `translate`, `smooth_union`,
+//! `subtraction`, `repetition` and `twist`, which needs a `lipschitz_bound` above 1 on the `sdf` object.
+
+mod lexer;
+mod parser;
+mod builder;
+
+use crate::{
+    math::{
+        Interval,
+    },
+    scene::{
+        Scene,
+    },
+    camera::{
+        Camera,
+    },
+    render::{
+        MaxDepths,
+    },
+    bvh,
+};
+use std::{
+    fmt,
+    path::Path,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub position: Option<Position>,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new(message: String) -> Self {
+        Self {
+            position: None,
+            message,
+        }
+    }
+
+    pub fn at(position: Position, message: String) -> Self {
+        Self {
+            position: Some(position),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.position {
+            Some(Position{ line, column }) => write!(f, "line {}, column {}: {}", line, column, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+pub fn load(path: &Path, aspect: f32, time_interval: &Interval<f32>, bvh_options: &bvh::Options) -> Result<(Scene, Camera, MaxDepths), String> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| format!("Couldn't read {}: {}", path.display(), e))?;
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    parse_and_build(&source, directory, aspect, time_interval, bvh_options)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// relative file paths in the source are resolved against the working directory
+#[cfg(test)]
+pub fn from_str(source: &str, aspect: f32, time_interval: &Interval<f32>, bvh_options: &bvh::Options) -> Result<(Scene, Camera, MaxDepths), Error> {
+    parse_and_build(source, Path::new(""), aspect, time_interval, bvh_options)
+}
+
+fn parse_and_build(source: &str, directory: &Path, aspect: f32, time_interval: &Interval<f32>, bvh_options: &bvh::Options) -> Result<(Scene, Camera, MaxDepths), Error> {
+    let statements = parser::parse(source)?;
+    builder::build(&statements, directory, aspect, time_interval, bvh_options)
+}
@@ -0,0 +1,341 @@
+use crate::{
+    math::{
+        Vec3,
+        Point3,
+        vec3,
+    },
+    scene_file::{
+        Error,
+        Position,
+        lexer::{
+            self,
+            Token,
+            TokenKind,
+        },
+    },
+};
+
+#[derive(Clone, Debug)]
+pub enum ValueKind {
+    Number(f32),
+    String(String),
+    List(Vec<Value>),
+    Block(Block),
+    Identifier(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct Value {
+    pub kind: ValueKind,
+    pub position: Position,
+}
+
+// `name { field = value ... }`
+#[derive(Clone, Debug)]
+pub struct Block {
+    pub name: String,
+    pub fields: Vec<Field>,
+    pub position: Position,
+}
+
+#[derive(Clone, Debug)]
+pub struct Field {
+    pub name: String,
+    pub value: Value,
+    pub position: Position,
+}
+
+#[derive(Clone, Debug)]
+pub struct Definition {
+    pub name: String,
+    pub value: Value,
+    pub position: Position,
+}
+
+#[derive(Clone, Debug)]
+pub enum Statement {
+    Camera(Block),
+    Settings(Block),
+    Background(Value),
+    Texture(Definition),
+    Material(Definition),
+    Geometry(Definition),
+    Object(Value),
+}
+
+impl Value {
+    pub fn as_number(&self) -> Result<f32, Error> {
+        match self.kind {
+            ValueKind::Number(n) => Ok(n),
+            _ => Err(Error::at(self.position, "expected a number".to_owned())),
+        }
+    }
+
+    pub fn as_string(&self) -> Result<&str, Error> {
+        match &self.kind {
+            ValueKind::String(s) => Ok(s),
+            _ => Err(Error::at(self.position, "expected a string".to_owned())),
+        }
+    }
+
+    pub fn as_list(&self, count: usize) -> Result<&[Value], Error> {
+        match &self.kind {
+            ValueKind::List(values) if values.len() == count => Ok(values),
+            _ => Err(Error::at(self.position, format!("expected a list of {} values", count))),
+        }
+    }
+
+    pub fn as_numbers(&self, count: usize) -> Result<Vec<f32>, Error> {
+        let error = || Error::at(self.position, format!("expected a list of {} numbers", count));
+        match &self.kind {
+            ValueKind::List(values) if values.len() == count => {
+                values.iter()
+                    .map(|v| v.as_number().map_err(|_| error()))
+                    .collect()
+            },
+            _ => Err(error()),
+        }
+    }
+
+    pub fn as_vec3(&self) -> Result<Vec3, Error> {
+        self.as_numbers(3).map(|n| vec3(n[0], n[1], n[2]))
+    }
+
+    pub fn as_point3(&self) -> Result<Point3, Error> {
+        self.as_numbers(3).map(|n| Point3::new(n[0], n[1], n[2]))
+    }
+
+    // `[min, max]` with min < max
+    pub fn as_range(&self) -> Result<(f32, f32), Error> {
+        let n = self.as_numbers(2)?;
+        if n[0] < n[1] {
+            Ok((n[0], n[1]))
+        }
+        else {
+            Err(Error::at(self.position, "expected a range [min, max] with min < max".to_owned()))
+        }
+    }
+}
+
+impl Block {
+    pub fn field(&self, name: &str) -> Result<&Value, Error> {
+        self.optional_field(name)
+            .ok_or_else(|| Error::at(self.position, format!("`{}` is missing field `{}`", self.name, name)))
+    }
+
+    pub fn optional_field(&self, name: &str) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find(|f| f.name == name)
+            .map(|f| &f.value)
+    }
+
+    pub fn check_fields(&self, allowed: &[&str]) -> Result<(), Error> {
+        match self.fields.iter().find(|f| !allowed.contains(&f.name.as_str())) {
+            Some(f) => Err(Error::at(f.position, format!("unknown field `{}` in `{}`", f.name, self.name))),
+            None => Ok(()),
+        }
+    }
+}
+
+pub fn parse(source: &str) -> Result<Vec<Statement>, Error> {
+    let mut parser = Parser {
+        tokens: lexer::tokenize(source)?,
+        index: 0,
+    };
+    let mut statements = Vec::new();
+    while parser.peek().kind != TokenKind::End {
+        statements.push(parser.statement()?);
+    }
+    Ok(statements)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index]
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.index].clone();
+        if token.kind != TokenKind::End {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, kind: TokenKind, what: &str) -> Result<Token, Error> {
+        let token = self.next();
+        if token.kind == kind {
+            Ok(token)
+        }
+        else {
+            Err(unexpected(&token, what))
+        }
+    }
+
+    fn identifier(&mut self, what: &str) -> Result<(String, Position), Error> {
+        let token = self.next();
+        match token.kind {
+            TokenKind::Identifier(name) => Ok((name, token.position)),
+            _ => Err(unexpected(&token, what)),
+        }
+    }
+
+    fn statement(&mut self) -> Result<Statement, Error> {
+        let (keyword, position) = self.identifier("`camera`, `settings`, `background`, `texture`, `material`, `geometry` or `object`")?;
+        match keyword.as_str() {
+            "camera" => {
+                let fields = self.fields()?;
+                Ok(Statement::Camera(Block{ name: keyword, fields, position }))
+            },
+            "settings" => {
+                let fields = self.fields()?;
+                Ok(Statement::Settings(Block{ name: keyword, fields, position }))
+            },
+            "background" => self.value().map(Statement::Background),
+            "texture" => self.definition().map(Statement::Texture),
+            "material" => self.definition().map(Statement::Material),
+            "geometry" => self.definition().map(Statement::Geometry),
+            "object" => self.value().map(Statement::Object),
+            _ => Err(Error::at(position, format!("unknown statement `{}`", keyword))),
+        }
+    }
+
+    fn definition(&mut self) -> Result<Definition, Error> {
+        let (name, position) = self.identifier("a name")?;
+        self.expect(TokenKind::Equals, "`=`")?;
+        let value = self.value()?;
+        Ok(Definition{ name, value, position })
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        let token = self.next();
+        let position = token.position;
+        let kind = match token.kind {
+            TokenKind::Number(n) => ValueKind::Number(n),
+            TokenKind::String(s) => ValueKind::String(s),
+            TokenKind::LeftBracket => ValueKind::List(self.list()?),
+            TokenKind::Identifier(name) => {
+                if self.peek().kind == TokenKind::LeftBrace {
+                    let fields = self.fields()?;
+                    ValueKind::Block(Block{ name, fields, position })
+                }
+                else {
+                    ValueKind::Identifier(name)
+                }
+            },
+            _ => return Err(unexpected(&token, "a value")),
+        };
+        Ok(Value{ kind, position })
+    }
+
+    // the opening `[` is already consumed
+    fn list(&mut self) -> Result<Vec<Value>, Error> {
+        let mut values = Vec::new();
+        loop {
+            if self.peek().kind == TokenKind::RightBracket {
+                self.next();
+                return Ok(values);
+            }
+            values.push(self.value()?);
+            match self.peek().kind {
+                TokenKind::Comma => { self.next(); },
+                TokenKind::RightBracket => (),
+                _ => return Err(unexpected(self.peek(), "`,` or `]`")),
+            }
+        }
+    }
+
+    // `{ name = value ... }`, fields may be separated by commas
+    fn fields(&mut self) -> Result<Vec<Field>, Error> {
+        self.expect(TokenKind::LeftBrace, "`{`")?;
+        let mut fields = Vec::new();
+        loop {
+            match self.peek().kind {
+                TokenKind::RightBrace => {
+                    self.next();
+                    return Ok(fields);
+                },
+                TokenKind::Comma if !fields.is_empty() => { self.next(); },
+                _ => {
+                    let (name, position) = self.identifier("a field name or `}`")?;
+                    if let Some(field) = fields.iter().find(|f: &&Field| f.name == name) {
+                        return Err(Error::at(position, format!(
+                            "field `{}` is already set at line {}", name, field.position.line)));
+                    }
+                    self.expect(TokenKind::Equals, "`=`")?;
+                    let value = self.value()?;
+                    fields.push(Field{ name, value, position });
+                },
+            }
+        }
+    }
+}
+
+fn unexpected(token: &Token, expected: &str) -> Error {
+    let found = match &token.kind {
+        TokenKind::Identifier(name) => format!("`{}`", name),
+        TokenKind::Number(n) => format!("number {}", n),
+        TokenKind::String(s) => format!("string \"{}\"", s),
+        TokenKind::Equals => "`=`".to_owned(),
+        TokenKind::Comma => "`,`".to_owned(),
+        TokenKind::LeftBrace => "`{`".to_owned(),
+        TokenKind::RightBrace => "`}`".to_owned(),
+        TokenKind::LeftBracket => "`[`".to_owned(),
+        TokenKind::RightBracket => "`]`".to_owned(),
+        TokenKind::End => "end of file".to_owned(),
+    };
+    Error::at(token.position, format!("expected {}, found {}", expected, found))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_nested_blocks() {
+        let source = "
+            material white = lambertian { albedo = [0.7, 0.7, 0.7] }
+            object translate {
+                offset = [1, 0, 0],
+                object = sphere { center = [0, 0, 0] radius = 1 material = white }
+            }
+        ";
+
+        let statements = parse(source).unwrap();
+
+        assert_eq!(statements.len(), 2);
+        match &statements[1] {
+            Statement::Object(Value{ kind: ValueKind::Block(block), .. }) => {
+                assert_eq!(block.name, "translate");
+                assert_eq!(block.fields.len(), 2);
+                assert_eq!(block.position, Position{ line: 3, column: 20 });
+            },
+            _ => panic!("object statement expected"),
+        }
+    }
+
+    #[test]
+    fn missing_equals_is_reported_with_its_position() {
+        let error = parse("camera {\n  fov 40\n}").unwrap_err();
+
+        assert_eq!(error.position, Some(Position{ line: 2, column: 7 }));
+    }
+
+    #[test]
+    fn unterminated_block_is_an_error() {
+        let error = parse("object sphere { radius = 1").unwrap_err();
+
+        assert_eq!(error.position, Some(Position{ line: 1, column: 27 }));
+    }
+
+    #[test]
+    fn repeated_field_is_an_error() {
+        assert!(parse("object sphere { radius = 1, radius = 2 }").is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{
+    math::{
+        Point3,
+        Vec3,
+    },
+    sdf::{
+        Sdf,
+    },
+};
+
+pub struct Translate {
+    pub sdf: Box<dyn Sdf>,
+    pub offset: Vec3,
+}
+
+impl Sdf for Translate {
+    fn distance(&self, p: &Point3) -> f32 {
+        self.sdf.distance(&(p - self.offset))
+    }
+}
+
+// The union of two shapes blended together within `smoothness` of where they meet.
+pub struct SmoothUnion {
+    pub a: Box<dyn Sdf>,
+    pub b: Box<dyn Sdf>,
+    pub smoothness: f32,
+}
+
+impl Sdf for SmoothUnion {
+    fn distance(&self, p: &Point3) -> f32 {
+        let (d1, d2) = (self.a.distance(p), self.b.distance(p));
+        let k = self.smoothness.max(f32::EPSILON);
+        let h = (0.5 + 0.5 * (d2 - d1) / k).clamp(0.0, 1.0);
+        d2 + h * (d1 - d2) - k * h * (1.0 - h)
+    }
+}
+
+// `a` with `b` cut out of it.
+pub struct Subtraction {
+    pub a: Box<dyn Sdf>,
+    pub b: Box<dyn Sdf>,
+}
+
+impl Sdf for Subtraction {
+    fn distance(&self, p: &Point3) -> f32 {
+        self.a.distance(p).max(-self.b.distance(p))
+    }
+}
+
+// Copies of a shape every `period` along each axis, a zero period doesn't repeat along its axis.
+// The shape should fit in one cell for the distance to stay a bound.
+pub struct Repetition {
+    pub sdf: Box<dyn Sdf>,
+    pub period: Vec3,
+}
+
+impl Sdf for Repetition {
+    fn distance(&self, p: &Point3) -> f32 {
+        let mut q = *p;
+        for d in 0..3 {
+            let period = self.period[d];
+            if period > 0.0 {
+                q[d] -= period * (q[d] / period).round();
+            }
+        }
+        self.sdf.distance(&q)
+    }
+}
+
+// Turns a shape around the y axis by `rate` radians per unit of height.
+// Twisting stretches distances, so the shape must declare a Lipschitz bound above 1 to be traced.
+pub struct Twist {
+    pub sdf: Box<dyn Sdf>,
+    pub rate: f32,
+}
+
+impl Sdf for Twist {
+    fn distance(&self, p: &Point3) -> f32 {
+        let (sin, cos) = (-self.rate * p.y).sin_cos();
+        let q = Point3::new(cos * p.x - sin * p.z, p.y, sin * p.x + cos * p.z);
+        self.sdf.distance(&q)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        math,
+        sdf::{
+            Sphere,
+            RoundedBox,
+        },
+    };
+
+    fn assert_near(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    fn sphere(x: f32) -> Box<dyn Sdf> {
+        Box::new(Translate { sdf: Box::new(Sphere { radius: 1.0 }), offset: math::vec3(x, 0.0, 0.0) })
+    }
+
+    #[test]
+    fn smooth_union_blends_only_near_both_shapes() {
+        let union = SmoothUnion { a: sphere(-1.0), b: sphere(1.0), smoothness: 0.5 };
+
+        // the blend fills the gap where the spheres touch
+        assert!(union.distance(&Point3::new(0.0, 0.2, 0.0)) < sphere(1.0).distance(&Point3::new(0.0, 0.2, 0.0)));
+        assert_near(union.distance(&Point3::new(-3.0, 0.0, 0.0)), 1.0);
+    }
+
+    #[test]
+    fn subtraction_and_repetition() {
+        let hollow = Subtraction { a: Box::new(Sphere { radius: 2.0 }), b: Box::new(Sphere { radius: 1.0 }) };
+        assert_near(hollow.distance(&Point3::new(0.0, 0.0, 0.0)), 1.0);
+        assert_near(hollow.distance(&Point3::new(1.5, 0.0, 0.0)), -0.5);
+
+        let repeated = Repetition { sdf: Box::new(Sphere { radius: 0.5 }), period: math::vec3(4.0, 0.0, 0.0) };
+        assert_near(repeated.distance(&Point3::new(8.0, 0.0, 0.0)), -0.5);
+        assert_near(repeated.distance(&Point3::new(8.0, 4.0, 0.0)), 3.5);
+    }
+
+    #[test]
+    fn twist_turns_the_shape_with_height() {
+        let slab = RoundedBox { half_extents: math::vec3(2.0, 10.0, 0.1), radius: 0.0 };
+        let twisted = Twist { sdf: Box::new(slab), rate: std::f32::consts::FRAC_PI_2 };
+
+        // a quarter turn one unit up
+        assert!(twisted.distance(&Point3::new(1.5, 0.0, 0.0)) < 0.0);
+        assert!(twisted.distance(&Point3::new(0.0, 1.0, 1.5)) < 0.0);
+        assert!(twisted.distance(&Point3::new(1.5, 1.0, 0.0)) > 0.0);
+    }
+}
@@ -0,0 +1,36 @@
+mod primitives;
+mod combinators;
+
+pub use primitives::{
+    Sphere,
+    Torus,
+    RoundedBox,
+    Capsule,
+    Cylinder,
+};
+pub use combinators::{
+    Translate,
+    SmoothUnion,
+    Subtraction,
+    Repetition,
+    Twist,
+};
+
+use crate::{
+    math::{
+        Point3,
+    },
+};
+
+// A signed distance field, negative inside the shape it describes.
+// The distance must not be more than the real distance to the surface
+// (or a declared multiple of it, see `SdfShape`) for sphere tracing to work.
+pub trait Sdf: Send + Sync {
+    fn distance(&self, p: &Point3) -> f32;
+}
+
+impl<F: Fn(&Point3) -> f32 + Send + Sync> Sdf for F {
+    fn distance(&self, p: &Point3) -> f32 {
+        self(p)
+    }
+}
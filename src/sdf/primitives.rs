@@ -0,0 +1,115 @@
+// Distance fields of shapes centred at the origin, from https://iquilezles.org/articles/distfunctions/
+
+use crate::{
+    math::{
+        self,
+        Point3,
+        Vec3,
+        EuclideanSpace,
+        InnerSpace,
+    },
+    sdf::{
+        Sdf,
+    },
+};
+
+pub struct Sphere {
+    pub radius: f32,
+}
+
+impl Sdf for Sphere {
+    fn distance(&self, p: &Point3) -> f32 {
+        p.to_vec().magnitude() - self.radius
+    }
+}
+
+// A ring around the y axis.
+pub struct Torus {
+    // from the centre to the middle of the tube
+    pub major_radius: f32,
+    // of the tube
+    pub minor_radius: f32,
+}
+
+impl Sdf for Torus {
+    fn distance(&self, p: &Point3) -> f32 {
+        let ring = (p.x * p.x + p.z * p.z).sqrt() - self.major_radius;
+        (ring * ring + p.y * p.y).sqrt() - self.minor_radius
+    }
+}
+
+// A box with its edges rounded by `radius`, which is included in the half extents.
+pub struct RoundedBox {
+    pub half_extents: Vec3,
+    pub radius: f32,
+}
+
+impl Sdf for RoundedBox {
+    fn distance(&self, p: &Point3) -> f32 {
+        let q = p.to_vec().map(f32::abs) - self.half_extents + math::vec3(self.radius, self.radius, self.radius);
+        let outside = q.map(|c| c.max(0.0)).magnitude();
+        let inside = q.x.max(q.y).max(q.z).min(0.0);
+        outside + inside - self.radius
+    }
+}
+
+// The points within `radius` of the segment from `a` to `b`.
+pub struct Capsule {
+    pub a: Point3,
+    pub b: Point3,
+    pub radius: f32,
+}
+
+impl Sdf for Capsule {
+    fn distance(&self, p: &Point3) -> f32 {
+        let (pa, ba) = (p - self.a, self.b - self.a);
+        let h = (pa.dot(ba) / ba.magnitude2()).clamp(0.0, 1.0);
+        (pa - ba * h).magnitude() - self.radius
+    }
+}
+
+// A capped cylinder along the y axis.
+pub struct Cylinder {
+    pub radius: f32,
+    pub half_height: f32,
+}
+
+impl Sdf for Cylinder {
+    fn distance(&self, p: &Point3) -> f32 {
+        let d = (
+            (p.x * p.x + p.z * p.z).sqrt() - self.radius,
+            p.y.abs() - self.half_height,
+        );
+        let outside = (d.0.max(0.0).powi(2) + d.1.max(0.0).powi(2)).sqrt();
+        d.0.max(d.1).min(0.0) + outside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn primitives_give_distances_to_their_surfaces() {
+        let torus = Torus { major_radius: 2.0, minor_radius: 0.5 };
+        assert_near(torus.distance(&Point3::new(2.0, 0.0, 0.0)), -0.5);
+        assert_near(torus.distance(&Point3::new(0.0, 0.0, 0.0)), 1.5);
+
+        let rounded_box = RoundedBox { half_extents: math::vec3(1.0, 2.0, 3.0), radius: 0.25 };
+        assert_near(rounded_box.distance(&Point3::new(0.0, 3.0, 0.0)), 1.0);
+        assert_near(rounded_box.distance(&Point3::new(0.0, 0.0, 0.0)), -1.0);
+
+        let capsule = Capsule { a: Point3::new(0.0, 0.0, 0.0), b: Point3::new(0.0, 2.0, 0.0), radius: 0.5 };
+        assert_near(capsule.distance(&Point3::new(1.0, 1.0, 0.0)), 0.5);
+        assert_near(capsule.distance(&Point3::new(0.0, 3.0, 0.0)), 0.5);
+
+        let cylinder = Cylinder { radius: 1.0, half_height: 2.0 };
+        assert_near(cylinder.distance(&Point3::new(3.0, 0.0, 0.0)), 2.0);
+        assert_near(cylinder.distance(&Point3::new(0.0, 0.0, 0.0)), -1.0);
+        assert_near(cylinder.distance(&Point3::new(4.0, 6.0, 0.0)), 5.0);
+    }
+}
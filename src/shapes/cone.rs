@@ -0,0 +1,137 @@
+use crate::{
+    math::{
+        self,
+        Interval,
+        InnerSpace,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+    },
+    materials::{
+        Material,
+    },
+    aabb::{
+        AABB,
+    },
+    textures::{
+        TextureCoordinates,
+    },
+    shapes::{
+        revolution::{
+            self,
+            Limits,
+        },
+    },
+};
+use std::sync::Arc;
+
+// An open cone around the y axis with its base on the xz plane and its apex above it.
+pub struct Cone {
+    radius: f32,
+    height: f32,
+    limits: Limits,
+    material: Arc<dyn Material>,
+}
+
+impl Cone {
+    pub fn new(radius: f32, height: f32, material: Arc<dyn Material>) -> Self {
+        Self {
+            radius,
+            height,
+            limits: Limits::new(0.0, height),
+            material,
+        }
+    }
+
+    // Keeps the part of the cone up to the angle from the x axis towards z.
+    pub fn with_sweep(self, degrees: f32) -> Self {
+        Self {
+            limits: self.limits.with_sweep(degrees),
+            ..self
+        }
+    }
+
+    // Cutting below the apex gives a frustum.
+    pub fn with_height_limits(self, y_min: f32, y_max: f32) -> Self {
+        Self {
+            limits: self.limits.with_heights(y_min, y_max),
+            ..self
+        }
+    }
+}
+
+impl Hittable for Cone {
+    // Solves x^2 + z^2 = k (h - y)^2, the other nappe above the apex is outside the limits.
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let (o, d) = (ray.origin, ray.direction);
+        let k = (self.radius / self.height) * (self.radius / self.height);
+        let w = self.height - o.y;
+        let a = d.x * d.x + d.z * d.z - k * d.y * d.y;
+        let b = 2.0 * (o.x * d.x + o.z * d.z + k * w * d.y);
+        let c = o.x * o.x + o.z * o.z - k * w * w;
+        let (t0, t1) = math::solve_quadratic(a, b, c)?;
+        let (t, hit_point, phi) = revolution::first_hit(ray, hit_interval, &[t0, t1], &self.limits)?;
+
+        let normal = math::vec3(hit_point.x, k * (self.height - hit_point.y), hit_point.z).normalize();
+        Some(HitRecord {
+            t,
+            hit_point,
+            normal,
+            material: self.material.as_ref(),
+            uv: TextureCoordinates{ u: self.limits.u(phi), v: self.limits.v(hit_point.y) },
+        })
+    }
+
+    // the cone is widest at its lowest point
+    fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
+        let radius = self.radius * (self.height - self.limits.y_min) / self.height;
+        Some(self.limits.bounding_box(radius))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_helpers::hit_interval,
+        materials::Dielectric,
+        math::Point3,
+    };
+
+    fn cone() -> Cone {
+        Cone::new(1.0, 2.0, Arc::new(Dielectric::new(1.5)))
+    }
+
+    #[test]
+    fn ray_hits_the_side_at_the_narrowing_radius() {
+        let ray = Ray::new(Point3::new(-5.0, 1.0, 0.0), math::vec3(1.0, 0.0, 0.0), 0.0);
+
+        let cone = cone();
+        let hit = cone.hit(&ray, &hit_interval()).unwrap();
+
+        // half way up the radius is halved
+        assert!((hit.t - 4.5).abs() < 1e-5);
+        assert!((hit.normal - math::vec3(-2.0, 1.0, 0.0).normalize()).magnitude() < 1e-5);
+        assert!((hit.uv.v - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn other_nappe_is_not_hit() {
+        let ray = Ray::new(Point3::new(-5.0, 3.0, 0.0), math::vec3(1.0, 0.0, 0.0), 0.0);
+
+        assert!(cone().hit(&ray, &hit_interval()).is_none());
+    }
+
+    #[test]
+    fn frustum_is_open_at_the_top() {
+        let frustum = cone().with_height_limits(0.0, 1.0);
+        let ray = Ray::new(Point3::new(0.0, 5.0, 0.0), math::vec3(0.0, -1.0, 0.0), 0.0);
+        assert!(frustum.hit(&ray, &hit_interval()).is_none());
+
+        let bbox = frustum.with_height_limits(0.5, 1.0).bounding_box(&hit_interval()).unwrap();
+        assert!((bbox.max.x - 0.75).abs() < 1e-5);
+        assert_eq!((bbox.min.y, bbox.max.y), (0.5, 1.0));
+    }
+}
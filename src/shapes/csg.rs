@@ -0,0 +1,254 @@
+use crate::{
+    math::{
+        Point3,
+        Interval,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+        Span,
+    },
+    aabb::{
+        self,
+        AABB,
+    },
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Operation {
+    Union,
+    Intersection,
+    // the left solid without the right one
+    Difference,
+}
+
+impl Operation {
+    fn contains(self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            Operation::Union => in_left || in_right,
+            Operation::Intersection => in_left && in_right,
+            Operation::Difference => in_left && !in_right,
+        }
+    }
+}
+
+// Constructive solid geometry, combines two solids into one.
+// Every surface of the result keeps the material of the solid it comes from.
+pub struct Csg {
+    operation: Operation,
+    left: Box<dyn Hittable>,
+    right: Box<dyn Hittable>,
+}
+
+impl Csg {
+    pub fn new(operation: Operation, left: Box<dyn Hittable>, right: Box<dyn Hittable>) -> Result<Self, String> {
+        if !left.is_solid() || !right.is_solid() {
+            return Err("only solids can be combined".to_owned());
+        }
+        Ok(Self {
+            operation,
+            left,
+            right,
+        })
+    }
+}
+
+// A surface the ray crosses, into or out of one of the solids.
+struct Crossing<'a> {
+    hit_record: HitRecord<'a>,
+    from_left: bool,
+    entering: bool,
+}
+
+fn crossings<'a>(hittable: &'a dyn Hittable, ray: &Ray, from_left: bool) -> Vec<Crossing<'a>> {
+    hittable.spans(ray)
+        .into_iter()
+        .flat_map(|span| vec![
+            Crossing { hit_record: span.enter, from_left, entering: true },
+            Crossing { hit_record: span.exit, from_left, entering: false },
+        ])
+        .collect()
+}
+
+impl Hittable for Csg {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let inside = |t: f32| hit_interval.min() < t && t < hit_interval.max();
+        self.spans(ray)
+            .into_iter()
+            .flat_map(|span| vec![span.enter, span.exit])
+            .find(|hit_record| inside(hit_record.t))
+    }
+
+    fn bounding_box(&self, time_interval: &Interval<f32>) -> Option<AABB> {
+        let left = self.left.bounding_box(time_interval)?;
+        let right = self.right.bounding_box(time_interval)?;
+        let bbox = match self.operation {
+            Operation::Union => aabb::surrounding_box(&left, &right),
+            Operation::Intersection => {
+                let min = Point3::new(left.min.x.max(right.min.x), left.min.y.max(right.min.y), left.min.z.max(right.min.z));
+                let max = Point3::new(left.max.x.min(right.max.x), left.max.y.min(right.max.y), left.max.z.min(right.max.z));
+                // an empty intersection gets a flat box
+                AABB {
+                    min,
+                    max: Point3::new(max.x.max(min.x), max.y.max(min.y), max.z.max(min.z)),
+                }
+            },
+            Operation::Difference => left,
+        };
+        Some(bbox)
+    }
+
+    // Walks the crossings of both solids in order and keeps those where the ray
+    // goes into or out of the result.
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        let mut all = crossings(self.left.as_ref(), ray, true);
+        all.extend(crossings(self.right.as_ref(), ray, false));
+        all.sort_by(|a, b| a.hit_record.t.partial_cmp(&b.hit_record.t).unwrap());
+
+        let mut spans = Vec::new();
+        let (mut in_left, mut in_right) = (false, false);
+        let mut enter = None;
+        for crossing in all {
+            let was_inside = self.operation.contains(in_left, in_right);
+            if crossing.from_left {
+                in_left = crossing.entering;
+            }
+            else {
+                in_right = crossing.entering;
+            }
+            let is_inside = self.operation.contains(in_left, in_right);
+
+            let mut hit_record = crossing.hit_record;
+            // the right solid is turned inside out where it is cut away from the left one
+            if self.operation == Operation::Difference && !crossing.from_left {
+                hit_record.normal = -hit_record.normal;
+            }
+            if !was_inside && is_inside {
+                enter = Some(hit_record);
+            }
+            else if was_inside && !is_inside {
+                if let Some(enter) = enter.take() {
+                    spans.push(Span { enter, exit: hit_record });
+                }
+            }
+        }
+        spans
+    }
+
+    fn is_solid(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        materials::{
+            Material,
+            Dielectric,
+            Metal,
+        },
+        shapes::{
+            Sphere,
+            Parallelepiped,
+            XYRectangle,
+        },
+        transformations::{
+            Translation,
+            YRotation,
+        },
+        math::{
+            vec3,
+            Vec3,
+        },
+    };
+    use std::sync::Arc;
+
+    fn sphere(x: f32, radius: f32) -> Box<dyn Hittable> {
+        Box::new(Sphere::new(Point3::new(x, 0.0, 0.0), radius, Arc::new(Dielectric::new(1.5))))
+    }
+
+    fn cube() -> Box<dyn Hittable> {
+        let material = Arc::new(Metal::new(vec3(0.5, 0.5, 0.5), 0.0));
+        Box::new(Parallelepiped::new(&Point3::new(-1.0, -1.0, -1.0), &Point3::new(1.0, 1.0, 1.0), material))
+    }
+
+    // a ray along the x axis from x = -5
+    fn ray() -> Ray {
+        Ray::new(Point3::new(-5.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0), 0.0)
+    }
+
+    fn boundaries(csg: &Csg) -> Vec<(f32, Vec3)> {
+        csg.spans(&ray())
+            .iter()
+            .flat_map(|span| vec![span.enter, span.exit])
+            .map(|hit_record| (hit_record.hit_point.x, hit_record.normal))
+            .collect()
+    }
+
+    fn assert_boundaries(csg: &Csg, expected: &[(f32, f32)]) {
+        let boundaries = boundaries(csg);
+        assert_eq!(boundaries.len(), expected.len());
+        for ((x, normal), (expected_x, expected_normal_x)) in boundaries.iter().zip(expected) {
+            assert!((x - expected_x).abs() < 1e-5, "{} != {}", x, expected_x);
+            assert!((normal.x - expected_normal_x).abs() < 1e-5, "{:?}", normal);
+        }
+    }
+
+    #[test]
+    fn union_of_overlapping_spheres_is_one_span() {
+        let union = Csg::new(Operation::Union, sphere(0.0, 1.0), sphere(1.5, 1.0)).unwrap();
+
+        assert_boundaries(&union, &[(-1.0, -1.0), (2.5, 1.0)]);
+    }
+
+    #[test]
+    fn intersection_of_spheres_is_a_lens() {
+        let lens = Csg::new(Operation::Intersection, sphere(0.0, 1.0), sphere(1.5, 1.0)).unwrap();
+
+        assert_boundaries(&lens, &[(0.5, -1.0), (1.0, 1.0)]);
+        let bbox = lens.bounding_box(&Interval::new(0.0, 1.0).unwrap()).unwrap();
+        assert_eq!((bbox.min.x, bbox.max.x), (0.5, 1.0));
+    }
+
+    #[test]
+    fn difference_cuts_a_hole_with_the_material_of_the_cutter() {
+        let cutter = sphere(0.0, 0.5);
+        let cutter_material = cutter.hit(&ray(), &Interval::new(0.0, 100.0).unwrap()).unwrap().material as *const dyn Material as *const u8;
+        let holed = Csg::new(Operation::Difference, cube(), cutter).unwrap();
+
+        // the walls of the hole face into it
+        assert_boundaries(&holed, &[(-1.0, -1.0), (-0.5, 1.0), (0.5, -1.0), (1.0, 1.0)]);
+        let hit_interval = Interval::new(4.2, 100.0).unwrap();
+        let hit_record = holed.hit(&ray(), &hit_interval).unwrap();
+        assert!((hit_record.hit_point.x + 0.5).abs() < 1e-5);
+        assert_eq!(hit_record.material as *const dyn Material as *const u8, cutter_material);
+        // a ray which misses the hole only crosses the cube
+        let ray = Ray::new(Point3::new(-5.0, 0.8, 0.0), vec3(1.0, 0.0, 0.0), 0.0);
+        assert_eq!(holed.spans(&ray).len(), 1);
+    }
+
+    #[test]
+    fn translated_and_rotated_solids_can_be_combined() {
+        let cutter = Box::new(Translation::on(sphere(0.0, 0.5), vec3(1.0, 0.0, 0.0)));
+        let notched = Csg::new(Operation::Difference, cube(), cutter).unwrap();
+        assert_boundaries(&notched, &[(-1.0, -1.0), (0.5, 1.0)]);
+
+        // the corners of the turned cube are on the x axis
+        let diamond = Box::new(YRotation::from_degrees(cube(), 45.0));
+        let clipped = Csg::new(Operation::Intersection, diamond, sphere(0.0, 2.0)).unwrap();
+        let corner = 2.0f32.sqrt();
+        assert_boundaries(&clipped, &[(-corner, -corner / 2.0), (corner, corner / 2.0)]);
+    }
+
+    #[test]
+    fn only_solids_can_be_combined() {
+        let rectangle = Box::new(XYRectangle::new(0.0, 1.0, 0.0, 1.0, 0.0, Arc::new(Dielectric::new(1.5))));
+
+        assert!(Csg::new(Operation::Union, sphere(0.0, 1.0), rectangle).is_err());
+        let nested = Csg::new(Operation::Union, sphere(0.0, 1.0), sphere(3.0, 1.0)).unwrap();
+        assert!(Csg::new(Operation::Difference, Box::new(nested), cube()).is_ok());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{
+    math::{
+        self,
+        Point3,
+        Interval,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+    },
+    materials::{
+        Material,
+    },
+    aabb::{
+        AABB,
+    },
+    textures::{
+        TextureCoordinates,
+    },
+    shapes::{
+        revolution::{
+            self,
+            Limits,
+        },
+    },
+};
+use std::sync::Arc;
+
+// A cylinder around the y axis standing on the xz plane, optionally closed at its ends.
+pub struct Cylinder {
+    radius: f32,
+    limits: Limits,
+    caps: bool,
+    material: Arc<dyn Material>,
+}
+
+impl Cylinder {
+    pub fn new(radius: f32, height: f32, material: Arc<dyn Material>) -> Self {
+        Self {
+            radius,
+            limits: Limits::new(0.0, height),
+            caps: false,
+            material,
+        }
+    }
+
+    // Keeps the part of the cylinder up to the angle from the x axis towards z.
+    pub fn with_sweep(self, degrees: f32) -> Self {
+        Self {
+            limits: self.limits.with_sweep(degrees),
+            ..self
+        }
+    }
+
+    pub fn with_height_limits(self, y_min: f32, y_max: f32) -> Self {
+        Self {
+            limits: self.limits.with_heights(y_min, y_max),
+            ..self
+        }
+    }
+
+    // Closes the ends with disks, or with sectors of them for a partial sweep.
+    pub fn with_caps(self) -> Self {
+        Self {
+            caps: true,
+            ..self
+        }
+    }
+
+    fn side_hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let (o, d) = (ray.origin, ray.direction);
+        let a = d.x * d.x + d.z * d.z;
+        let b = 2.0 * (o.x * d.x + o.z * d.z);
+        let c = o.x * o.x + o.z * o.z - self.radius * self.radius;
+        let (t0, t1) = math::solve_quadratic(a, b, c)?;
+        let (t, hit_point, phi) = revolution::first_hit(ray, hit_interval, &[t0, t1], &self.limits)?;
+
+        Some(HitRecord {
+            t,
+            hit_point,
+            normal: math::vec3(hit_point.x, 0.0, hit_point.z) / self.radius,
+            material: self.material.as_ref(),
+            uv: TextureCoordinates{ u: self.limits.u(phi), v: self.limits.v(hit_point.y) },
+        })
+    }
+
+    // The closer of the two ends, with the distance from the axis as v.
+    fn cap_hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let mut closest = None;
+        let mut end = hit_interval.max();
+        for &(y, normal_y) in &[(self.limits.y_min, -1.0), (self.limits.y_max, 1.0)] {
+            let t = (y - ray.origin.y) / ray.direction.y;
+            if !(hit_interval.min() < t && t < end) {
+                continue;
+            }
+            let hit_point = Point3::new(ray.origin.x + t * ray.direction.x, y, ray.origin.z + t * ray.direction.z);
+            let distance = (hit_point.x * hit_point.x + hit_point.z * hit_point.z).sqrt();
+            if distance > self.radius {
+                continue;
+            }
+            if let Some(phi) = self.limits.angle_within(&hit_point) {
+                closest = Some(HitRecord {
+                    t,
+                    hit_point,
+                    normal: math::vec3(0.0, normal_y, 0.0),
+                    material: self.material.as_ref(),
+                    uv: TextureCoordinates{ u: self.limits.u(phi), v: distance / self.radius },
+                });
+                end = t;
+            }
+        }
+        closest
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let side = self.side_hit(ray, hit_interval);
+        if !self.caps {
+            return side;
+        }
+        match (side, self.cap_hit(ray, hit_interval)) {
+            (Some(side), Some(cap)) => Some(if cap.t < side.t { cap } else { side }),
+            (side, cap) => side.or(cap),
+        }
+    }
+
+    fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
+        Some(self.limits.bounding_box(self.radius))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_helpers::hit_interval,
+        materials::Dielectric,
+        math::InnerSpace,
+    };
+
+    fn cylinder() -> Cylinder {
+        Cylinder::new(1.0, 2.0, Arc::new(Dielectric::new(1.5)))
+    }
+
+    #[test]
+    fn ray_hits_the_side_with_its_coordinates() {
+        let ray = Ray::new(Point3::new(-5.0, 0.5, 0.0), math::vec3(1.0, 0.0, 0.0), 0.0);
+
+        let cylinder = cylinder();
+        let hit = cylinder.hit(&ray, &hit_interval()).unwrap();
+
+        assert!((hit.t - 4.0).abs() < 1e-5);
+        assert!((hit.normal - math::vec3(-1.0, 0.0, 0.0)).magnitude() < 1e-5);
+        assert!((hit.uv.u - 0.5).abs() < 1e-5);
+        assert!((hit.uv.v - 0.25).abs() < 1e-5);
+    }
+
+    #[test]
+    fn open_ends_are_seen_through() {
+        let ray = Ray::new(Point3::new(0.0, 5.0, 0.0), math::vec3(0.0, -1.0, 0.0), 0.0);
+        assert!(cylinder().hit(&ray, &hit_interval()).is_none());
+
+        let capped = cylinder().with_caps();
+        let hit = capped.hit(&ray, &hit_interval()).unwrap();
+        assert!((hit.t - 3.0).abs() < 1e-5);
+        assert_eq!(hit.normal, math::vec3(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn partial_sweep_reveals_the_inside() {
+        // the near half is cut away so the ray hits the far side from inside
+        let half = cylinder().with_sweep(180.0);
+        let ray = Ray::new(Point3::new(0.5, 1.0, -5.0), math::vec3(0.0, 0.0, 1.0), 0.0);
+
+        let hit = half.hit(&ray, &hit_interval()).unwrap();
+
+        assert!(hit.hit_point.z > 0.0);
+        assert!(half.bounding_box(&hit_interval()).unwrap().min.z.abs() < 1e-5);
+    }
+
+    #[test]
+    fn height_limits_cut_the_cylinder() {
+        let short = cylinder().with_height_limits(0.5, 1.0).with_caps();
+        let ray = Ray::new(Point3::new(-5.0, 1.5, 0.0), math::vec3(1.0, 0.0, 0.0), 0.0);
+        assert!(short.hit(&ray, &hit_interval()).is_none());
+
+        let bbox = short.bounding_box(&hit_interval()).unwrap();
+        assert_eq!((bbox.min.y, bbox.max.y), (0.5, 1.0));
+    }
+}
@@ -0,0 +1,185 @@
+use crate::{
+    math::{
+        self,
+        Point3,
+        Vec3,
+        Interval,
+        InnerSpace,
+        Onb,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+    },
+    materials::{
+        Material,
+    },
+    aabb::{
+        AABB,
+    },
+    textures::{
+        TextureCoordinates,
+    },
+    shapes,
+    sampling,
+};
+use std::{
+    f32::consts::PI,
+    sync::Arc,
+};
+
+// A flat circle facing along its normal.
+pub struct Disk {
+    center: Point3,
+    radius: f32,
+    // w is the normal, u and v measure the angle for texture coordinates
+    frame: Onb,
+    material: Arc<dyn Material>,
+}
+
+impl Disk {
+    pub fn new(center: Point3, normal: Vec3, radius: f32, material: Arc<dyn Material>) -> Self {
+        Self {
+            center,
+            radius,
+            frame: Onb::from_w(&normal),
+            material,
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let normal = self.frame.w;
+        let denominator = math::dot(normal, ray.direction);
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+        let t = math::dot(normal, self.center - ray.origin) / denominator;
+        if !(hit_interval.min() < t && t < hit_interval.max()) {
+            return None;
+        }
+
+        let hit_point = ray.at(t);
+        let offset = hit_point - self.center;
+        let distance = offset.magnitude();
+        if distance > self.radius {
+            return None;
+        }
+
+        // the angle around the center and the distance from it
+        let phi = math::dot(offset, self.frame.v).atan2(math::dot(offset, self.frame.u));
+        let uv = TextureCoordinates {
+            u: if phi < 0.0 { phi / (2.0 * PI) + 1.0 } else { phi / (2.0 * PI) },
+            v: distance / self.radius,
+        };
+        Some(HitRecord {
+            t,
+            hit_point,
+            normal,
+            material: self.material.as_ref(),
+            uv,
+        })
+    }
+
+    // along each axis the disk reaches as far as the radius times the sine of the angle between the axis and the normal
+    fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
+        let normal = self.frame.w;
+        let extent = normal.map(|n| self.radius * (1.0 - n * n).max(0.0).sqrt());
+        // flat boxes of disks facing an axis are padded
+        let padding = math::vec3(0.0001, 0.0001, 0.0001);
+        Some(AABB {
+            min: self.center - extent - padding,
+            max: self.center + extent + padding,
+        })
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        let ray = Ray::new(*origin, *direction, 0.0);
+        let area = PI * self.radius * self.radius;
+        shapes::area_pdf_to_solid_angle(&ray, self.hit(&ray, &shapes::sampling_hit_interval()), area)
+    }
+
+    fn random(&self, origin: &Point3, u: (f32, f32)) -> Vec3 {
+        let p = sampling::concentric_disk(u);
+        let point = self.center + self.radius * self.frame.local(&math::vec3(p.x, p.y, 0.0));
+        point - origin
+    }
+
+    fn is_light(&self) -> bool {
+        self.material.is_emissive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Dielectric;
+
+    fn disk() -> Disk {
+        Disk::new(Point3::new(0.0, 1.0, 0.0), math::vec3(0.0, 1.0, 1.0), 1.0, Arc::new(Dielectric::new(1.5)))
+    }
+
+    #[test]
+    fn ray_through_the_disk_hits_it() {
+        let ray = Ray::new(Point3::new(0.5, 3.0, 2.0), math::vec3(0.0, -1.0, -1.0), 0.0);
+        let hit_interval = Interval::new(0.0, 100.0).unwrap();
+        let disk = disk();
+
+        let hit_record = disk.hit(&ray, &hit_interval).unwrap();
+
+        assert!((hit_record.t - 2.0).abs() < 1e-5);
+        assert!((hit_record.uv.v - 0.5).abs() < 1e-5);
+        assert!((hit_record.normal - math::vec3(0.0, 1.0, 1.0).normalize()).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn ray_outside_the_disk_does_not_hit_it() {
+        let ray = Ray::new(Point3::new(1.1, 3.0, 2.0), math::vec3(0.0, -1.0, -1.0), 0.0);
+        let hit_interval = Interval::new(0.0, 100.0).unwrap();
+
+        assert!(disk().hit(&ray, &hit_interval).is_none());
+    }
+
+    #[test]
+    fn angle_around_the_center_gives_u() {
+        let disk = disk();
+        let hit_interval = Interval::new(0.0, 100.0).unwrap();
+        let mut us = Vec::new();
+        for i in 0..8 {
+            let angle = 2.0 * PI * (i as f32 + 0.5) / 8.0;
+            let offset = 0.5 * disk.frame.local(&math::vec3(angle.cos(), angle.sin(), 0.0));
+            let ray = Ray::new(disk.center + offset + disk.frame.w, -disk.frame.w, 0.0);
+            us.push(disk.hit(&ray, &hit_interval).unwrap().uv.u);
+        }
+
+        for (i, u) in us.iter().enumerate() {
+            assert!((u - (i as f32 + 0.5) / 8.0).abs() < 1e-4, "{:?}", us);
+        }
+    }
+
+    #[test]
+    fn bounding_box_is_tight() {
+        let bbox = disk().bounding_box(&Interval::new(0.0, 1.0).unwrap()).unwrap();
+        let half = 0.5f32.sqrt();
+
+        assert!((bbox.max.x - 1.0).abs() < 1e-3);
+        assert!((bbox.max.y - (1.0 + half)).abs() < 1e-3);
+        assert!((bbox.min.z + half).abs() < 1e-3);
+    }
+
+    #[test]
+    fn pdf_value_of_a_direction_towards_the_disk() {
+        let light = Disk::new(Point3::new(0.0, 2.0, 0.0), math::vec3(0.0, -1.0, 0.0), 1.0, Arc::new(Dielectric::new(1.5)));
+        let origin = Point3::new(0.0, 0.0, 0.0);
+
+        // distance squared over the area
+        assert!((light.pdf_value(&origin, &math::vec3(0.0, 1.0, 0.0)) - 4.0 / PI).abs() < 1e-5);
+        for i in 0..10 {
+            let direction = light.random(&origin, (i as f32 / 10.0, 0.3));
+            assert!(light.pdf_value(&origin, &direction) > 0.0);
+        }
+        assert_eq!(light.pdf_value(&origin, &math::vec3(0.0, -1.0, 0.0)), 0.0);
+    }
+}
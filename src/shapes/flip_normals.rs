@@ -1,5 +1,7 @@
 use crate::{
     math::{
+        Point3,
+        Vec3,
         Interval,
     },
     core::{
@@ -25,7 +27,7 @@ impl<T: Hittable> FlipNormals<T> {
 }
 
 impl<T: Hittable> Hittable for FlipNormals<T> {
-    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
         self.hittable.hit(ray, hit_interval)
         .map(|hit_rec| {
             HitRecord {
@@ -40,4 +42,16 @@ impl<T: Hittable> Hittable for FlipNormals<T> {
     fn bounding_box(&self, time_interval: &Interval<f32>) -> Option<AABB> {
         self.hittable.bounding_box(time_interval)
     }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        self.hittable.pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point3, u: (f32, f32)) -> Vec3 {
+        self.hittable.random(origin, u)
+    }
+
+    fn is_light(&self) -> bool {
+        self.hittable.is_light()
+    }
 }
\ No newline at end of file
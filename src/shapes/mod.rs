@@ -5,6 +5,34 @@ mod yz_rectangle;
 mod flip_normals;
 mod parallelepiped;
 pub mod moving_sphere;
+mod triangle;
+mod quad;
+mod disk;
+mod plane;
+mod revolution;
+mod cylinder;
+mod cone;
+mod paraboloid;
+mod torus;
+pub mod triangle_mesh;
+mod obj;
+mod csg;
+mod sdf_shape;
+
+use crate::{
+    math::{
+        self,
+        Point3,
+        Interval,
+    },
+    core::{
+        HitRecord,
+        Ray,
+    },
+    aabb::{
+        AABB,
+    },
+};
 
 pub use sphere::Sphere;
 pub use moving_sphere::MovingSphere;
@@ -12,4 +40,55 @@ pub use xy_rectangle::XYRectangle;
 pub use xz_rectangle::XZRectangle;
 pub use yz_rectangle::YZRectangle;
 pub use flip_normals::FlipNormals;
-pub use parallelepiped::Parallelepiped;
\ No newline at end of file
+pub use parallelepiped::Parallelepiped;
+pub use triangle::Triangle;
+pub use quad::Quad;
+pub use disk::Disk;
+pub use plane::Plane;
+pub use cylinder::Cylinder;
+pub use cone::Cone;
+pub use paraboloid::Paraboloid;
+pub use torus::Torus;
+pub use triangle_mesh::TriangleMesh;
+pub use sdf_shape::SdfShape;
+pub use csg::{
+    Csg,
+    Operation,
+};
+
+// The interval used when looking for the point a sampled direction leads to.
+fn sampling_hit_interval() -> Interval<f32> {
+    Interval::new(0.001, f32::MAX).unwrap()
+}
+
+// Converts the density of picking a uniform point on a surface with the given area
+// to a density over the directions from the origin of the ray.
+fn area_pdf_to_solid_angle(ray: &Ray, hit_record: Option<HitRecord>, area: f32) -> f32 {
+    use math::InnerSpace;
+
+    match hit_record {
+        Some(hit_record) => {
+            let distance_squared = hit_record.t * hit_record.t * ray.direction.magnitude2();
+            let cosine = (math::dot(ray.direction, hit_record.normal) / ray.direction.magnitude()).abs();
+            if cosine > 0.0 { distance_squared / (cosine * area) } else { 0.0 }
+        },
+        None => 0.0,
+    }
+}
+
+// The box around some points, padded so that flat shapes don't get a flat box.
+fn points_bounding_box(points: &[Point3]) -> AABB {
+    let mut min = points[0];
+    let mut max = points[0];
+    for p in &points[1..] {
+        for d in 0..3 {
+            min[d] = min[d].min(p[d]);
+            max[d] = max[d].max(p[d]);
+        }
+    }
+    let padding = math::vec3(0.0001, 0.0001, 0.0001);
+    AABB {
+        min: min - padding,
+        max: max + padding,
+    }
+}
@@ -20,6 +20,7 @@ use crate::{
         TextureCoordinates,
     },
 };
+use std::sync::Arc;
 
 pub struct Centers {
     pub starting: Point3,
@@ -30,11 +31,11 @@ pub struct MovingSphere {
     movement_time_interval: Interval<f32>,
     centers: Centers,
     pub radius: f32,
-    pub material: Box<dyn Material>,
+    pub material: Arc<dyn Material>,
 }
 
 impl MovingSphere {
-    pub fn new(centers: Centers, radius: f32, movement_time_interval: Interval<f32>, material: Box<dyn Material>) -> MovingSphere {
+    pub fn new(centers: Centers, radius: f32, movement_time_interval: Interval<f32>, material: Arc<dyn Material>) -> MovingSphere {
         MovingSphere {
             centers,
             movement_time_interval,
@@ -59,7 +60,7 @@ impl MovingSphere {
 }
 
 impl Hittable for MovingSphere {
-    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
         use math::{dot, EuclideanSpace};
         
         let center = self.center_at(ray.time);
@@ -77,18 +78,18 @@ impl Hittable for MovingSphere {
         solutions
         .iter()
         .find(|&&x| hit_interval.min() < x && x < hit_interval.max()) 
-        .and_then(|x| {
+        .map(|x| {
             let t = *x;
             let hit_point = ray.at(t);
             let normal = (hit_point - center) / self.radius;
             let unit_sphere_coords = Point3::from_vec(normal);
-            Some(HitRecord {
+            HitRecord {
                 t,
                 hit_point,
                 normal,
                 material: self.material.as_ref(),
                 uv: TextureCoordinates::from_unit_sphere_coordinates(&unit_sphere_coords),
-            })
+            }
         })
     }
 
@@ -125,7 +126,7 @@ mod tests {
             centers,
             2.0,
             movement_interval,
-            Box::new(Dielectric::new(1.5)),
+            Arc::new(Dielectric::new(1.5)),
         );
         let ray = Ray::new(
             Point3::new(0.0, 0.0, 0.0),
@@ -150,7 +151,7 @@ mod tests {
             centers,
             2.0,
             movement_interval,
-            Box::new(Dielectric::new(1.5)),
+            Arc::new(Dielectric::new(1.5)),
         );
         let ray = Ray::new(
             Point3::new(0.0, 0.0, 0.0),
@@ -0,0 +1,154 @@
+// A loader for the geometry of Wavefront OBJ files.
+// Only `v`, `vt`, `vn` and `f` are used, polygons are split into triangle fans
+// and everything else (groups, smoothing, materials) is ignored.
+
+use crate::{
+    math::{
+        Point3,
+        vec3,
+    },
+    textures::{
+        TextureCoordinates,
+    },
+    shapes::{
+        triangle_mesh::{
+            MeshBuffers,
+            VertexIndices,
+        },
+    },
+};
+
+pub fn parse(source: &str) -> Result<MeshBuffers, String> {
+    let mut buffers = MeshBuffers::default();
+    for (i, line) in source.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("");
+        let mut parts = line.split_whitespace();
+        let keyword = match parts.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let arguments = parts.collect::<Vec<_>>();
+        parse_line(keyword, &arguments, &mut buffers)
+            .map_err(|e| format!("line {}: {}", i + 1, e))?;
+    }
+    Ok(buffers)
+}
+
+fn parse_line(keyword: &str, arguments: &[&str], buffers: &mut MeshBuffers) -> Result<(), String> {
+    match keyword {
+        "v" => {
+            let c = numbers(arguments, 3)?;
+            buffers.positions.push(Point3::new(c[0], c[1], c[2]));
+        },
+        "vn" => {
+            let c = numbers(arguments, 3)?;
+            buffers.normals.push(vec3(c[0], c[1], c[2]));
+        },
+        "vt" => {
+            let c = numbers(arguments, 2)?;
+            buffers.uvs.push(TextureCoordinates{ u: c[0], v: c[1] });
+        },
+        "f" => {
+            if arguments.len() < 3 {
+                return Err("a face needs at least three vertices".to_owned());
+            }
+            let vertices = arguments
+                .iter()
+                .map(|a| vertex_indices(a, buffers))
+                .collect::<Result<Vec<_>, _>>()?;
+            for i in 1..vertices.len() - 1 {
+                buffers.faces.push([vertices[0], vertices[i], vertices[i + 1]]);
+            }
+        },
+        _ => (),
+    }
+    Ok(())
+}
+
+// reads the first `count` numbers, `v` and `vt` may carry an optional extra coordinate
+fn numbers(arguments: &[&str], count: usize) -> Result<Vec<f32>, String> {
+    if arguments.len() < count {
+        return Err(format!("expected {} numbers", count));
+    }
+    arguments[..count]
+        .iter()
+        .map(|a| a.parse().map_err(|_| format!("invalid number `{}`", a)))
+        .collect()
+}
+
+// `position`, `position/uv`, `position//normal` or `position/uv/normal`
+fn vertex_indices(argument: &str, buffers: &MeshBuffers) -> Result<VertexIndices, String> {
+    let mut parts = argument.split('/');
+    let position = parts.next().unwrap_or("");
+    let uv = parts.next().filter(|s| !s.is_empty());
+    let normal = parts.next().filter(|s| !s.is_empty());
+    Ok(VertexIndices {
+        position: index(position, buffers.positions.len())?,
+        uv: uv.map(|i| index(i, buffers.uvs.len())).transpose()?,
+        normal: normal.map(|i| index(i, buffers.normals.len())).transpose()?,
+    })
+}
+
+// OBJ indices start from 1, negative ones are relative to the end of the list
+fn index(text: &str, len: usize) -> Result<usize, String> {
+    let i = text.parse::<i64>()
+        .map_err(|_| format!("invalid index `{}`", text))?;
+    let resolved = if i < 0 { len as i64 + i } else { i - 1 };
+    if 0 <= resolved && resolved < len as i64 {
+        Ok(resolved as usize)
+    }
+    else {
+        Err(format!("index {} is out of range", i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_vertices_and_faces() {
+        let source = "
+            # a unit square
+            o square
+            v 0 0 0
+            v 1 0 0
+            v 1 1 0
+            v 0 1 0
+            vt 0 0
+            vt 1 0
+            vt 1 1
+            vn 0 0 1
+            f 1/1/1 2/2/1 3/3/1
+            f 1//1 -2//1 -1//1
+        ";
+
+        let buffers = parse(source).unwrap();
+
+        assert_eq!(buffers.positions.len(), 4);
+        assert_eq!(buffers.uvs.len(), 3);
+        assert_eq!(buffers.normals.len(), 1);
+        assert_eq!(buffers.faces.len(), 2);
+        assert_eq!(buffers.faces[0][2], VertexIndices{ position: 2, uv: Some(2), normal: Some(0) });
+        assert_eq!(buffers.faces[1][1], VertexIndices{ position: 2, uv: None, normal: Some(0) });
+    }
+
+    #[test]
+    fn polygons_are_split_into_triangles() {
+        let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n";
+
+        let buffers = parse(source).unwrap();
+
+        assert_eq!(buffers.faces.len(), 2);
+        assert_eq!(buffers.faces[1][0].position, 0);
+        assert_eq!(buffers.faces[1][1].position, 2);
+        assert_eq!(buffers.faces[1][2].position, 3);
+    }
+
+    #[test]
+    fn errors_report_the_line() {
+        let source = "v 0 0 0\nv 1 0 0\nf 1 2 3\n";
+
+        assert_eq!(parse(source).unwrap_err(), "line 3: index 3 is out of range");
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    math::{
+        self,
+        Interval,
+        InnerSpace,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+    },
+    materials::{
+        Material,
+    },
+    aabb::{
+        AABB,
+    },
+    textures::{
+        TextureCoordinates,
+    },
+    shapes::{
+        revolution::{
+            self,
+            Limits,
+        },
+    },
+};
+use std::sync::Arc;
+
+// A bowl around the y axis with its vertex at the origin, reaching the radius at the height.
+pub struct Paraboloid {
+    radius: f32,
+    height: f32,
+    limits: Limits,
+    material: Arc<dyn Material>,
+}
+
+impl Paraboloid {
+    pub fn new(radius: f32, height: f32, material: Arc<dyn Material>) -> Self {
+        Self {
+            radius,
+            height,
+            limits: Limits::new(0.0, height),
+            material,
+        }
+    }
+
+    // Keeps the part of the paraboloid up to the angle from the x axis towards z.
+    pub fn with_sweep(self, degrees: f32) -> Self {
+        Self {
+            limits: self.limits.with_sweep(degrees),
+            ..self
+        }
+    }
+
+    // Cutting above the vertex leaves a hole at the bottom.
+    pub fn with_height_limits(self, y_min: f32, y_max: f32) -> Self {
+        Self {
+            limits: self.limits.with_heights(y_min, y_max),
+            ..self
+        }
+    }
+}
+
+impl Hittable for Paraboloid {
+    // Solves k (x^2 + z^2) = y, which is linear in t for rays along the axis.
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let (o, d) = (ray.origin, ray.direction);
+        let k = self.height / (self.radius * self.radius);
+        let a = k * (d.x * d.x + d.z * d.z);
+        let b = 2.0 * k * (o.x * d.x + o.z * d.z) - d.y;
+        let c = k * (o.x * o.x + o.z * o.z) - o.y;
+        let (t0, t1) = math::solve_quadratic(a, b, c)?;
+        let (t, hit_point, phi) = revolution::first_hit(ray, hit_interval, &[t0, t1], &self.limits)?;
+
+        // facing away from the axis and down, out of the bowl
+        let normal = math::vec3(2.0 * k * hit_point.x, -1.0, 2.0 * k * hit_point.z).normalize();
+        Some(HitRecord {
+            t,
+            hit_point,
+            normal,
+            material: self.material.as_ref(),
+            uv: TextureCoordinates{ u: self.limits.u(phi), v: self.limits.v(hit_point.y) },
+        })
+    }
+
+    // the paraboloid is widest at its highest point
+    fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
+        let radius = self.radius * (self.limits.y_max / self.height).sqrt();
+        Some(self.limits.bounding_box(radius))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_helpers::hit_interval,
+        materials::Dielectric,
+        math::Point3,
+    };
+
+    fn paraboloid() -> Paraboloid {
+        Paraboloid::new(2.0, 4.0, Arc::new(Dielectric::new(1.5)))
+    }
+
+    #[test]
+    fn ray_along_the_axis_hits_the_vertex() {
+        let ray = Ray::new(Point3::new(0.0, 5.0, 0.0), math::vec3(0.0, -1.0, 0.0), 0.0);
+
+        let paraboloid = paraboloid();
+        let hit = paraboloid.hit(&ray, &hit_interval()).unwrap();
+
+        assert!((hit.t - 5.0).abs() < 1e-5);
+        assert!((hit.normal - math::vec3(0.0, -1.0, 0.0)).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn ray_from_the_side_hits_the_rim() {
+        let ray = Ray::new(Point3::new(-5.0, 1.0, 0.0), math::vec3(1.0, 0.0, 0.0), 0.0);
+
+        let paraboloid = paraboloid();
+        let hit = paraboloid.hit(&ray, &hit_interval()).unwrap();
+
+        // at a quarter of the height the radius is halved
+        assert!((hit.t - 4.0).abs() < 1e-5);
+        assert!((hit.uv.v - 0.25).abs() < 1e-5);
+        assert!(hit.normal.x < 0.0);
+    }
+
+    #[test]
+    fn height_limits_cut_a_hole_and_narrow_the_box() {
+        let ring = paraboloid().with_height_limits(1.0, 2.0);
+        let ray = Ray::new(Point3::new(0.0, 5.0, 0.0), math::vec3(0.0, -1.0, 0.0), 0.0);
+        assert!(ring.hit(&ray, &hit_interval()).is_none());
+
+        let bbox = ring.bounding_box(&hit_interval()).unwrap();
+        assert!((bbox.max.x - 2.0f32.sqrt()).abs() < 1e-5);
+        assert_eq!((bbox.min.y, bbox.max.y), (1.0, 2.0));
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
         Hittable, 
         HitRecord,
         Ray,
+        Span,
     },
     materials::{
         Material,
@@ -21,6 +22,7 @@ use crate::{
         FlipNormals,
     }
 };
+use std::sync::Arc;
 
 pub struct Parallelepiped {
     min: Point3,
@@ -29,7 +31,7 @@ pub struct Parallelepiped {
 }
 
 impl Hittable for Parallelepiped {
-    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
         let mut interval = *hit_interval;
         let mut result = None;
         for hittable in &self.walls {
@@ -47,17 +49,40 @@ impl Hittable for Parallelepiped {
             max: self.max,
         })
     }
+
+    // The line crosses the walls on the way in and out, edges and corners are hit more than once.
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        let whole_line = Interval::new(-f32::MAX, f32::MAX).unwrap();
+        let hits = self.walls
+            .iter()
+            .filter_map(|wall| wall.hit(ray, &whole_line))
+            .collect::<Vec<_>>();
+        let first = hits.iter().min_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
+        let last = hits.iter().max_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
+        match (first, last) {
+            (Some(enter), Some(exit)) if enter.t < exit.t => vec![Span {
+                enter: *enter,
+                exit: *exit,
+            }],
+            _ => Vec::new(),
+        }
+    }
+
+    fn is_solid(&self) -> bool {
+        true
+    }
 }
 
 impl Parallelepiped {
-    pub fn new<F: Fn() -> Box<dyn Material>>(min: &Point3, max: &Point3, material_generator: F) -> Self {
+    // The walls share the material.
+    pub fn new(min: &Point3, max: &Point3, material: Arc<dyn Material>) -> Self {
         let walls : [Box<dyn Hittable>; 6] = [
-            Box::new(XYRectangle::new(min.x, max.x, min.y, max.y, max.z, material_generator())),
-            Box::new(FlipNormals::new(XYRectangle::new(min.x, max.x, min.y, max.y, min.z, material_generator()))),
-            Box::new(XZRectangle::new(min.x, max.x, min.z, max.z, max.y, material_generator())),
-            Box::new(FlipNormals::new(XZRectangle::new(min.x, max.x, min.z, max.z, min.y, material_generator()))),
-            Box::new(YZRectangle::new(min.y, max.y, min.z, max.z, max.x, material_generator())),
-            Box::new(FlipNormals::new(YZRectangle::new(min.y, max.y, min.z, max.z, min.x, material_generator()))),
+            Box::new(XYRectangle::new(min.x, max.x, min.y, max.y, max.z, material.clone())),
+            Box::new(FlipNormals::new(XYRectangle::new(min.x, max.x, min.y, max.y, min.z, material.clone()))),
+            Box::new(XZRectangle::new(min.x, max.x, min.z, max.z, max.y, material.clone())),
+            Box::new(FlipNormals::new(XZRectangle::new(min.x, max.x, min.z, max.z, min.y, material.clone()))),
+            Box::new(YZRectangle::new(min.y, max.y, min.z, max.z, max.x, material.clone())),
+            Box::new(FlipNormals::new(YZRectangle::new(min.y, max.y, min.z, max.z, min.x, material.clone()))),
         ];
 
         Self {
@@ -81,10 +106,10 @@ mod tests {
 
     #[test]
     fn ray_through_the_parallelepiped_hits_it() {
-        let material_gen = || Box::new(Dielectric::new(1.5)) as Box<dyn Material>;
+        let material = Arc::new(Dielectric::new(1.5));
         let (min, max) = (Point3::new(0.0, 0.0, 0.0), Point3::new(3.0, 3.0, 3.0));
-        let parallelepiped = Parallelepiped::new(&min, &max, material_gen);
-        let hit_interval = Interval::new(0.0, std::f32::MAX).unwrap();
+        let parallelepiped = Parallelepiped::new(&min, &max, material);
+        let hit_interval = Interval::new(0.0, f32::MAX).unwrap();
         let ray = Ray::new(Point3::new(1.0, -1.0, 1.0), math::vec3(1.0, 2.0, 1.0), 1.0);
 
         assert!(parallelepiped.hit(&ray, &hit_interval).is_some());
@@ -92,10 +117,10 @@ mod tests {
     
     #[test]
     fn ray_outside_the_parallelepiped_does_not_hit_it() {
-        let material_gen = || Box::new(Dielectric::new(1.5)) as Box<dyn Material>;
+        let material = Arc::new(Dielectric::new(1.5));
         let (min, max) = (Point3::new(0.0, 0.0, 0.0), Point3::new(3.0, 3.0, 3.0));
-        let parallelepiped = Parallelepiped::new(&min, &max, material_gen);
-        let hit_interval = Interval::new(0.0, std::f32::MAX).unwrap();
+        let parallelepiped = Parallelepiped::new(&min, &max, material);
+        let hit_interval = Interval::new(0.0, f32::MAX).unwrap();
         let ray = Ray::new(Point3::new(1.0, -5.0, -5.0), math::vec3(5.0, 5.0, 5.0), 1.0);
 
         assert!(parallelepiped.hit(&ray, &hit_interval).is_none());
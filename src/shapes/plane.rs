@@ -0,0 +1,118 @@
+use crate::{
+    math::{
+        self,
+        Point3,
+        Vec3,
+        Interval,
+        Onb,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+    },
+    materials::{
+        Material,
+    },
+    aabb::{
+        AABB,
+    },
+    textures::{
+        TextureCoordinates,
+    },
+};
+use std::sync::Arc;
+
+// An infinite plane through a point facing along its normal.
+// It has no bounding box so the scene keeps it out of the BVH.
+pub struct Plane {
+    point: Point3,
+    // w is the normal, u and v give the texture coordinates
+    frame: Onb,
+    material: Arc<dyn Material>,
+}
+
+impl Plane {
+    pub fn new(point: Point3, normal: Vec3, material: Arc<dyn Material>) -> Self {
+        Self {
+            point,
+            frame: Onb::from_w(&normal),
+            material,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let normal = self.frame.w;
+        let denominator = math::dot(normal, ray.direction);
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+        let t = math::dot(normal, self.point - ray.origin) / denominator;
+        if !(hit_interval.min() < t && t < hit_interval.max()) {
+            return None;
+        }
+
+        // textures repeat every unit along u and v, rem_euclid rounds tiny negative values up to 1
+        let hit_point = ray.at(t);
+        let offset = hit_point - self.point;
+        let repeat = |c: f32| c.rem_euclid(1.0).min(1.0 - f32::EPSILON / 2.0);
+        let uv = TextureCoordinates {
+            u: repeat(math::dot(offset, self.frame.u)),
+            v: repeat(math::dot(offset, self.frame.v)),
+        };
+        Some(HitRecord {
+            t,
+            hit_point,
+            normal,
+            material: self.material.as_ref(),
+            uv,
+        })
+    }
+
+    fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Dielectric;
+
+    fn ground() -> Plane {
+        Plane::new(Point3::new(0.0, -1.0, 0.0), math::vec3(0.0, 1.0, 0.0), Arc::new(Dielectric::new(1.5)))
+    }
+
+    #[test]
+    fn plane_is_hit_far_from_its_point() {
+        let ray = Ray::new(Point3::new(1000.0, 1.0, -2000.0), math::vec3(1.0, -1.0, 0.0), 0.0);
+        let ground = ground();
+        let hit = ground.hit(&ray, &Interval::new(0.001, f32::MAX).unwrap()).unwrap();
+
+        assert!((hit.t - 2.0).abs() < 1e-4);
+        assert_eq!(hit.normal, math::vec3(0.0, 1.0, 0.0));
+        assert!((0.0..1.0).contains(&hit.uv.u) && (0.0..1.0).contains(&hit.uv.v));
+    }
+
+    #[test]
+    fn texture_coordinates_stay_below_one() {
+        let ground = Plane::new(Point3::new(0.0, 0.0, 0.0), math::vec3(0.0, 0.0, 1.0), Arc::new(Dielectric::new(1.5)));
+        let hit_interval = Interval::new(0.001, f32::MAX).unwrap();
+        for &offset in &[-1e-9, -1e-30, -1.0, 0.0] {
+            let ray = Ray::new(Point3::new(offset, offset, -1.0), math::vec3(0.0, 0.0, 1.0), 0.0);
+            let hit = ground.hit(&ray, &hit_interval).unwrap();
+            assert!((0.0..1.0).contains(&hit.uv.u) && (0.0..1.0).contains(&hit.uv.v), "{:?}", hit.uv);
+        }
+    }
+
+    #[test]
+    fn parallel_ray_misses_and_there_is_no_bounding_box() {
+        let ray = Ray::new(Point3::new(0.0, 1.0, 0.0), math::vec3(1.0, 0.0, 0.0), 0.0);
+        let ground = ground();
+
+        assert!(ground.hit(&ray, &Interval::new(0.001, f32::MAX).unwrap()).is_none());
+        assert!(ground.bounding_box(&Interval::new(0.0, 1.0).unwrap()).is_none());
+    }
+}
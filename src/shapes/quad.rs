@@ -0,0 +1,165 @@
+use crate::{
+    math::{
+        self,
+        Point3,
+        Vec3,
+        Interval,
+        InnerSpace,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+    },
+    materials::{
+        Material,
+    },
+    aabb::{
+        AABB,
+    },
+    textures::{
+        TextureCoordinates,
+    },
+    shapes,
+};
+use std::sync::Arc;
+
+// A parallelogram spanned by two edges from a corner, facing along their cross product.
+pub struct Quad {
+    corner: Point3,
+    u: Vec3,
+    v: Vec3,
+    normal: Vec3,
+    // u x v scaled so that dot products with it give the coordinates along the edges
+    w: Vec3,
+    area: f32,
+    material: Arc<dyn Material>,
+}
+
+impl Quad {
+    pub fn new(corner: Point3, u: Vec3, v: Vec3, material: Arc<dyn Material>) -> Self {
+        let n = u.cross(v);
+        Self {
+            corner,
+            u,
+            v,
+            normal: n.normalize(),
+            w: n / n.magnitude2(),
+            area: n.magnitude(),
+            material,
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let denominator = math::dot(self.normal, ray.direction);
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+        let t = math::dot(self.normal, self.corner - ray.origin) / denominator;
+        if !(hit_interval.min() < t && t < hit_interval.max()) {
+            return None;
+        }
+
+        // the coordinates of the hit point along the edges, both in [0, 1] inside the quad
+        let hit_point = ray.at(t);
+        let p = hit_point - self.corner;
+        let alpha = math::dot(self.w, p.cross(self.v));
+        let beta = math::dot(self.w, self.u.cross(p));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        Some(HitRecord {
+            t,
+            hit_point,
+            normal: self.normal,
+            material: self.material.as_ref(),
+            uv: TextureCoordinates{ u: alpha, v: beta },
+        })
+    }
+
+    fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
+        let corners = [self.corner, self.corner + self.u, self.corner + self.v, self.corner + self.u + self.v];
+        Some(shapes::points_bounding_box(&corners))
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        let ray = Ray::new(*origin, *direction, 0.0);
+        shapes::area_pdf_to_solid_angle(&ray, self.hit(&ray, &shapes::sampling_hit_interval()), self.area)
+    }
+
+    fn random(&self, origin: &Point3, (s, t): (f32, f32)) -> Vec3 {
+        (self.corner + s * self.u + t * self.v) - origin
+    }
+
+    fn is_light(&self) -> bool {
+        self.material.is_emissive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Dielectric;
+
+    // tilted about the x axis, a unit square in x by a diagonal of length 2 in y and z
+    fn quad() -> Quad {
+        Quad::new(
+            Point3::new(0.0, 0.0, 0.0),
+            math::vec3(1.0, 0.0, 0.0),
+            math::vec3(0.0, 2.0f32.sqrt(), 2.0f32.sqrt()),
+            Arc::new(Dielectric::new(1.5)),
+        )
+    }
+
+    #[test]
+    fn ray_through_the_quad_hits_it_with_its_coordinates() {
+        let quad = quad();
+        let target = Point3::new(0.25, 0.5 * 2.0f32.sqrt(), 0.5 * 2.0f32.sqrt());
+        let ray = Ray::new(target + math::vec3(0.0, 3.0, -3.0), math::vec3(0.0, -1.0, 1.0), 0.0);
+        let hit_interval = Interval::new(0.0, 100.0).unwrap();
+
+        let hit_record = quad.hit(&ray, &hit_interval).unwrap();
+
+        assert!((hit_record.t - 3.0).abs() < 1e-5);
+        assert!((hit_record.uv.u - 0.25).abs() < 1e-5);
+        assert!((hit_record.uv.v - 0.5).abs() < 1e-5);
+        assert!((hit_record.normal - math::vec3(0.0, -1.0, 1.0).normalize()).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn ray_outside_the_quad_does_not_hit_it() {
+        let ray = Ray::new(Point3::new(1.5, 3.0, -2.0), math::vec3(0.0, -1.0, 1.0), 0.0);
+        let hit_interval = Interval::new(0.0, 100.0).unwrap();
+
+        assert!(quad().hit(&ray, &hit_interval).is_none());
+    }
+
+    #[test]
+    fn bounding_box_is_tight() {
+        let bbox = quad().bounding_box(&Interval::new(0.0, 1.0).unwrap()).unwrap();
+
+        assert!((bbox.max.x - 1.0).abs() < 1e-3);
+        assert!((bbox.max.y - 2.0f32.sqrt()).abs() < 1e-3);
+        assert!((bbox.max.z - 2.0f32.sqrt()).abs() < 1e-3);
+        assert!(bbox.min.y.abs() < 1e-3);
+    }
+
+    #[test]
+    fn pdf_value_of_a_direction_towards_the_quad() {
+        let light = Quad::new(
+            Point3::new(-1.0, 2.0, -1.0),
+            math::vec3(2.0, 0.0, 0.0),
+            math::vec3(0.0, 0.0, 2.0),
+            Arc::new(Dielectric::new(1.5)),
+        );
+        let origin = Point3::new(0.0, 0.0, 0.0);
+
+        // distance squared over the area
+        assert!((light.pdf_value(&origin, &math::vec3(0.0, 1.0, 0.0)) - 1.0).abs() < 1e-6);
+        assert!(light.pdf_value(&origin, &light.random(&origin, (0.3, 0.7))) > 0.0);
+        assert_eq!(light.pdf_value(&origin, &math::vec3(0.0, -1.0, 0.0)), 0.0);
+    }
+}
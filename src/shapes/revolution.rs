@@ -0,0 +1,136 @@
+// Helpers for the surfaces of revolution around the y axis, which can be cut down to part of
+// a turn and to a range of heights.
+
+use crate::{
+    math::{
+        Point3,
+        Interval,
+    },
+    core::{
+        Ray,
+    },
+    aabb::{
+        AABB,
+    },
+};
+use std::f32::consts::PI;
+
+// The part of a surface which is kept, where the angle is measured from the x axis towards z.
+#[derive(Clone, Copy)]
+pub(super) struct Limits {
+    pub phi_max: f32,
+    pub y_min: f32,
+    pub y_max: f32,
+}
+
+impl Limits {
+    pub fn new(y_min: f32, y_max: f32) -> Self {
+        Self {
+            phi_max: 2.0 * PI,
+            y_min,
+            y_max,
+        }
+    }
+
+    pub fn with_sweep(self, degrees: f32) -> Self {
+        Self {
+            phi_max: degrees.clamp(0.0, 360.0).to_radians(),
+            ..self
+        }
+    }
+
+    // Narrows the heights, limits outside of the current ones don't widen them.
+    pub fn with_heights(self, y_min: f32, y_max: f32) -> Self {
+        Self {
+            y_min: self.y_min.max(y_min),
+            y_max: self.y_max.min(y_max),
+            ..self
+        }
+    }
+
+    // The angle of the point if it is within the limits.
+    pub fn angle_within(&self, p: &Point3) -> Option<f32> {
+        if p.y < self.y_min || p.y > self.y_max {
+            return None;
+        }
+        let phi = angle(p);
+        if phi <= self.phi_max { Some(phi) } else { None }
+    }
+
+    // The box around the swept sector of the disk with the given radius, between the heights.
+    pub fn bounding_box(&self, radius: f32) -> AABB {
+        let mut corners = vec![(0.0, 0.0), (radius, 0.0)];
+        let (sin, cos) = self.phi_max.sin_cos();
+        corners.push((radius * cos, radius * sin));
+        for quarter in 1..4 {
+            let phi = quarter as f32 * 0.5 * PI;
+            if phi < self.phi_max {
+                let (sin, cos) = phi.sin_cos();
+                corners.push((radius * cos, radius * sin));
+            }
+        }
+        let (mut min, mut max) = (Point3::new(0.0, self.y_min, 0.0), Point3::new(0.0, self.y_max, 0.0));
+        for (x, z) in corners {
+            min.x = min.x.min(x);
+            min.z = min.z.min(z);
+            max.x = max.x.max(x);
+            max.z = max.z.max(z);
+        }
+        AABB { min, max }
+    }
+
+    pub fn u(&self, phi: f32) -> f32 {
+        phi / self.phi_max
+    }
+
+    pub fn v(&self, y: f32) -> f32 {
+        (y - self.y_min) / (self.y_max - self.y_min)
+    }
+}
+
+// The angle around the y axis from x towards z, in [0, 2 pi).
+pub(super) fn angle(p: &Point3) -> f32 {
+    let phi = p.z.atan2(p.x);
+    if phi < 0.0 { phi + 2.0 * PI } else { phi }
+}
+
+// The first of the increasing distances which is in the interval and leads to a point within the limits,
+// with that point and its angle.
+pub(super) fn first_hit(
+    ray: &Ray,
+    hit_interval: &Interval<f32>,
+    ts: &[f32],
+    limits: &Limits
+) -> Option<(f32, Point3, f32)> {
+    ts.iter()
+        .filter(|&&t| hit_interval.min() < t && t < hit_interval.max())
+        .find_map(|&t| {
+            let p = ray.at(t);
+            limits.angle_within(&p).map(|phi| (t, p, phi))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounding_box_covers_only_the_swept_sector() {
+        let bbox = Limits::new(0.0, 1.0).with_sweep(135.0).bounding_box(2.0);
+        let half = 0.5f32.sqrt();
+
+        assert!((bbox.min.x + 2.0 * half).abs() < 1e-5);
+        assert!((bbox.max.x - 2.0).abs() < 1e-5);
+        assert!(bbox.min.z.abs() < 1e-5);
+        assert!((bbox.max.z - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn points_outside_the_limits_are_rejected() {
+        let limits = Limits::new(0.0, 2.0).with_sweep(90.0).with_heights(-1.0, 1.0);
+
+        assert!(limits.angle_within(&Point3::new(1.0, 0.5, 1.0)).is_some());
+        assert!(limits.angle_within(&Point3::new(1.0, 1.5, 1.0)).is_none());
+        assert!(limits.angle_within(&Point3::new(1.0, 0.5, -1.0)).is_none());
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{
+    math::{
+        self,
+        Point3,
+        Interval,
+        InnerSpace,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+    },
+    materials::{
+        Material,
+    },
+    aabb::{
+        AABB,
+    },
+    textures::{
+        TextureCoordinates,
+    },
+    sdf::{
+        Sdf,
+    },
+};
+use std::sync::Arc;
+
+// most steps taken along a ray before giving up
+const MAX_STEPS: usize = 512;
+// how close to the surface counts as hitting it
+const SURFACE_DISTANCE: f32 = 1e-4;
+// the box is traced slightly larger so that rays don't start on surfaces which touch it
+const BOX_MARGIN: f32 = 1e-3;
+
+// A surface found by sphere tracing a signed distance field.
+// The field is only traced inside the declared box, which must contain the whole surface.
+pub struct SdfShape {
+    sdf: Box<dyn Sdf>,
+    bbox: AABB,
+    // how much the field can change per unit of distance, above 1 for fields which overestimate
+    lipschitz_bound: f32,
+    material: Arc<dyn Material>,
+}
+
+impl SdfShape {
+    pub fn new(sdf: Box<dyn Sdf>, bbox: AABB, material: Arc<dyn Material>) -> Self {
+        Self {
+            sdf,
+            bbox,
+            lipschitz_bound: 1.0,
+            material,
+        }
+    }
+
+    // Steps are divided by the bound, fields like `Twist` need one above 1.
+    pub fn with_lipschitz_bound(self, lipschitz_bound: f32) -> Self {
+        Self {
+            lipschitz_bound: lipschitz_bound.max(1.0),
+            ..self
+        }
+    }
+
+    // The part of the interval in which the ray is inside the box.
+    fn clip(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<(f32, f32)> {
+        let (mut t0, mut t1) = (hit_interval.min(), hit_interval.max());
+        for d in 0..3 {
+            let inv_dir = 1.0 / ray.direction[d];
+            let near = (self.bbox.min[d] - BOX_MARGIN - ray.origin[d]) * inv_dir;
+            let far = (self.bbox.max[d] + BOX_MARGIN - ray.origin[d]) * inv_dir;
+            let (near, far) = if inv_dir < 0.0 { (far, near) } else { (near, far) };
+            // NaN when the ray is parallel to a face it lies on, which doesn't narrow the interval
+            t0 = if near > t0 { near } else { t0 };
+            t1 = if far < t1 { far } else { t1 };
+        }
+        if t0 <= t1 { Some((t0, t1)) } else { None }
+    }
+
+    // The normalized gradient of the field by central differences.
+    // The step grows with the distance from the origin to stay well above the spacing of f32 coordinates.
+    fn normal(&self, p: &Point3) -> math::Vec3 {
+        use math::EuclideanSpace;
+
+        let h = 0.5 * SURFACE_DISTANCE * p.to_vec().magnitude().max(1.0);
+        let partial = |offset: math::Vec3| self.sdf.distance(&(p + offset)) - self.sdf.distance(&(p - offset));
+        let gradient = math::vec3(
+            partial(math::vec3(h, 0.0, 0.0)),
+            partial(math::vec3(0.0, h, 0.0)),
+            partial(math::vec3(0.0, 0.0, h)),
+        );
+        // a flat field has no direction, any normal will do
+        if gradient.magnitude2() > 0.0 { gradient.normalize() } else { math::vec3(0.0, 1.0, 0.0) }
+    }
+}
+
+impl Hittable for SdfShape {
+    // Steps along the ray by the distance to the surface until it is close enough.
+    // Rays which start on the surface step off of it first, and ones inside the shape
+    // trace the negated field to find where they leave it.
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let (mut t, end) = self.clip(ray, hit_interval)?;
+        let speed = ray.direction.magnitude();
+        let mut side = 0.0;
+        for _ in 0..MAX_STEPS {
+            if t > end {
+                return None;
+            }
+            let distance = self.sdf.distance(&ray.at(t));
+            if side == 0.0 {
+                if distance.abs() < SURFACE_DISTANCE {
+                    t += SURFACE_DISTANCE / speed;
+                    continue;
+                }
+                side = distance.signum();
+            }
+            let distance = side * distance;
+            if distance < SURFACE_DISTANCE {
+                let hit_point = ray.at(t);
+                return Some(HitRecord {
+                    t,
+                    hit_point,
+                    normal: self.normal(&hit_point),
+                    material: self.material.as_ref(),
+                    uv: TextureCoordinates::zero(),
+                });
+            }
+            t += distance / (self.lipschitz_bound * speed);
+        }
+        None
+    }
+
+    fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
+        Some(self.bbox)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_helpers::hit_interval,
+        materials::Dielectric,
+        math::vec3,
+        sdf::{
+            Torus,
+            Sphere,
+            Twist,
+            RoundedBox,
+            Translate,
+        },
+    };
+
+    fn shape(sdf: Box<dyn Sdf>, extent: f32) -> SdfShape {
+        let bbox = AABB {
+            min: Point3::new(-extent, -extent, -extent),
+            max: Point3::new(extent, extent, extent),
+        };
+        SdfShape::new(sdf, bbox, Arc::new(Dielectric::new(1.5)))
+    }
+
+    #[test]
+    fn ray_hits_a_torus_on_its_tube() {
+        let torus = shape(Box::new(Torus { major_radius: 2.0, minor_radius: 0.5 }), 3.0);
+        let ray = Ray::new(Point3::new(2.0, 5.0, 0.0), vec3(0.0, -2.0, 0.0), 0.0);
+
+        let hit = torus.hit(&ray, &hit_interval()).unwrap();
+        assert!((hit.t - 2.25).abs() < 1e-3, "{}", hit.t);
+        assert!((hit.normal - vec3(0.0, 1.0, 0.0)).magnitude() < 1e-2);
+        // through the hole
+        let ray = Ray::new(Point3::new(0.0, 5.0, 0.0), vec3(0.0, -1.0, 0.0), 0.0);
+        assert!(torus.hit(&ray, &hit_interval()).is_none());
+    }
+
+    #[test]
+    fn rays_from_inside_and_on_the_surface_find_the_way_out() {
+        let sphere = shape(Box::new(Sphere { radius: 1.0 }), 1.5);
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0), 0.0);
+
+        let hit = sphere.hit(&ray, &hit_interval()).unwrap();
+        assert!((hit.hit_point.x - 1.0).abs() < 1e-3);
+        assert!((hit.normal.x - 1.0).abs() < 1e-2);
+
+        let ray = Ray::new(hit.hit_point, vec3(-1.0, 0.0, 0.0), 0.0);
+        let hit = sphere.hit(&ray, &hit_interval()).unwrap();
+        assert!((hit.hit_point.x + 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn surfaces_touching_the_box_are_hit_from_outside() {
+        let cube = shape(Box::new(RoundedBox { half_extents: vec3(1.0, 1.0, 1.0), radius: 0.0 }), 1.0);
+        let ray = Ray::new(Point3::new(0.2, 5.0, 0.3), vec3(0.0, -1.0, 0.0), 0.0);
+
+        let hit = cube.hit(&ray, &hit_interval()).unwrap();
+        assert!((hit.hit_point.y - 1.0).abs() < 1e-3);
+        assert!((hit.normal.y - 1.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn normals_far_from_the_origin_are_smooth() {
+        let center = Point3::new(3000.0, 0.0, 0.0);
+        let sphere = shape(Box::new(Translate { sdf: Box::new(Sphere { radius: 1.0 }), offset: center - Point3::new(0.0, 0.0, 0.0) }), 1.0);
+
+        for &direction in &[vec3(0.6, 0.8, 0.0), vec3(0.0, 0.6, -0.8), vec3(-1.0, 0.0, 0.0)] {
+            let normal = sphere.normal(&(center + direction));
+            assert!((normal - direction).magnitude() < 1e-2, "{:?}", normal);
+        }
+    }
+
+    #[test]
+    fn distance_functions_can_be_closures() {
+        let plane = shape(Box::new(|p: &Point3| p.y), 1.0);
+        let ray = Ray::new(Point3::new(0.0, 3.0, 0.0), vec3(0.0, -1.0, 0.0), 0.0);
+
+        let hit = plane.hit(&ray, &hit_interval()).unwrap();
+        assert!((hit.t - 3.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn twisted_shapes_are_traced_with_a_lipschitz_bound() {
+        let slab = RoundedBox { half_extents: vec3(2.0, 1.0, 0.2), radius: 0.05 };
+        let twisted = shape(Box::new(Twist { sdf: Box::new(slab), rate: 1.0 }), 3.0).with_lipschitz_bound(2.5);
+        let ray = Ray::new(Point3::new(0.0, 0.5, -5.0), vec3(0.0, 0.0, 1.0), 0.0);
+
+        let hit = twisted.hit(&ray, &hit_interval()).unwrap();
+        assert!(twisted.sdf.distance(&hit.hit_point).abs() < 1e-3);
+    }
+}
@@ -2,12 +2,14 @@ use crate::{
     math::{
         self,
         Point3, 
+        Vec3,
         Interval,
     },
     core::{
         Hittable, 
         HitRecord,
         Ray,
+        Span,
     },
     materials::{
         Material,
@@ -17,29 +19,34 @@ use crate::{
     },
     textures::{
         TextureCoordinates,
-    }
+    },
+    shapes,
+    sampling,
+};
+use std::{
+    f32::consts::PI,
+    sync::Arc,
 };
 
 pub struct Sphere {
     pub center: Point3,
     pub radius: f32,
-    pub material: Box<dyn Material>,
+    pub material: Arc<dyn Material>,
 }
 
 impl Sphere {
-    pub fn new(center: Point3, radius: f32, material: Box<dyn Material>) -> Sphere {
+    pub fn new(center: Point3, radius: f32, material: Arc<dyn Material>) -> Sphere {
         Sphere {
             center,
             radius,
             material,
         }
     }
-}
 
-impl Hittable for Sphere {
-    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord> {
-        use math::{dot, EuclideanSpace};
-        
+    // Where the line of the ray enters and exits the sphere, if it crosses it.
+    fn solutions(&self, ray: &Ray) -> Option<[f32; 2]> {
+        use math::dot;
+
         let oc = ray.origin - self.center;
         let a = dot(ray.direction, ray.direction);
         let b = dot(ray.direction, oc);
@@ -50,23 +57,31 @@ impl Hittable for Sphere {
         }
 
         let d_sqrt = discriminant.sqrt();
-        let solutions = [(-b - d_sqrt) / a, (-b + d_sqrt) / a];
-        solutions
-        .iter()
-        .find(|&&x| hit_interval.min() < x && x < hit_interval.max()) 
-        .and_then(|x| {
-            let t = *x;
-            let hit_point = ray.at(t);
-            let normal = (hit_point - self.center) / self.radius;
-            let unit_sphere_coords = Point3::from_vec(normal);
-            Some(HitRecord {
-                t,
-                hit_point,
-                normal,
-                material: self.material.as_ref(),
-                uv: TextureCoordinates::from_unit_sphere_coordinates(&unit_sphere_coords),
-            })
-        })
+        Some([(-b - d_sqrt) / a, (-b + d_sqrt) / a])
+    }
+
+    fn hit_record(&self, ray: &Ray, t: f32) -> HitRecord<'_> {
+        use math::EuclideanSpace;
+
+        let hit_point = ray.at(t);
+        let normal = (hit_point - self.center) / self.radius;
+        let unit_sphere_coords = Point3::from_vec(normal);
+        HitRecord {
+            t,
+            hit_point,
+            normal,
+            material: self.material.as_ref(),
+            uv: TextureCoordinates::from_unit_sphere_coordinates(&unit_sphere_coords),
+        }
+    }
+}
+
+impl Hittable for Sphere {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        self.solutions(ray)?
+            .iter()
+            .find(|&&x| hit_interval.min() < x && x < hit_interval.max())
+            .map(|&t| self.hit_record(ray, t))
     }
 
     fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
@@ -76,18 +91,69 @@ impl Hittable for Sphere {
             max: self.center + v,
         })
     }
+
+    // directions are sampled uniformly from the cone the sphere subtends
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        use math::InnerSpace;
+
+        let ray = Ray::new(*origin, *direction, 0.0);
+        let distance_squared = (self.center - origin).magnitude2();
+        if distance_squared <= self.radius * self.radius || self.hit(&ray, &shapes::sampling_hit_interval()).is_none() {
+            return 0.0;
+        }
+        let cos_theta_max = (1.0 - self.radius * self.radius / distance_squared).sqrt();
+        1.0 / (2.0 * PI * (1.0 - cos_theta_max))
+    }
+
+    fn random(&self, origin: &Point3, u: (f32, f32)) -> Vec3 {
+        use math::InnerSpace;
+
+        let direction = self.center - origin;
+        let distance_squared = direction.magnitude2();
+        if distance_squared <= self.radius * self.radius {
+            return sampling::uniform_sphere(u);
+        }
+        let cos_theta_max = (1.0 - self.radius * self.radius / distance_squared).sqrt();
+        let (r1, r2) = u;
+        let z = 1.0 + r2 * (cos_theta_max - 1.0);
+        let phi = 2.0 * PI * r1;
+        let sin_theta = (1.0 - z * z).sqrt();
+        math::Onb::from_w(&direction).local(&math::vec3(phi.cos() * sin_theta, phi.sin() * sin_theta, z))
+    }
+
+    fn is_light(&self) -> bool {
+        self.material.is_emissive()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        match self.solutions(ray) {
+            Some([t0, t1]) => vec![Span {
+                enter: self.hit_record(ray, t0),
+                exit: self.hit_record(ray, t1),
+            }],
+            None => Vec::new(),
+        }
+    }
+
+    fn is_solid(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)] 
 mod tests {
     use super::*;
-    use crate::materials::Dielectric;
+    use crate::{
+        materials::Dielectric,
+        random::Rng,
+    };
+
     #[test]
     fn ray_outside_a_sphere_does_not_hit_it() {
         let sphere = Sphere::new(
             Point3::new(2.0, 2.0, 2.0),
             2.0,
-            Box::new(Dielectric::new(1.5)),
+            Arc::new(Dielectric::new(1.5)),
         );
         let ray = Ray::new(
             Point3::new(0.0, 0.0, 0.0),
@@ -106,7 +172,7 @@ mod tests {
         let sphere = Sphere::new(
             Point3::new(2.0, 2.0, 2.0),
             2.0,
-            Box::new(Dielectric::new(1.5)),
+            Arc::new(Dielectric::new(1.5)),
         );
         let ray = Ray::new(
             Point3::new(0.0, 0.0, 0.0),
@@ -119,4 +185,17 @@ mod tests {
 
         assert!(hit_record.is_some());
     }
+
+    #[test]
+    fn random_directions_point_at_the_sphere() {
+        let sphere = Sphere::new(Point3::new(0.0, 0.0, 5.0), 1.0, Arc::new(Dielectric::new(1.5)));
+        let origin = Point3::new(0.0, 0.0, 0.0);
+        let mut rng = Rng::new(0);
+
+        for _ in 0..100 {
+            let direction = sphere.random(&origin, (rng.random_float_from_0_to_1(), rng.random_float_from_0_to_1()));
+            assert!(sphere.pdf_value(&origin, &direction) > 0.0);
+        }
+        assert_eq!(sphere.pdf_value(&origin, &math::vec3(0.0, 0.0, -1.0)), 0.0);
+    }
 }
\ No newline at end of file
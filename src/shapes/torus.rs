@@ -0,0 +1,176 @@
+use crate::{
+    math::{
+        self,
+        Interval,
+        InnerSpace,
+        EuclideanSpace,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+    },
+    materials::{
+        Material,
+    },
+    aabb::{
+        AABB,
+    },
+    textures::{
+        TextureCoordinates,
+    },
+    shapes::{
+        revolution::{
+            self,
+            Limits,
+        },
+    },
+};
+use std::{
+    f32::consts::PI,
+    sync::Arc,
+};
+
+// A ring around the y axis centred at the origin.
+pub struct Torus {
+    // from the centre to the middle of the tube
+    major_radius: f32,
+    // of the tube
+    minor_radius: f32,
+    limits: Limits,
+    material: Arc<dyn Material>,
+}
+
+impl Torus {
+    pub fn new(major_radius: f32, minor_radius: f32, material: Arc<dyn Material>) -> Self {
+        Self {
+            major_radius,
+            minor_radius,
+            limits: Limits::new(-minor_radius, minor_radius),
+            material,
+        }
+    }
+
+    // Keeps the part of the ring up to the angle from the x axis towards z.
+    pub fn with_sweep(self, degrees: f32) -> Self {
+        Self {
+            limits: self.limits.with_sweep(degrees),
+            ..self
+        }
+    }
+
+    pub fn with_height_limits(self, y_min: f32, y_max: f32) -> Self {
+        Self {
+            limits: self.limits.with_heights(y_min, y_max),
+            ..self
+        }
+    }
+}
+
+impl Hittable for Torus {
+    // Solves (|p|^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + z^2) for p on the ray, a quartic in t.
+    // The ray starts from its closest point to the centre so that the coefficients stay small.
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let direction = ray.direction.cast::<f64>().unwrap();
+        let start = -math::dot(ray.origin.to_vec(), ray.direction) / ray.direction.magnitude2();
+        let origin = ray.at(start).to_vec().cast::<f64>().unwrap();
+        let (major, minor) = (self.major_radius as f64, self.minor_radius as f64);
+
+        let a = direction.magnitude2();
+        let b = 2.0 * origin.dot(direction);
+        let c = origin.magnitude2() + major * major - minor * minor;
+        let four_major_squared = 4.0 * major * major;
+        let roots = math::solve_quartic(
+            a * a,
+            2.0 * a * b,
+            b * b + 2.0 * a * c - four_major_squared * (direction.x * direction.x + direction.z * direction.z),
+            2.0 * b * c - 2.0 * four_major_squared * (origin.x * direction.x + origin.z * direction.z),
+            c * c - four_major_squared * (origin.x * origin.x + origin.z * origin.z),
+        );
+        let ts = roots.iter().map(|&s| start + s as f32).collect::<Vec<_>>();
+        let (t, hit_point, phi) = revolution::first_hit(ray, hit_interval, &ts, &self.limits)?;
+
+        // away from the circle through the middle of the tube
+        let ring = (hit_point.x * hit_point.x + hit_point.z * hit_point.z).sqrt();
+        let centre = math::vec3(hit_point.x, 0.0, hit_point.z) * (self.major_radius / ring);
+        let normal = (hit_point.to_vec() - centre).normalize();
+        // the angle around the tube from its outer equator towards the top
+        let theta = hit_point.y.atan2(ring - self.major_radius);
+        let theta = if theta < 0.0 { theta + 2.0 * PI } else { theta };
+        Some(HitRecord {
+            t,
+            hit_point,
+            normal,
+            material: self.material.as_ref(),
+            uv: TextureCoordinates{ u: self.limits.u(phi), v: theta / (2.0 * PI) },
+        })
+    }
+
+    fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
+        Some(self.limits.bounding_box(self.major_radius + self.minor_radius))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_helpers::hit_interval,
+        materials::Dielectric,
+        math::Point3,
+    };
+
+    fn torus() -> Torus {
+        Torus::new(2.0, 0.5, Arc::new(Dielectric::new(1.5)))
+    }
+
+    #[test]
+    fn ray_hits_the_tube_and_passes_through_the_hole() {
+        let torus = torus();
+        let ray = Ray::new(Point3::new(2.0, 5.0, 0.0), math::vec3(0.0, -1.0, 0.0), 0.0);
+
+        let hit = torus.hit(&ray, &hit_interval()).unwrap();
+        assert!((hit.t - 4.5).abs() < 1e-4, "{}", hit.t);
+        assert!((hit.normal - math::vec3(0.0, 1.0, 0.0)).magnitude() < 1e-4);
+        assert!((hit.uv.v - 0.25).abs() < 1e-4);
+
+        let ray = Ray::new(Point3::new(0.0, 5.0, 0.0), math::vec3(0.0, -1.0, 0.0), 0.0);
+        assert!(torus.hit(&ray, &hit_interval()).is_none());
+    }
+
+    #[test]
+    fn ray_through_the_middle_hits_all_four_crossings() {
+        let torus = torus();
+        let mut t = 0.0;
+        let mut crossings = Vec::new();
+        let ray = Ray::new(Point3::new(-10.0, 0.0, 0.0), math::vec3(1.0, 0.0, 0.0), 0.0);
+        while let Some(hit) = torus.hit(&ray, &Interval::new(t + 1e-3, 100.0).unwrap()) {
+            t = hit.t;
+            crossings.push(hit.hit_point.x);
+        }
+
+        assert_eq!(crossings.len(), 4);
+        for (x, expected) in crossings.iter().zip(&[-2.5, -1.5, 1.5, 2.5]) {
+            assert!((x - expected).abs() < 1e-4, "{:?}", crossings);
+        }
+    }
+
+    #[test]
+    fn distant_grazing_rays_stay_accurate() {
+        let torus = torus();
+        let ray = Ray::new(Point3::new(-1000.0, 0.49, 0.0), math::vec3(1.0, 0.0, 0.0), 0.0);
+
+        let hit = torus.hit(&ray, &Interval::new(0.001, 10000.0).unwrap()).unwrap();
+        let expected = 2.0 + (0.25f32 - 0.49 * 0.49).sqrt();
+        assert!((hit.hit_point.x + expected).abs() < 1e-3, "{}", hit.hit_point.x);
+    }
+
+    #[test]
+    fn sweep_and_heights_narrow_the_box() {
+        let bbox = torus().with_sweep(90.0).with_height_limits(0.0, 1.0).bounding_box(&hit_interval()).unwrap();
+
+        assert!(bbox.min.x.abs() < 1e-5 && bbox.min.y.abs() < 1e-5 && bbox.min.z.abs() < 1e-5);
+        assert!((bbox.max.x - 2.5).abs() < 1e-5);
+        assert!((bbox.max.y - 0.5).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{
+    math::{
+        self,
+        Point3,
+        Vec3,
+        Interval,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+    },
+    materials::{
+        Material,
+    },
+    aabb::{
+        AABB,
+    },
+    textures::{
+        TextureCoordinates,
+    },
+    shapes,
+};
+use std::sync::Arc;
+
+pub struct Triangle {
+    vertices: [Point3; 3],
+    normals: Option<[Vec3; 3]>,
+    uvs: Option<[TextureCoordinates; 3]>,
+    material: Arc<dyn Material>,
+}
+
+impl Triangle {
+    pub fn new(vertices: [Point3; 3], material: Arc<dyn Material>) -> Self {
+        Self {
+            vertices,
+            normals: None,
+            uvs: None,
+            material,
+        }
+    }
+
+    // per vertex normals which are interpolated over the surface
+    pub fn with_normals(self, normals: [Vec3; 3]) -> Self {
+        Self {
+            normals: Some(normals),
+            ..self
+        }
+    }
+
+    pub fn with_uvs(self, uvs: [TextureCoordinates; 3]) -> Self {
+        Self {
+            uvs: Some(uvs),
+            ..self
+        }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        hit(ray, hit_interval, &self.vertices, self.normals.as_ref(), self.uvs.as_ref(), self.material.as_ref())
+    }
+
+    fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
+        Some(bounding_box(&self.vertices))
+    }
+
+    // the geometric normal gives the cosine, vertex normals only shade the surface
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        use math::InnerSpace;
+
+        let ray = Ray::new(*origin, *direction, 0.0);
+        let hit_record = hit(&ray, &shapes::sampling_hit_interval(), &self.vertices, None, None, self.material.as_ref());
+        let area = 0.5 * (self.vertices[1] - self.vertices[0]).cross(self.vertices[2] - self.vertices[0]).magnitude();
+        shapes::area_pdf_to_solid_angle(&ray, hit_record, area)
+    }
+
+    // uniform over the area by folding the square onto the triangle with a square root
+    fn random(&self, origin: &Point3, (u, v): (f32, f32)) -> Vec3 {
+        let su = u.sqrt();
+        let [a, b, c] = self.vertices;
+        let point = a + su * (1.0 - v) * (b - a) + su * v * (c - a);
+        point - origin
+    }
+
+    fn is_light(&self) -> bool {
+        self.material.is_emissive()
+    }
+}
+
+// Möller-Trumbore ray-triangle intersection.
+// Returns t and the barycentric coordinates of the hit point with respect to the second and third vertex.
+fn intersect(ray: &Ray, hit_interval: &Interval<f32>, vertices: &[Point3; 3]) -> Option<(f32, f32, f32)> {
+    use math::dot;
+
+    let edge1 = vertices[1] - vertices[0];
+    let edge2 = vertices[2] - vertices[0];
+    let p = ray.direction.cross(edge2);
+    let determinant = dot(edge1, p);
+    if determinant.abs() < 1e-12 {
+        return None;
+    }
+
+    let inv_determinant = 1.0 / determinant;
+    let s = ray.origin - vertices[0];
+    let b1 = dot(s, p) * inv_determinant;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+    let q = s.cross(edge1);
+    let b2 = dot(ray.direction, q) * inv_determinant;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+    let t = dot(edge2, q) * inv_determinant;
+    if hit_interval.min() < t && t < hit_interval.max() {
+        Some((t, b1, b2))
+    }
+    else {
+        None
+    }
+}
+
+// Hits a triangle with optional per vertex normals and texture coordinates.
+// Without texture coordinates the barycentric coordinates are used instead.
+pub(super) fn hit<'a>(
+    ray: &Ray,
+    hit_interval: &Interval<f32>,
+    vertices: &[Point3; 3],
+    normals: Option<&[Vec3; 3]>,
+    uvs: Option<&[TextureCoordinates; 3]>,
+    material: &'a dyn Material
+) -> Option<HitRecord<'a>> {
+    use math::InnerSpace;
+
+    let (t, b1, b2) = intersect(ray, hit_interval, vertices)?;
+    let b0 = 1.0 - b1 - b2;
+    let normal = match normals {
+        Some(n) => (b0 * n[0] + b1 * n[1] + b2 * n[2]).normalize(),
+        None => (vertices[1] - vertices[0]).cross(vertices[2] - vertices[0]).normalize(),
+    };
+    let uv = match uvs {
+        Some(uv) => TextureCoordinates {
+            u: b0 * uv[0].u + b1 * uv[1].u + b2 * uv[2].u,
+            v: b0 * uv[0].v + b1 * uv[1].v + b2 * uv[2].v,
+        },
+        None => TextureCoordinates{ u: b1, v: b2 },
+    };
+    Some(HitRecord {
+        t,
+        hit_point: ray.at(t),
+        normal,
+        material,
+        uv,
+    })
+}
+
+pub(super) fn bounding_box(vertices: &[Point3; 3]) -> AABB {
+    shapes::points_bounding_box(vertices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::Dielectric;
+
+    fn triangle() -> Triangle {
+        let vertices = [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+        ];
+        Triangle::new(vertices, Arc::new(Dielectric::new(1.5)))
+    }
+
+    #[test]
+    fn ray_through_the_triangle_hits_it() {
+        let ray = Ray::new(Point3::new(0.25, 0.25, -1.0), math::vec3(0.0, 0.0, 1.0), 1.0);
+        let hit_interval = Interval::new(0.0, 100.0).unwrap();
+        let triangle = triangle();
+
+        let hit_record = triangle.hit(&ray, &hit_interval).unwrap();
+
+        assert!((hit_record.t - 1.0).abs() < 1e-6);
+        assert!((hit_record.uv.u - 0.25).abs() < 1e-6);
+        assert!((hit_record.uv.v - 0.25).abs() < 1e-6);
+        assert_eq!(hit_record.normal, math::vec3(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn ray_outside_the_triangle_does_not_hit_it() {
+        let ray = Ray::new(Point3::new(0.75, 0.75, -1.0), math::vec3(0.0, 0.0, 1.0), 1.0);
+        let hit_interval = Interval::new(0.0, 100.0).unwrap();
+
+        assert!(triangle().hit(&ray, &hit_interval).is_none());
+    }
+
+    #[test]
+    fn ray_parallel_to_the_triangle_does_not_hit_it() {
+        let ray = Ray::new(Point3::new(-1.0, 0.25, 0.0), math::vec3(1.0, 0.0, 0.0), 1.0);
+        let hit_interval = Interval::new(0.0, 100.0).unwrap();
+
+        assert!(triangle().hit(&ray, &hit_interval).is_none());
+    }
+
+    #[test]
+    fn vertex_normals_are_interpolated() {
+        let normals = [
+            math::vec3(0.0, 0.0, 1.0),
+            math::vec3(1.0, 0.0, 0.0),
+            math::vec3(1.0, 0.0, 0.0),
+        ];
+        let triangle = triangle().with_normals(normals);
+        let ray = Ray::new(Point3::new(0.25, 0.25, -1.0), math::vec3(0.0, 0.0, 1.0), 1.0);
+        let hit_interval = Interval::new(0.0, 100.0).unwrap();
+
+        let normal = triangle.hit(&ray, &hit_interval).unwrap().normal;
+
+        let expected = 1.0 / 2.0f32.sqrt();
+        assert!((normal.x - expected).abs() < 1e-6);
+        assert!((normal.z - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn pdf_value_of_a_direction_towards_the_triangle() {
+        let vertices = [
+            Point3::new(-1.0, 2.0, -1.0),
+            Point3::new(1.0, 2.0, -1.0),
+            Point3::new(-1.0, 2.0, 1.0),
+        ];
+        // tilted vertex normals don't change the density
+        let light = Triangle::new(vertices, Arc::new(Dielectric::new(1.5)))
+            .with_normals([math::vec3(1.0, 0.0, 0.0); 3]);
+        let origin = Point3::new(0.0, 0.0, 0.0);
+
+        // distance squared over the area
+        assert!((light.pdf_value(&origin, &math::vec3(-0.5, 2.0, -0.5)) - 4.5 / 2.0 / (2.0 / 4.5f32.sqrt())).abs() < 1e-5);
+        for i in 0..10 {
+            let direction = light.random(&origin, (i as f32 / 10.0, 0.7));
+            assert!(light.pdf_value(&origin, &direction) > 0.0);
+        }
+        assert_eq!(light.pdf_value(&origin, &math::vec3(0.5, 2.0, 0.5)), 0.0);
+    }
+}
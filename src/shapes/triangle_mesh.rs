@@ -0,0 +1,195 @@
+use crate::{
+    math::{
+        Point3,
+        Vec3,
+        Interval,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+    },
+    materials::{
+        Material,
+    },
+    aabb::{
+        AABB,
+    },
+    textures::{
+        TextureCoordinates,
+    },
+    shapes::{
+        obj,
+        triangle,
+    },
+    bvh,
+};
+use std::{
+    path::Path,
+    sync::Arc,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VertexIndices {
+    pub position: usize,
+    pub normal: Option<usize>,
+    pub uv: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MeshBuffers {
+    pub positions: Vec<Point3>,
+    pub normals: Vec<Vec3>,
+    pub uvs: Vec<TextureCoordinates>,
+    pub faces: Vec<[VertexIndices; 3]>,
+}
+
+struct MeshData {
+    buffers: MeshBuffers,
+    material: Arc<dyn Material>,
+}
+
+// A triangle referring to a face of the shared mesh buffers.
+struct MeshTriangle {
+    mesh: Arc<MeshData>,
+    face: usize,
+}
+
+impl MeshTriangle {
+    fn vertices(&self) -> [Point3; 3] {
+        let face = &self.mesh.buffers.faces[self.face];
+        let positions = &self.mesh.buffers.positions;
+        [positions[face[0].position], positions[face[1].position], positions[face[2].position]]
+    }
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let buffers = &self.mesh.buffers;
+        let face = &buffers.faces[self.face];
+        let normals = match (face[0].normal, face[1].normal, face[2].normal) {
+            (Some(a), Some(b), Some(c)) => Some([buffers.normals[a], buffers.normals[b], buffers.normals[c]]),
+            _ => None,
+        };
+        let uvs = match (face[0].uv, face[1].uv, face[2].uv) {
+            (Some(a), Some(b), Some(c)) => Some([buffers.uvs[a], buffers.uvs[b], buffers.uvs[c]]),
+            _ => None,
+        };
+        triangle::hit(ray, hit_interval, &self.vertices(), normals.as_ref(), uvs.as_ref(), self.mesh.material.as_ref())
+    }
+
+    fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
+        Some(triangle::bounding_box(&self.vertices()))
+    }
+}
+
+// A triangle mesh with its own BVH over its faces.
+pub struct TriangleMesh {
+    root: bvh::FlatBvh,
+}
+
+impl TriangleMesh {
+    pub fn new(buffers: MeshBuffers, material: Arc<dyn Material>) -> Result<Self, String> {
+        validate(&buffers)?;
+        let face_count = buffers.faces.len();
+        let mesh = Arc::new(MeshData {
+            buffers,
+            material,
+        });
+        let triangles = (0..face_count)
+            .map(|face| Box::new(MeshTriangle{ mesh: mesh.clone(), face }) as Box<dyn Hittable>)
+            .collect::<Vec<_>>();
+        let root = bvh::FlatBvh::new(triangles, &Interval::new(0.0, 1.0).unwrap(), &bvh::Options::default());
+
+        Ok(Self {
+            root,
+        })
+    }
+
+    pub fn from_obj_file(path: &Path, material: Arc<dyn Material>) -> Result<Self, String> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| format!("Couldn't read {}: {}", path.display(), e))?;
+        obj::parse(&source)
+            .and_then(|buffers| Self::new(buffers, material))
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+fn validate(buffers: &MeshBuffers) -> Result<(), String> {
+    if buffers.faces.is_empty() {
+        return Err("the mesh has no faces".to_owned());
+    }
+    let in_range = |index: Option<usize>, len| index.is_none_or(|i| i < len);
+    for (i, face) in buffers.faces.iter().enumerate() {
+        for vertex in face {
+            if !in_range(Some(vertex.position), buffers.positions.len()) ||
+               !in_range(vertex.normal, buffers.normals.len()) ||
+               !in_range(vertex.uv, buffers.uvs.len()) {
+                return Err(format!("face {} refers to a missing vertex attribute", i + 1));
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        self.root.hit(ray, hit_interval)
+    }
+
+    fn bounding_box(&self, time_interval: &Interval<f32>) -> Option<AABB> {
+        self.root.bounding_box(time_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        materials::Dielectric,
+        math,
+    };
+
+    fn quad_buffers() -> MeshBuffers {
+        let vertex = |position| VertexIndices{ position, normal: None, uv: None };
+        MeshBuffers {
+            positions: vec![
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(1.0, 1.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+            ],
+            faces: vec![
+                [vertex(0), vertex(1), vertex(2)],
+                [vertex(0), vertex(2), vertex(3)],
+            ],
+            ..MeshBuffers::default()
+        }
+    }
+
+    #[test]
+    fn ray_through_the_mesh_hits_it() {
+        let mesh = TriangleMesh::new(quad_buffers(), Arc::new(Dielectric::new(1.5))).unwrap();
+        let hit_interval = Interval::new(0.0, 100.0).unwrap();
+        let ray = Ray::new(Point3::new(0.2, 0.8, -1.0), math::vec3(0.0, 0.0, 1.0), 1.0);
+
+        assert!(mesh.hit(&ray, &hit_interval).is_some());
+    }
+
+    #[test]
+    fn ray_outside_the_mesh_does_not_hit_it() {
+        let mesh = TriangleMesh::new(quad_buffers(), Arc::new(Dielectric::new(1.5))).unwrap();
+        let hit_interval = Interval::new(0.0, 100.0).unwrap();
+        let ray = Ray::new(Point3::new(1.2, 0.8, -1.0), math::vec3(0.0, 0.0, 1.0), 1.0);
+
+        assert!(mesh.hit(&ray, &hit_interval).is_none());
+    }
+
+    #[test]
+    fn face_with_invalid_index_is_rejected() {
+        let mut buffers = quad_buffers();
+        buffers.faces[1][2].position = 4;
+
+        assert!(TriangleMesh::new(buffers, Arc::new(Dielectric::new(1.5))).is_err());
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     math::{
         self,
         Point3, 
+        Vec3,
         Interval,
     },
     core::{
@@ -17,8 +18,10 @@ use crate::{
     },
     textures::{
         TextureCoordinates,
-    }
+    },
+    shapes,
 };
+use std::sync::Arc;
 
 pub struct XYRectangle {
     x0: f32,
@@ -26,11 +29,11 @@ pub struct XYRectangle {
     y0: f32,
     y1: f32,
     z: f32,
-    material: Box<dyn Material>,
+    material: Arc<dyn Material>,
 }
 
 impl XYRectangle {
-    pub fn new(x0: f32, x1: f32, y0: f32, y1: f32, z: f32, material: Box<dyn Material>) -> Self {
+    pub fn new(x0: f32, x1: f32, y0: f32, y1: f32, z: f32, material: Arc<dyn Material>) -> Self {
         Self {
             x0,
             x1,
@@ -43,9 +46,9 @@ impl XYRectangle {
 }
 
 impl Hittable for XYRectangle {
-    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
         let t = (self.z - ray.origin.z) / ray.direction.z;
-        if t < hit_interval.min() || t > hit_interval.max() { 
+        if !(hit_interval.min() < t && t < hit_interval.max()) {
             return None;
         }
 
@@ -74,6 +77,25 @@ impl Hittable for XYRectangle {
             max: Point3::new(self.x1, self.y1, self.z + 0.0001),
         })
     }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        let ray = Ray::new(*origin, *direction, 0.0);
+        let area = (self.x1 - self.x0) * (self.y1 - self.y0);
+        shapes::area_pdf_to_solid_angle(&ray, self.hit(&ray, &shapes::sampling_hit_interval()), area)
+    }
+
+    fn random(&self, origin: &Point3, (u, v): (f32, f32)) -> Vec3 {
+        let point = Point3::new(
+            self.x0 + u * (self.x1 - self.x0),
+            self.y0 + v * (self.y1 - self.y0),
+            self.z,
+        );
+        point - origin
+    }
+
+    fn is_light(&self) -> bool {
+        self.material.is_emissive()
+    }
 }
 
 #[cfg(test)]
@@ -83,7 +105,7 @@ mod tests {
 
     #[test]
     fn ray_through_the_rectangle_hits_it() {
-        let material = Box::new(Dielectric::new(1.5));
+        let material = Arc::new(Dielectric::new(1.5));
         let rect = XYRectangle::new(0.0, 10.0, 0.0, 10.0, -4.0, material);
         let hit_interval = Interval::new(0.0, 100.0).unwrap();
         let ray = Ray::new(Point3::new(1.0, -3.0, 0.0), math::vec3(1.0, 3.0, -1.0), 1.0);
@@ -93,7 +115,7 @@ mod tests {
     
     #[test]
     fn ray_outside_the_rectangle_does_not_hit_it() {
-        let material = Box::new(Dielectric::new(1.5));
+        let material = Arc::new(Dielectric::new(1.5));
         let rect = XYRectangle::new(0.0, 10.0, 0.0, 10.0, -4.0, material);
         let hit_interval = Interval::new(0.0, 100.0).unwrap();
         let ray = Ray::new(Point3::new(1.0, -3.0, 0.0), math::vec3(1.0, -3.0, -1.0), 1.0);
@@ -2,6 +2,7 @@ use crate::{
     math::{
         self,
         Point3, 
+        Vec3,
         Interval,
     },
     core::{
@@ -17,8 +18,10 @@ use crate::{
     },
     textures::{
         TextureCoordinates,
-    }
+    },
+    shapes,
 };
+use std::sync::Arc;
 
 pub struct YZRectangle {
     y0: f32,
@@ -26,11 +29,11 @@ pub struct YZRectangle {
     z0: f32,
     z1: f32,
     x: f32,
-    material: Box<dyn Material>,
+    material: Arc<dyn Material>,
 }
 
 impl YZRectangle {
-    pub fn new(y0: f32, y1: f32, z0: f32, z1: f32, x: f32, material: Box<dyn Material>) -> Self {
+    pub fn new(y0: f32, y1: f32, z0: f32, z1: f32, x: f32, material: Arc<dyn Material>) -> Self {
         Self {
             y0,
             y1,
@@ -43,9 +46,9 @@ impl YZRectangle {
 }
 
 impl Hittable for YZRectangle {
-    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
         let t = (self.x - ray.origin.x) / ray.direction.x;
-        if t < hit_interval.min() || t > hit_interval.max() { 
+        if !(hit_interval.min() < t && t < hit_interval.max()) {
             return None;
         }
 
@@ -74,6 +77,25 @@ impl Hittable for YZRectangle {
             max: Point3::new(self.x + 0.0001, self.y1, self.z1),
         })
     }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        let ray = Ray::new(*origin, *direction, 0.0);
+        let area = (self.y1 - self.y0) * (self.z1 - self.z0);
+        shapes::area_pdf_to_solid_angle(&ray, self.hit(&ray, &shapes::sampling_hit_interval()), area)
+    }
+
+    fn random(&self, origin: &Point3, (u, v): (f32, f32)) -> Vec3 {
+        let point = Point3::new(
+            self.x,
+            self.y0 + u * (self.y1 - self.y0),
+            self.z0 + v * (self.z1 - self.z0),
+        );
+        point - origin
+    }
+
+    fn is_light(&self) -> bool {
+        self.material.is_emissive()
+    }
 }
 
 #[cfg(test)]
@@ -83,9 +105,9 @@ mod tests {
 
     #[test]
     fn ray_through_the_rectangle_hits_it() {
-        let material = Box::new(Dielectric::new(1.5));
+        let material = Arc::new(Dielectric::new(1.5));
         let rect = YZRectangle::new(0.0, 10.0, 0.0, 10.0, 4.0, material);
-        let hit_interval = Interval::new(0.0, std::f32::MAX).unwrap();
+        let hit_interval = Interval::new(0.0, f32::MAX).unwrap();
         let ray = Ray::new(Point3::new(-2.0, 2.0, 2.0), math::vec3(2.0, 2.0, 2.0), 1.0);
 
         assert!(rect.hit(&ray, &hit_interval).is_some());
@@ -93,9 +115,9 @@ mod tests {
     
     #[test]
     fn ray_outside_the_rectangle_does_not_hit_it() {
-        let material = Box::new(Dielectric::new(1.5));
+        let material = Arc::new(Dielectric::new(1.5));
         let rect = YZRectangle::new(0.0, 10.0, 0.0, 10.0, 4.0, material);
-        let hit_interval = Interval::new(0.0, std::f32::MAX).unwrap();
+        let hit_interval = Interval::new(0.0, f32::MAX).unwrap();
         let ray = Ray::new(Point3::new(-2.0, 2.0, 2.0), math::vec3(2.0,-2.0, 2.0), 1.0);
 
         assert!(rect.hit(&ray, &hit_interval).is_none());
@@ -0,0 +1,29 @@
+// Fixtures shared by the unit tests.
+
+use crate::{
+    math::{
+        Point3,
+        Interval,
+    },
+    core::{
+        Hittable,
+    },
+    materials::{
+        Dielectric,
+    },
+    shapes::{
+        Parallelepiped,
+    },
+};
+use std::sync::Arc;
+
+// everything in front of a ray, past the offset which keeps it off the surface it starts on
+pub fn hit_interval() -> Interval<f32> {
+    Interval::new(0.001, f32::MAX).unwrap()
+}
+
+// a glass cube with unit sides around the origin
+pub fn unit_cube() -> Box<dyn Hittable> {
+    let material = Arc::new(Dielectric::new(1.5));
+    Box::new(Parallelepiped::new(&Point3::new(-0.5, -0.5, -0.5), &Point3::new(0.5, 0.5, 0.5), material))
+}
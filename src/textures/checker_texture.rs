@@ -8,10 +8,11 @@ use crate::{
         Vec3,
     }
 };
+use std::sync::Arc;
 
 pub struct CheckerTexture {
-    pub even: Box<dyn Texture>,
-    pub odd:  Box<dyn Texture>,
+    pub even: Arc<dyn Texture>,
+    pub odd:  Arc<dyn Texture>,
 }
 
 impl Texture for CheckerTexture {
@@ -0,0 +1,162 @@
+use crate::{
+    math::{
+        Point3,
+        Vec3,
+    },
+    textures::{
+        Texture,
+        TextureCoordinates,
+    },
+    input::{
+        self,
+        Image,
+    },
+};
+use std::path::Path;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Filter {
+    Nearest,
+    Bilinear,
+}
+
+// How coordinates outside of [0, 1] are brought back onto the image.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Wrap {
+    Repeat,
+    Clamp,
+    Mirror,
+}
+
+// An image stretched over the texture coordinates, (0, 0) is its bottom left corner.
+pub struct ImageTexture {
+    image: Image,
+    filter: Filter,
+    wrap: Wrap,
+}
+
+impl ImageTexture {
+    pub fn new(image: Image) -> Self {
+        Self {
+            image,
+            filter: Filter::Bilinear,
+            wrap: Wrap::Repeat,
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        input::read(path).map(Self::new)
+    }
+
+    pub fn with_filter(self, filter: Filter) -> Self {
+        Self {
+            filter,
+            ..self
+        }
+    }
+
+    pub fn with_wrap(self, wrap: Wrap) -> Self {
+        Self {
+            wrap,
+            ..self
+        }
+    }
+
+    fn texel(&self, x: i64, row: i64) -> Vec3 {
+        let x = self.wrap.apply(x, self.image.width);
+        let row = self.wrap.apply(row, self.image.height);
+        self.image.get(x, row)
+    }
+}
+
+impl Wrap {
+    fn apply(self, i: i64, size: usize) -> usize {
+        let size = size as i64;
+        let i = match self {
+            Wrap::Repeat => i.rem_euclid(size),
+            Wrap::Clamp => i.clamp(0, size - 1),
+            Wrap::Mirror => {
+                let i = i.rem_euclid(2 * size);
+                if i < size { i } else { 2 * size - 1 - i }
+            },
+        };
+        i as usize
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, uv: &TextureCoordinates, _: &Point3) -> Vec3 {
+        // texel coordinates, with rows counted from the top
+        let x = uv.u * self.image.width as f32;
+        let y = (1.0 - uv.v) * self.image.height as f32;
+        match self.filter {
+            Filter::Nearest => self.texel(x.floor() as i64, y.floor() as i64),
+            Filter::Bilinear => {
+                // texel centres are at half coordinates
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (tx, ty) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as i64, y0 as i64);
+                let top = (1.0 - tx) * self.texel(x0, y0) + tx * self.texel(x0 + 1, y0);
+                let bottom = (1.0 - tx) * self.texel(x0, y0 + 1) + tx * self.texel(x0 + 1, y0 + 1);
+                (1.0 - ty) * top + ty * bottom
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::vec3;
+
+    // black on the left and white on the right, red on the bottom row
+    fn texture() -> ImageTexture {
+        ImageTexture::new(Image {
+            width: 2,
+            height: 2,
+            pixels: vec![
+                vec3(0.0, 0.0, 0.0), vec3(1.0, 1.0, 1.0),
+                vec3(1.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0),
+            ],
+        })
+    }
+
+    fn value(texture: &ImageTexture, u: f32, v: f32) -> Vec3 {
+        texture.value(&TextureCoordinates{ u, v }, &Point3::new(0.0, 0.0, 0.0))
+    }
+
+    #[test]
+    fn nearest_filtering_picks_the_texel_under_the_coordinates() {
+        let texture = texture().with_filter(Filter::Nearest);
+
+        assert_eq!(value(&texture, 0.1, 0.9), vec3(0.0, 0.0, 0.0));
+        assert_eq!(value(&texture, 0.9, 0.9), vec3(1.0, 1.0, 1.0));
+        assert_eq!(value(&texture, 0.4, 0.1), vec3(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn bilinear_filtering_blends_neighbouring_texels() {
+        let texture = texture().with_wrap(Wrap::Clamp);
+
+        assert_eq!(value(&texture, 0.25, 0.75), vec3(0.0, 0.0, 0.0));
+        assert_eq!(value(&texture, 0.5, 0.75), vec3(0.5, 0.5, 0.5));
+        assert_eq!(value(&texture, 0.5, 0.5), vec3(0.75, 0.25, 0.25));
+    }
+
+    #[test]
+    fn wrapping_modes_handle_coordinates_outside_the_image() {
+        assert_eq!(Wrap::Repeat.apply(-1, 4), 3);
+        assert_eq!(Wrap::Repeat.apply(9, 4), 1);
+        assert_eq!(Wrap::Clamp.apply(-3, 4), 0);
+        assert_eq!(Wrap::Clamp.apply(9, 4), 3);
+        assert_eq!(Wrap::Mirror.apply(-1, 4), 0);
+        assert_eq!(Wrap::Mirror.apply(5, 4), 2);
+        assert_eq!(Wrap::Mirror.apply(8, 4), 0);
+
+        let texture = texture().with_filter(Filter::Nearest);
+        assert_eq!(value(&texture, 1.1, 0.9), vec3(0.0, 0.0, 0.0));
+        let texture = texture.with_wrap(Wrap::Mirror);
+        assert_eq!(value(&texture, 1.1, 0.9), vec3(1.0, 1.0, 1.0));
+    }
+}
@@ -1,10 +1,16 @@
 mod constant_texture;
 mod checker_texture;
 mod noise_texture;
+mod image_texture;
 
 pub use constant_texture::ConstantTexture;
 pub use checker_texture::CheckerTexture;
 pub use noise_texture::NoiseTexture;
+pub use image_texture::{
+    ImageTexture,
+    Filter,
+    Wrap,
+};
 
 use crate::{
     math::{
@@ -39,6 +45,6 @@ impl TextureCoordinates {
     }
 }
 
-pub trait Texture {
+pub trait Texture: Send + Sync {
     fn value(&self, uv: &TextureCoordinates, p: &Point3) -> Vec3;
 }
@@ -8,7 +8,9 @@ use crate::{
         Point3,
         Vec3,
     },
-    random,
+    random::{
+        Rng,
+    },
 };
 
 use lazy_static::lazy_static;
@@ -47,13 +49,17 @@ struct PerlinData {
     perm_z: Vec<usize>,
 }
 
+// the noise is part of the scene, so it doesn't depend on the render seed
+const PERLIN_SEED: u64 = 0;
+
 impl PerlinData {
     pub fn new() -> Self {
+        let mut rng = Rng::new(PERLIN_SEED);
         Self {
-            random_unit_vectors: random_255_unit_vectors(),
-            perm_x: random_permutation_0_to_255(),
-            perm_y: random_permutation_0_to_255(),
-            perm_z: random_permutation_0_to_255(),
+            random_unit_vectors: random_255_unit_vectors(&mut rng),
+            perm_x: random_permutation_0_to_255(&mut rng),
+            perm_y: random_permutation_0_to_255(&mut rng),
+            perm_z: random_permutation_0_to_255(&mut rng),
         }
     }
 
@@ -85,6 +91,7 @@ impl PerlinData {
         perlin_interpolation(&cube, u, v, w)
     }
 
+    #[allow(clippy::needless_range_loop)]
     fn random_unit_vecs_cube(&self, i: usize, j: usize, k: usize) -> [[[Vec3; 2]; 2]; 2] {
         let mut result = [[[math::vec3(0.0, 0.0, 0.0); 2]; 2]; 2];
         for di in 0..2 {
@@ -102,9 +109,9 @@ impl PerlinData {
     }
 }
 
-fn random_255_unit_vectors() -> Vec<Vec3> {
+fn random_255_unit_vectors(rng: &mut Rng) -> Vec<Vec3> {
     use math::InnerSpace;
-    let rand = || 2.0*random::random_float_from_0_to_1() - 1.0;
+    let mut rand = || 2.0*rng.random_float_from_0_to_1() - 1.0;
     (0..256)
     .map(|_| {
         math::vec3(rand(), rand(), rand()).normalize()
@@ -112,22 +119,21 @@ fn random_255_unit_vectors() -> Vec<Vec3> {
     .collect()
 }
 
-fn random_permutation_0_to_255() -> Vec<usize> {
+fn random_permutation_0_to_255(rng: &mut Rng) -> Vec<usize> {
     let mut result = (0..256).collect::<Vec<usize>>();
-    permute(&mut result);
+    permute(&mut result, rng);
     result
 }
 
-fn permute(vec: &mut Vec<usize>) {
-    use random::random_float_from_0_to_1;
-    
+fn permute(vec: &mut Vec<usize>, rng: &mut Rng) {
     for i in (1..vec.len()).rev() {
-        let target = random_float_from_0_to_1()*(i as f32 + 1.0);
+        let target = rng.random_float_from_0_to_1()*(i as f32 + 1.0);
         let target = target.floor() as usize;
         vec.as_mut_slice().swap(i, target);
     }
 }
 
+#[allow(clippy::needless_range_loop)]
 fn perlin_interpolation(coefficients: &[[[Vec3; 2]; 2]; 2], u: f32, v: f32, w: f32) -> f32 {
     let p = Point3::new(u, v, w).map(|c| c*c*(3.0 - 2.0*c));
     let (uu, vv, ww) = (p.x, p.y, p.z);
@@ -0,0 +1,168 @@
+use crate::{
+    math::{
+        Vec3,
+        Point3,
+        Interval,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+        Span,
+    },
+    materials::{
+        Material,
+    },
+    aabb::{
+        AABB,
+    },
+    transformations::{
+        Transform,
+    },
+};
+use std::sync::Arc;
+
+// A placement of a shared hittable, usually a bottom level BVH built once with `bvh::build`
+// for many instances. Only the transformation and the optional material replacing the ones
+// of the shared hittable are stored per instance.
+pub struct Instance {
+    transform: Transform<Arc<dyn Hittable>>,
+    material: Option<Arc<dyn Material>>,
+}
+
+impl Instance {
+    pub fn new(transform: Transform<Arc<dyn Hittable>>) -> Self {
+        Self {
+            transform,
+            material: None,
+        }
+    }
+
+    pub fn with_material(self, material: Arc<dyn Material>) -> Self {
+        Self {
+            material: Some(material),
+            ..self
+        }
+    }
+
+    fn replace_material<'a>(&'a self, hit_record: HitRecord<'a>) -> HitRecord<'a> {
+        match &self.material {
+            Some(material) => HitRecord {
+                material: material.as_ref(),
+                ..hit_record
+            },
+            None => hit_record,
+        }
+    }
+}
+
+impl Hittable for Instance {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        self.transform
+            .hit(ray, hit_interval)
+            .map(|hit_record| self.replace_material(hit_record))
+    }
+
+    fn bounding_box(&self, time_interval: &Interval<f32>) -> Option<AABB> {
+        self.transform.bounding_box(time_interval)
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        self.transform.pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point3, u: (f32, f32)) -> Vec3 {
+        self.transform.random(origin, u)
+    }
+
+    // Whether the shared hittable can be sampled doesn't depend on its material, but the light it gives does,
+    // so instances with a replaced material are lit only by the paths which hit them.
+    fn is_light(&self) -> bool {
+        self.material.is_none() && self.transform.is_light()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        self.transform
+            .spans(ray)
+            .into_iter()
+            .map(|span| span.map(|hit_record| self.replace_material(hit_record)))
+            .collect()
+    }
+
+    fn is_solid(&self) -> bool {
+        self.transform.is_solid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_helpers::hit_interval,
+        bvh,
+        materials::{
+            Dielectric,
+            Lambertian,
+            DiffuseLight,
+        },
+        textures::{
+            ConstantTexture,
+        },
+        shapes::{
+            Sphere,
+        },
+        math::vec3,
+    };
+
+    // two spheres next to each other in a bottom level BVH
+    fn blas() -> Arc<dyn Hittable> {
+        let material = Arc::new(Dielectric::new(1.5));
+        let spheres: Vec<Box<dyn Hittable>> = vec![
+            Box::new(Sphere::new(Point3::new(-1.0, 0.0, 0.0), 0.5, material.clone())),
+            Box::new(Sphere::new(Point3::new(1.0, 0.0, 0.0), 0.5, material)),
+        ];
+        let time_interval = Interval::new(0.0, 1.0).unwrap();
+        let (blas, _) = bvh::build(spheres, &time_interval, &bvh::Options::default());
+        Arc::from(blas)
+    }
+
+    #[test]
+    fn instances_share_the_bottom_level_bvh() {
+        let blas = blas();
+        let instances: Vec<Instance> = (0..3)
+            .map(|i| Instance::new(Transform::new(blas.clone()).translate(vec3(0.0, 5.0 * i as f32, 0.0))))
+            .collect();
+
+        assert_eq!(Arc::strong_count(&blas), 4);
+        let ray = Ray::new(Point3::new(1.0, 10.0, -5.0), vec3(0.0, 0.0, 1.0), 0.0);
+        assert!(instances[2].hit(&ray, &hit_interval()).is_some());
+        assert!(instances[1].hit(&ray, &hit_interval()).is_none());
+        let bbox = instances[2].bounding_box(&hit_interval()).unwrap();
+        assert!((bbox.min.y - 9.5).abs() < 1e-5 && (bbox.max.x - 1.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn material_override_replaces_the_shared_material() {
+        let light = Arc::new(DiffuseLight::new(Arc::new(ConstantTexture::from_rgb(vec3(4.0, 4.0, 4.0)))));
+        let instance = Instance::new(Transform::new(blas()).scale(vec3(2.0, 2.0, 2.0))).with_material(light);
+        let ray = Ray::new(Point3::new(2.0, 0.0, -5.0), vec3(0.0, 0.0, 1.0), 0.0);
+
+        let hit = instance.hit(&ray, &hit_interval()).unwrap();
+        assert!((hit.t - 4.0).abs() < 1e-4);
+        assert!(hit.material.is_emissive());
+        assert!(!instance.is_light());
+    }
+
+    #[test]
+    fn lights_stay_lights_without_an_override() {
+        let light = Arc::new(DiffuseLight::new(Arc::new(ConstantTexture::from_rgb(vec3(4.0, 4.0, 4.0)))));
+        let sphere: Arc<dyn Hittable> = Arc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, light));
+        let lamp = Instance::new(Transform::new(sphere.clone()).translate(vec3(0.0, 3.0, 0.0)));
+        let painted = Instance::new(Transform::new(sphere))
+            .with_material(Arc::new(Lambertian::new(Arc::new(ConstantTexture::from_rgb(vec3(0.5, 0.5, 0.5))))));
+
+        assert!(lamp.is_light());
+        assert!(lamp.pdf_value(&Point3::new(0.0, 0.0, 0.0), &vec3(0.0, 1.0, 0.0)) > 0.0);
+        assert!(!painted.is_light());
+    }
+}
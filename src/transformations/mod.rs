@@ -1,5 +1,14 @@
 mod translation;
 mod y_rotation;
+mod transform;
+mod motion_transform;
+mod instance;
 
 pub use translation::Translation;
-pub use y_rotation::YRotation;
\ No newline at end of file
+pub use y_rotation::YRotation;
+pub use transform::Transform;
+pub use instance::Instance;
+pub use motion_transform::{
+    MotionTransform,
+    Keyframe,
+};
\ No newline at end of file
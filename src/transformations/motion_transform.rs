@@ -0,0 +1,292 @@
+use crate::{
+    math::{
+        self,
+        Vec3,
+        Mat4,
+        Interval,
+        InnerSpace,
+        ElementWise,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+        Span,
+    },
+    aabb::{
+        self,
+        AABB,
+    },
+    transformations::{
+        transform,
+    },
+};
+use cgmath::{
+    Deg,
+    Quaternion,
+    Rotation3,
+};
+
+// The pose of a hittable at some time, it is scaled first, then rotated and then translated.
+#[derive(Copy, Clone, Debug)]
+pub struct Keyframe {
+    pub time: f32,
+    pub scale: Vec3,
+    pub rotation: Quaternion<f32>,
+    pub translation: Vec3,
+}
+
+impl Keyframe {
+    pub fn new(time: f32) -> Self {
+        Self {
+            time,
+            scale: math::vec3(1.0, 1.0, 1.0),
+            rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            translation: math::vec3(0.0, 0.0, 0.0),
+        }
+    }
+
+    // The factors must not be zero.
+    pub fn with_scale(self, scale: Vec3) -> Self {
+        Self {
+            scale,
+            ..self
+        }
+    }
+
+    // Counterclockwise when looking down the axis, which must not be zero.
+    pub fn with_rotation(self, axis: Vec3, degrees: f32) -> Self {
+        Self {
+            rotation: Quaternion::from_axis_angle(axis.normalize(), Deg(degrees)),
+            ..self
+        }
+    }
+
+    pub fn with_translation(self, translation: Vec3) -> Self {
+        Self {
+            translation,
+            ..self
+        }
+    }
+
+    // Scale and translation are blended linearly, rotation along the shortest arc.
+    fn lerp(&self, other: &Keyframe, t: f32) -> Keyframe {
+        let other_rotation = if self.rotation.dot(other.rotation) < 0.0 { -other.rotation } else { other.rotation };
+        Keyframe {
+            time: self.time + t * (other.time - self.time),
+            scale: self.scale + t * (other.scale - self.scale),
+            rotation: self.rotation.slerp(other_rotation, t).normalize(),
+            translation: self.translation + t * (other.translation - self.translation),
+        }
+    }
+
+    fn matrix(&self) -> Mat4 {
+        Mat4::from_translation(self.translation)
+            * Mat4::from(self.rotation)
+            * Mat4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+    }
+
+    fn inverse(&self) -> Mat4 {
+        let scale = self.scale.map(|s| 1.0 / s);
+        Mat4::from_nonuniform_scale(scale.x, scale.y, scale.z)
+            * Mat4::from(self.rotation.conjugate())
+            * Mat4::from_translation(-self.translation)
+    }
+}
+
+// A hittable moving between keyframed poses over the time of the rays.
+// Before the first and after the last keyframe it stays in their pose.
+pub struct MotionTransform {
+    hittable: Box<dyn Hittable>,
+    // sorted by time
+    keyframes: Vec<Keyframe>,
+}
+
+// poses sampled between two keyframes when bounding the motion
+const BOX_SAMPLES_PER_SEGMENT: usize = 16;
+
+impl MotionTransform {
+    pub fn new(hittable: Box<dyn Hittable>, mut keyframes: Vec<Keyframe>) -> Result<Self, String> {
+        if keyframes.is_empty() {
+            return Err("at least one keyframe is needed".to_owned());
+        }
+        keyframes.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
+        if keyframes.windows(2).any(|pair| pair[0].time == pair[1].time) {
+            return Err("two keyframes have the same time".to_owned());
+        }
+        Ok(Self {
+            hittable,
+            keyframes,
+        })
+    }
+
+    fn pose(&self, time: f32) -> Keyframe {
+        let next = self.keyframes.partition_point(|k| k.time <= time);
+        if next == 0 {
+            return self.keyframes[0];
+        }
+        if next == self.keyframes.len() {
+            return self.keyframes[next - 1];
+        }
+        let (a, b) = (&self.keyframes[next - 1], &self.keyframes[next]);
+        a.lerp(b, (time - a.time) / (b.time - a.time))
+    }
+}
+
+impl Hittable for MotionTransform {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        let pose = self.pose(ray.time);
+        transform::hit_transformed(self.hittable.as_ref(), &pose.matrix(), &pose.inverse(), ray, hit_interval)
+    }
+
+    // The union of the boxes of poses sampled over the interval.
+    // Corners swing outwards between the samples, which the boxes are padded by.
+    fn bounding_box(&self, time_interval: &Interval<f32>) -> Option<AABB> {
+        let bbox = self.hittable.bounding_box(time_interval)?;
+
+        let mut times = vec![time_interval.min(), time_interval.max()];
+        for pair in self.keyframes.windows(2) {
+            let (start, end) = (pair[0].time.max(time_interval.min()), pair[1].time.min(time_interval.max()));
+            for i in 0..=BOX_SAMPLES_PER_SEGMENT {
+                let time = start + (end - start) * i as f32 / BOX_SAMPLES_PER_SEGMENT as f32;
+                if start < end {
+                    times.push(time);
+                }
+            }
+        }
+
+        let mut result: Option<AABB> = None;
+        let mut padding = 0.0f32;
+        for &time in &times {
+            let pose = self.pose(time);
+            let moved = transform::transform_aabb(&pose.matrix(), &bbox);
+            result = Some(result.map_or(moved, |r| aabb::surrounding_box(&r, &moved)));
+            // the farthest a corner is from the centre of rotation
+            let reach = (0..8)
+                .map(|i| {
+                    let corner = math::vec3(
+                        if i & 1 == 0 { bbox.min.x } else { bbox.max.x },
+                        if i & 2 == 0 { bbox.min.y } else { bbox.max.y },
+                        if i & 4 == 0 { bbox.min.z } else { bbox.max.z },
+                    );
+                    corner.mul_element_wise(pose.scale).magnitude()
+                })
+                .fold(0.0, f32::max);
+            padding = padding.max(reach);
+        }
+        // the largest rotation between two samples, a point turning by it strays
+        // at most (1 - cos(angle / 2)) of its distance from the straight path
+        let step = self.keyframes
+            .windows(2)
+            .map(|pair| {
+                let dot = pair[0].rotation.dot(pair[1].rotation).abs().min(1.0);
+                2.0 * dot.acos() / BOX_SAMPLES_PER_SEGMENT as f32
+            })
+            .fold(0.0, f32::max);
+        let padding = padding * (1.0 - (0.5 * step).cos());
+        let padding = math::vec3(padding, padding, padding);
+
+        result.map(|r| AABB {
+            min: r.min - padding,
+            max: r.max + padding,
+        })
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        let pose = self.pose(ray.time);
+        transform::spans_transformed(self.hittable.as_ref(), &pose.matrix(), &pose.inverse(), ray)
+    }
+
+    fn is_solid(&self) -> bool {
+        self.hittable.is_solid()
+    }
+
+    // Lights aren't sampled directly since the pose depends on the time of the ray,
+    // so emission from moving objects is only found by scattering.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_helpers::{
+            hit_interval,
+            unit_cube,
+        },
+        math::{
+            vec3,
+            Point3,
+        },
+    };
+
+    #[test]
+    fn translation_is_interpolated_over_the_ray_time() {
+        let keyframes = vec![
+            Keyframe::new(1.0).with_translation(vec3(4.0, 0.0, 0.0)),
+            Keyframe::new(0.0),
+        ];
+        let moving = MotionTransform::new(unit_cube(), keyframes).unwrap();
+        let ray_at = |time| Ray::new(Point3::new(2.0, 0.0, -5.0), vec3(0.0, 0.0, 1.0), time);
+
+        assert!(moving.hit(&ray_at(0.0), &hit_interval()).is_none());
+        assert!(moving.hit(&ray_at(0.5), &hit_interval()).is_some());
+        assert!(moving.hit(&ray_at(1.0), &hit_interval()).is_none());
+        // the last pose is kept after the last keyframe
+        let ray = Ray::new(Point3::new(4.0, 0.0, -5.0), vec3(0.0, 0.0, 1.0), 3.0);
+        assert!(moving.hit(&ray, &hit_interval()).is_some());
+    }
+
+    #[test]
+    fn rotation_is_interpolated_along_the_shortest_arc() {
+        let keyframes = vec![
+            Keyframe::new(0.0).with_rotation(vec3(0.0, 0.0, 1.0), -20.0),
+            // the same as 20 degrees but with the opposite quaternion
+            Keyframe::new(1.0).with_rotation(vec3(0.0, 0.0, 1.0), 380.0),
+        ];
+        let moving = MotionTransform::new(unit_cube(), keyframes).unwrap();
+
+        let pose = moving.pose(0.5);
+        let x = (pose.matrix() * vec3(1.0, 0.0, 0.0).extend(0.0)).truncate();
+        assert!((x - vec3(1.0, 0.0, 0.0)).magnitude() < 1e-4, "{:?}", x);
+        let identity = pose.matrix() * pose.inverse();
+        assert!((identity.x.x - 1.0).abs() < 1e-5 && identity.w.x.abs() < 1e-5);
+    }
+
+    #[test]
+    fn bounding_box_covers_every_pose_in_the_interval() {
+        let keyframes = vec![
+            Keyframe::new(0.0),
+            Keyframe::new(1.0).with_rotation(vec3(0.0, 1.0, 0.0), 90.0).with_translation(vec3(0.0, 2.0, 0.0)),
+            Keyframe::new(2.0).with_translation(vec3(10.0, 0.0, 0.0)),
+        ];
+        let moving = MotionTransform::new(unit_cube(), keyframes).unwrap();
+        let bbox = moving.bounding_box(&Interval::new(0.0, 1.0).unwrap()).unwrap();
+
+        // the cube turns by 45 degrees halfway, when its corners reach furthest
+        assert!(bbox.max.x >= 0.5 * 2.0f32.sqrt() - 1e-4);
+        assert!(bbox.max.y >= 2.5 && bbox.min.y <= -0.5);
+        // the last segment is outside of the interval
+        assert!(bbox.max.x < 1.0);
+
+        for i in 0..=100 {
+            let pose = moving.pose(i as f32 / 100.0);
+            for corner in 0..8 {
+                let p = vec3(
+                    if corner & 1 == 0 { -0.5 } else { 0.5 },
+                    if corner & 2 == 0 { -0.5 } else { 0.5 },
+                    if corner & 4 == 0 { -0.5 } else { 0.5 },
+                );
+                let p = transform::transform_point(&pose.matrix(), &Point3::new(p.x, p.y, p.z));
+                for d in 0..3 {
+                    assert!(bbox.min[d] <= p[d] && p[d] <= bbox.max[d]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn keyframes_need_distinct_times() {
+        assert!(MotionTransform::new(unit_cube(), vec![]).is_err());
+        assert!(MotionTransform::new(unit_cube(), vec![Keyframe::new(0.0), Keyframe::new(0.0)]).is_err());
+    }
+}
@@ -0,0 +1,361 @@
+use crate::{
+    math::{
+        self,
+        Vec3,
+        Point3,
+        Mat4,
+        Interval,
+        InnerSpace,
+        Matrix,
+        SquareMatrix,
+    },
+    core::{
+        Hittable,
+        HitRecord,
+        Ray,
+        Span,
+    },
+    aabb::{
+        AABB,
+    },
+};
+use cgmath::{
+    Deg,
+    vec4,
+};
+
+// An affine transformation of a hittable from its object space into the world.
+// The builder methods apply their transformation after the ones before them.
+// The hittable is usually owned, instances hold an `Arc` to share it.
+pub struct Transform<H = Box<dyn Hittable>> {
+    hittable: H,
+    // object to world
+    matrix: Mat4,
+    // world to object
+    inverse: Mat4,
+}
+
+impl<H: Hittable> Transform<H> {
+    pub fn new(hittable: H) -> Self {
+        Self {
+            hittable,
+            matrix: Mat4::identity(),
+            inverse: Mat4::identity(),
+        }
+    }
+
+    // Applies an arbitrary affine matrix, fails if it's projective or can't be inverted.
+    pub fn then_matrix(self, matrix: Mat4) -> Result<Self, String> {
+        // a projective bottom row would make t differ between object and world space
+        if matrix.row(3) != vec4(0.0, 0.0, 0.0, 1.0) {
+            return Err("the bottom row of the transformation matrix must be [0, 0, 0, 1]".to_owned());
+        }
+        let inverse = matrix
+            .invert()
+            .ok_or_else(|| "the transformation matrix is not invertible".to_owned())?;
+        Ok(self.then(matrix, inverse))
+    }
+
+    pub fn translate(self, offset: Vec3) -> Self {
+        self.then(Mat4::from_translation(offset), Mat4::from_translation(-offset))
+    }
+
+    // Counterclockwise when looking down the axis, which must not be zero.
+    pub fn rotate(self, axis: Vec3, degrees: f32) -> Self {
+        let axis = axis.normalize();
+        self.then(Mat4::from_axis_angle(axis, Deg(degrees)), Mat4::from_axis_angle(axis, Deg(-degrees)))
+    }
+
+    // The factors must not be zero, negative ones mirror the hittable.
+    pub fn scale(self, factors: Vec3) -> Self {
+        self.then(
+            Mat4::from_nonuniform_scale(factors.x, factors.y, factors.z),
+            Mat4::from_nonuniform_scale(1.0 / factors.x, 1.0 / factors.y, 1.0 / factors.z),
+        )
+    }
+
+    // Moves the origin to `from` and turns the z axis towards `to`, keeping the y axis as close to `up` as possible.
+    // Fails if the points coincide or `up` is parallel to the direction between them.
+    pub fn look_at(self, from: Point3, to: Point3, up: Vec3) -> Result<Self, String> {
+        use math::EuclideanSpace;
+
+        if to == from {
+            return Err("the points to look from and at must differ".to_owned());
+        }
+        let z = (to - from).normalize();
+        let x = up.cross(z);
+        if x.magnitude2() <= 1e-12 * up.magnitude2() {
+            return Err("the up direction must not be parallel to the direction looked at".to_owned());
+        }
+        let x = x.normalize();
+        let y = z.cross(x);
+        let rotation = Mat4::from_cols(x.extend(0.0), y.extend(0.0), z.extend(0.0), math::vec3(0.0, 0.0, 0.0).extend(1.0));
+        // the rotation is orthonormal so its inverse is its transpose
+        Ok(self.then(rotation, rotation.transpose())
+            .translate(from.to_vec()))
+    }
+
+    fn then(self, matrix: Mat4, inverse: Mat4) -> Self {
+        Self {
+            matrix: matrix * self.matrix,
+            inverse: self.inverse * inverse,
+            ..self
+        }
+    }
+}
+
+pub(crate) fn transform_point(matrix: &Mat4, p: &Point3) -> Point3 {
+    Point3::from_homogeneous(matrix * p.to_homogeneous())
+}
+
+pub(crate) fn transform_vector(matrix: &Mat4, v: &Vec3) -> Vec3 {
+    (matrix * v.extend(0.0)).truncate()
+}
+
+// Normals are transformed by the inverse transpose to stay perpendicular to the surface.
+pub(crate) fn transform_normal(inverse: &Mat4, n: &Vec3) -> Vec3 {
+    (inverse.transpose() * n.extend(0.0)).truncate().normalize()
+}
+
+// The box around the eight transformed corners of `bbox`.
+pub(crate) fn transform_aabb(matrix: &Mat4, bbox: &AABB) -> AABB {
+    let mut min = Point3::new(f32::MAX, f32::MAX, f32::MAX);
+    let mut max = Point3::new(-f32::MAX, -f32::MAX, -f32::MAX);
+    for i in 0..8 {
+        let corner = Point3::new(
+            if i & 1 == 0 { bbox.min.x } else { bbox.max.x },
+            if i & 2 == 0 { bbox.min.y } else { bbox.max.y },
+            if i & 4 == 0 { bbox.min.z } else { bbox.max.z },
+        );
+        let corner = transform_point(matrix, &corner);
+        for d in 0..3 {
+            min[d] = min[d].min(corner[d]);
+            max[d] = max[d].max(corner[d]);
+        }
+    }
+    AABB { min, max }
+}
+
+// Hits `hittable` with the ray taken into object space and brings the hit back into the world.
+// The direction isn't normalized so t is the same in both spaces.
+pub(crate) fn hit_transformed<'a>(hittable: &'a dyn Hittable, matrix: &Mat4, inverse: &Mat4, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'a>> {
+    hittable.hit(&object_ray(inverse, ray), hit_interval)
+        .map(|hit_rec| to_world(matrix, inverse, hit_rec))
+}
+
+pub(crate) fn spans_transformed<'a>(hittable: &'a dyn Hittable, matrix: &Mat4, inverse: &Mat4, ray: &Ray) -> Vec<Span<'a>> {
+    hittable.spans(&object_ray(inverse, ray))
+        .into_iter()
+        .map(|span| span.map(|hit_rec| to_world(matrix, inverse, hit_rec)))
+        .collect()
+}
+
+fn object_ray(inverse: &Mat4, ray: &Ray) -> Ray {
+    Ray {
+        origin: transform_point(inverse, &ray.origin),
+        direction: transform_vector(inverse, &ray.direction),
+        ..*ray
+    }
+}
+
+fn to_world<'a>(matrix: &Mat4, inverse: &Mat4, hit_rec: HitRecord<'a>) -> HitRecord<'a> {
+    HitRecord {
+        hit_point: transform_point(matrix, &hit_rec.hit_point),
+        normal: transform_normal(inverse, &hit_rec.normal),
+        ..hit_rec
+    }
+}
+
+// Solid angle densities change with the directions unless the transformation is rigid,
+// a unit direction w in object space covers |det A| / |Aw|^3 as much solid angle in the world.
+pub(crate) fn pdf_value_transformed(hittable: &dyn Hittable, matrix: &Mat4, inverse: &Mat4, origin: &Point3, direction: &Vec3) -> f32 {
+    let object_direction = transform_vector(inverse, direction).normalize();
+    let pdf = hittable.pdf_value(&transform_point(inverse, origin), &object_direction);
+    if pdf == 0.0 {
+        return 0.0;
+    }
+    let stretch = transform_vector(matrix, &object_direction).magnitude();
+    pdf * stretch.powi(3) / matrix.determinant().abs()
+}
+
+pub(crate) fn random_transformed(hittable: &dyn Hittable, matrix: &Mat4, inverse: &Mat4, origin: &Point3, u: (f32, f32)) -> Vec3 {
+    let direction = hittable.random(&transform_point(inverse, origin), u);
+    transform_vector(matrix, &direction)
+}
+
+impl<H: Hittable> Hittable for Transform<H> {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        hit_transformed(&self.hittable, &self.matrix, &self.inverse, ray, hit_interval)
+    }
+
+    fn bounding_box(&self, time_interval: &Interval<f32>) -> Option<AABB> {
+        self.hittable
+            .bounding_box(time_interval)
+            .map(|bbox| transform_aabb(&self.matrix, &bbox))
+    }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        pdf_value_transformed(&self.hittable, &self.matrix, &self.inverse, origin, direction)
+    }
+
+    fn random(&self, origin: &Point3, u: (f32, f32)) -> Vec3 {
+        random_transformed(&self.hittable, &self.matrix, &self.inverse, origin, u)
+    }
+
+    fn is_light(&self) -> bool {
+        self.hittable.is_light()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        spans_transformed(&self.hittable, &self.matrix, &self.inverse, ray)
+    }
+
+    // mirroring keeps normals pointing out of the solid since they're transformed by the inverse transpose
+    fn is_solid(&self) -> bool {
+        self.hittable.is_solid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_helpers::{
+            hit_interval,
+            unit_cube,
+        },
+        materials::{
+            Dielectric,
+            DiffuseLight,
+        },
+        textures::{
+            ConstantTexture,
+        },
+        shapes::{
+            Sphere,
+            XZRectangle,
+        },
+        math::vec3,
+    };
+    use std::sync::Arc;
+
+    fn unit_sphere() -> Box<dyn Hittable> {
+        Box::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, Arc::new(Dielectric::new(1.5))))
+    }
+
+    fn assert_near(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-4, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn builders_keep_the_inverse() {
+        let transform = Transform::new(unit_sphere())
+            .scale(vec3(2.0, 3.0, 0.5))
+            .rotate(vec3(1.0, 1.0, 0.0), 30.0)
+            .translate(vec3(1.0, -2.0, 3.0))
+            .look_at(Point3::new(1.0, 2.0, 3.0), Point3::new(0.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0))
+            .unwrap();
+
+        let product = transform.matrix * transform.inverse;
+        for c in 0..4 {
+            for r in 0..4 {
+                assert_near(product[c][r], if c == r { 1.0 } else { 0.0 });
+            }
+        }
+    }
+
+    #[test]
+    fn scaled_sphere_has_ellipsoid_hits_and_normals() {
+        let ellipsoid = Transform::new(unit_sphere()).scale(vec3(2.0, 1.0, 1.0));
+        let ray = Ray::new(Point3::new(-5.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0), 0.0);
+        let hit = ellipsoid.hit(&ray, &hit_interval()).unwrap();
+        assert_near(hit.hit_point.x, -2.0);
+        assert_near(hit.t, 3.0);
+
+        // the gradient of x^2/4 + y^2 is (x/2, 2y)
+        let p = Point3::new(2.0 * 0.6, 0.8, 0.0);
+        let ray = Ray::new(p + vec3(0.0, 5.0, 0.0), vec3(0.0, -1.0, 0.0), 0.0);
+        let hit = ellipsoid.hit(&ray, &hit_interval()).unwrap();
+        let expected = vec3(p.x / 2.0, 2.0 * p.y, 0.0).normalize();
+        assert_near(hit.normal.dot(expected), 1.0);
+    }
+
+    #[test]
+    fn spans_are_brought_back_into_the_world() {
+        let ellipsoid = Transform::new(unit_sphere()).scale(vec3(2.0, 1.0, 1.0)).translate(vec3(1.0, 0.0, 0.0));
+        let ray = Ray::new(Point3::new(-5.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0), 0.0);
+
+        let spans = ellipsoid.spans(&ray);
+        assert!(ellipsoid.is_solid());
+        assert_eq!(spans.len(), 1);
+        assert_near(spans[0].enter.hit_point.x, -1.0);
+        assert_near(spans[0].exit.hit_point.x, 3.0);
+        assert_near(spans[0].exit.normal.x, 1.0);
+    }
+
+    #[test]
+    fn cube_rotated_about_z_has_a_tight_box() {
+        let rotated = Transform::new(unit_cube()).rotate(vec3(0.0, 0.0, 1.0), 45.0);
+        let bbox = rotated.bounding_box(&Interval::new(0.0, 1.0).unwrap()).unwrap();
+        let half_diagonal = 0.5 * 2.0f32.sqrt();
+
+        assert_near(bbox.max.x, half_diagonal);
+        assert_near(bbox.min.y, -half_diagonal);
+        assert_near(bbox.max.z, 0.5);
+        // the corner of the box is outside of the cube
+        let ray = Ray::new(Point3::new(0.6, 0.6, 5.0), vec3(0.0, 0.0, -1.0), 0.0);
+        assert!(rotated.hit(&ray, &hit_interval()).is_none());
+        let ray = Ray::new(Point3::new(0.6, 0.0, 5.0), vec3(0.0, 0.0, -1.0), 0.0);
+        assert!(rotated.hit(&ray, &hit_interval()).is_some());
+    }
+
+    #[test]
+    fn look_at_turns_z_towards_the_target() {
+        let from = Point3::new(1.0, 2.0, 3.0);
+        let transform = Transform::new(unit_sphere()).look_at(from, Point3::new(1.0, 2.0, -7.0), vec3(0.0, 1.0, 0.0)).unwrap();
+
+        let z = transform_vector(&transform.matrix, &vec3(0.0, 0.0, 1.0));
+        let y = transform_vector(&transform.matrix, &vec3(0.0, 1.0, 0.0));
+        assert_near(z.z, -1.0);
+        assert_near(y.y, 1.0);
+        assert_eq!(transform_point(&transform.matrix, &Point3::new(0.0, 0.0, 0.0)), from);
+    }
+
+    #[test]
+    fn degenerate_look_at_and_projective_matrices_are_rejected() {
+        let from = Point3::new(1.0, 2.0, 3.0);
+        assert!(Transform::new(unit_sphere()).look_at(from, from, vec3(0.0, 1.0, 0.0)).is_err());
+        assert!(Transform::new(unit_sphere()).look_at(from, Point3::new(1.0, 5.0, 3.0), vec3(0.0, 2.0, 0.0)).is_err());
+
+        let projective = Mat4::from_translation(vec3(1.0, 0.0, 0.0)) + Mat4::from_cols(
+            vec4(0.0, 0.0, 0.0, 0.0), vec4(0.0, 0.0, 0.0, 0.0), vec4(0.0, 0.0, 0.0, 0.5), vec4(0.0, 0.0, 0.0, 0.0),
+        );
+        assert!(Transform::new(unit_sphere()).then_matrix(projective).is_err());
+        assert!(Transform::new(unit_sphere()).then_matrix(Mat4::from_scale(2.0)).is_ok());
+    }
+
+    #[test]
+    fn light_pdf_of_a_stretched_light_integrates_to_one() {
+        let emit = Arc::new(DiffuseLight::new(Arc::new(ConstantTexture::from_rgb(vec3(1.0, 1.0, 1.0)))));
+        let light = XZRectangle::new(-0.5, 0.5, -0.5, 0.5, 0.0, emit);
+        let light = Transform::new(Box::new(light))
+            .scale(vec3(3.0, 1.0, 0.5))
+            .rotate(vec3(1.0, 0.0, 0.0), 20.0)
+            .translate(vec3(0.0, 2.0, 0.0));
+        let origin = Point3::new(0.2, 0.0, 0.1);
+
+        // midpoint rule over the upper hemisphere
+        let n = 400;
+        let mut integral = 0.0;
+        for i in 0..n {
+            for j in 0..n {
+                let theta = 0.5 * std::f32::consts::PI * (i as f32 + 0.5) / n as f32;
+                let phi = 2.0 * std::f32::consts::PI * (j as f32 + 0.5) / n as f32;
+                let direction = vec3(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin());
+                let area = theta.sin() * (0.5 * std::f32::consts::PI / n as f32) * (2.0 * std::f32::consts::PI / n as f32);
+                integral += light.pdf_value(&origin, &direction) * area;
+            }
+        }
+        assert!((integral - 1.0).abs() < 0.02, "{}", integral);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     math::{
+        Point3,
         Vec3, 
         Interval,
     },
@@ -7,6 +8,7 @@ use crate::{
         Hittable, 
         HitRecord,
         Ray,
+        Span,
     },
     aabb::{
         AABB,
@@ -25,21 +27,26 @@ impl Translation {
             offset,
         }
     }
+
+    fn moved_ray(&self, ray: &Ray) -> Ray {
+        Ray{ origin: ray.origin - self.offset, ..*ray }
+    }
+
+    fn moved_hit<'a>(&self, hit_rec: HitRecord<'a>) -> HitRecord<'a> {
+        HitRecord {
+            t: hit_rec.t,
+            uv: hit_rec.uv,
+            normal: hit_rec.normal,
+            material: hit_rec.material,
+            hit_point: hit_rec.hit_point + self.offset,
+        }
+    }
 }
 
 impl Hittable for Translation {
-    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord> {
-        let moved_ray = Ray::new(ray.origin - self.offset, ray.direction, ray.time);
-        self.hittable.hit(&moved_ray, hit_interval)
-        .map(|hit_rec| {
-            HitRecord {
-                t: hit_rec.t,
-                uv: hit_rec.uv,
-                normal: hit_rec.normal,
-                material: hit_rec.material,
-                hit_point: hit_rec.hit_point + self.offset,
-            }
-        })
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        self.hittable.hit(&self.moved_ray(ray), hit_interval)
+        .map(|hit_rec| self.moved_hit(hit_rec))
     }
 
     fn bounding_box(&self, time_interval: &Interval<f32>) -> Option<AABB> {
@@ -51,6 +58,29 @@ impl Hittable for Translation {
             }
         })
     }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        self.hittable.pdf_value(&(origin - self.offset), direction)
+    }
+
+    fn random(&self, origin: &Point3, u: (f32, f32)) -> Vec3 {
+        self.hittable.random(&(origin - self.offset), u)
+    }
+
+    fn is_light(&self) -> bool {
+        self.hittable.is_light()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        self.hittable.spans(&self.moved_ray(ray))
+            .into_iter()
+            .map(|span| span.map(|hit_rec| self.moved_hit(hit_rec)))
+            .collect()
+    }
+
+    fn is_solid(&self) -> bool {
+        self.hittable.is_solid()
+    }
 }
 
 #[cfg(test)]
@@ -68,14 +98,15 @@ mod tests {
             Point3,
         },
     };
+    use std::sync::Arc;
 
     #[test]
     fn ray_through_a_translated_rectangle_hits_it() {
-        let material = Box::new(Dielectric::new(1.5));
+        let material = Arc::new(Dielectric::new(1.5));
         let rect = XYRectangle::new(-5.0, 5.0, 0.0, 10.0, -4.0, material);
         let offset = math::vec3(5.0, 0.0, 0.0);
         let translated_rect = Translation::on(Box::new(rect), offset);
-        let hit_interval = Interval::new(0.0, std::f32::MAX).unwrap();
+        let hit_interval = Interval::new(0.0, f32::MAX).unwrap();
         let ray = Ray::new(Point3::new(6.0, -3.0, 0.0), math::vec3(0.0, 1.0, -1.0), 1.0);
         
         assert!(translated_rect.hit(&ray, &hit_interval).is_some());
@@ -83,11 +114,11 @@ mod tests {
     
     #[test]
     fn ray_outside_a_translated_rectangle_does_not_hit_it() {
-        let material = Box::new(Dielectric::new(1.5));
+        let material = Arc::new(Dielectric::new(1.5));
         let rect = XYRectangle::new(-5.0, 5.0, 0.0, 10.0, -4.0, material);
         let offset = math::vec3(5.0, 0.0, 0.0);
         let translated_rect = Translation::on(Box::new(rect), offset);
-        let hit_interval = Interval::new(0.0, std::f32::MAX).unwrap();
+        let hit_interval = Interval::new(0.0, f32::MAX).unwrap();
         let ray = Ray::new(Point3::new(6.0, -3.0, 0.0), math::vec3(-10.0, 1.0, -1.0), 1.0);
         
         assert!(translated_rect.hit(&ray, &hit_interval).is_none());
@@ -10,6 +10,7 @@ use crate::{
         Hittable, 
         HitRecord,
         Ray,
+        Span,
     },
     aabb::{
         AABB,
@@ -31,8 +32,8 @@ impl YRotation {
         let bbox = hittable
             .bounding_box(&Interval::new(0.0, 1.0).unwrap())
             .map(|bbox| {
-                let mut min = Point3::new(std::f32::MAX, std::f32::MAX, std::f32::MAX);
-                let mut max = Point3::new(-std::f32::MAX, -std::f32::MAX, -std::f32::MAX);
+                let mut min = Point3::new(f32::MAX, f32::MAX, f32::MAX);
+                let mut max = Point3::new(-f32::MAX, -f32::MAX, -f32::MAX);
                 for i in 0..2 {
                     for j in 0..2 {
                         for k in 0..2 {
@@ -67,44 +68,82 @@ impl YRotation {
     }
 }
 
-impl Hittable for YRotation {
-    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord> {
+impl YRotation {
+    fn rotate_y_theta(&self, v: Vec3) -> Vec3 {
+        let mut result = v;
+        result.x = self.cos_theta * v.x - self.sin_theta * v.z;
+        result.z = self.sin_theta * v.x + self.cos_theta * v.z;
+        result
+    }
+
+    fn rotate_y_minus_theta(&self, v: Vec3) -> Vec3 {
+        let mut result = v;
+        result.x =  self.cos_theta * v.x + self.sin_theta * v.z;
+        result.z = -self.sin_theta * v.x + self.cos_theta * v.z;
+        result
+    }
+
+    fn rotated_ray(&self, ray: &Ray) -> Ray {
         use math::EuclideanSpace;
 
-        let rotate_y_theta = |v: Vec3| {
-            let mut result = v;
-            result.x = self.cos_theta * v.x - self.sin_theta * v.z;
-            result.z = self.sin_theta * v.x + self.cos_theta * v.z;
-            result
-        };
-        let rotate_y_minus_theta = |v: Vec3| {
-            let mut result = v;
-            result.x =  self.cos_theta * v.x + self.sin_theta * v.z;
-            result.z = -self.sin_theta * v.x + self.cos_theta * v.z;
-            result
-        };
-
-        let origin = Point3::from_vec(rotate_y_theta(ray.origin.to_vec()));
-        let direction = rotate_y_theta(ray.direction);
-        let rotated_ray = Ray::new(origin, direction, ray.time);
-        
-        self.hittable.hit(&rotated_ray, hit_interval)
-        .map(|hit_rec| {
-            let normal = rotate_y_minus_theta(hit_rec.normal);
-            let hit_point = Point3::from_vec(rotate_y_minus_theta(hit_rec.hit_point.to_vec()));
-            HitRecord {
-                t: hit_rec.t,
-                uv: hit_rec.uv,
-                material: hit_rec.material,
-                normal,
-                hit_point,
-            }
-        })
+        let origin = Point3::from_vec(self.rotate_y_theta(ray.origin.to_vec()));
+        let direction = self.rotate_y_theta(ray.direction);
+        Ray{ origin, direction, ..*ray }
+    }
+
+    fn rotated_hit<'a>(&self, hit_rec: HitRecord<'a>) -> HitRecord<'a> {
+        use math::EuclideanSpace;
+
+        let normal = self.rotate_y_minus_theta(hit_rec.normal);
+        let hit_point = Point3::from_vec(self.rotate_y_minus_theta(hit_rec.hit_point.to_vec()));
+        HitRecord {
+            t: hit_rec.t,
+            uv: hit_rec.uv,
+            material: hit_rec.material,
+            normal,
+            hit_point,
+        }
+    }
+}
+
+impl Hittable for YRotation {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
+        self.hittable.hit(&self.rotated_ray(ray), hit_interval)
+        .map(|hit_rec| self.rotated_hit(hit_rec))
     }
 
     fn bounding_box(&self, _: &Interval<f32>) -> Option<AABB> {
         self.bbox
     }
+
+    fn pdf_value(&self, origin: &Point3, direction: &Vec3) -> f32 {
+        use math::EuclideanSpace;
+
+        let origin = Point3::from_vec(self.rotate_y_theta(origin.to_vec()));
+        self.hittable.pdf_value(&origin, &self.rotate_y_theta(*direction))
+    }
+
+    fn random(&self, origin: &Point3, u: (f32, f32)) -> Vec3 {
+        use math::EuclideanSpace;
+
+        let origin = Point3::from_vec(self.rotate_y_theta(origin.to_vec()));
+        self.rotate_y_minus_theta(self.hittable.random(&origin, u))
+    }
+
+    fn is_light(&self) -> bool {
+        self.hittable.is_light()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        self.hittable.spans(&self.rotated_ray(ray))
+            .into_iter()
+            .map(|span| span.map(|hit_rec| self.rotated_hit(hit_rec)))
+            .collect()
+    }
+
+    fn is_solid(&self) -> bool {
+        self.hittable.is_solid()
+    }
 }
 
 #[cfg(test)]
@@ -112,7 +151,6 @@ mod tests {
     use super::*;
     use crate::{
         materials::{
-            Material,
             Dielectric,
         },
         math,
@@ -120,14 +158,15 @@ mod tests {
             Parallelepiped,
         },
     };
+    use std::sync::Arc;
 
     #[test]
     fn ray_through_a_rotated_parallelepiped_hits_it() {
-        let material_gen = || Box::new(Dielectric::new(1.5)) as Box<dyn Material>;
+        let material = Arc::new(Dielectric::new(1.5));
         let (min, max) = (Point3::new(0.0, 0.0, 0.0), Point3::new(3.0, 3.0, 3.0));
-        let parallelepiped = Parallelepiped::new(&min, &max, material_gen);
+        let parallelepiped = Parallelepiped::new(&min, &max, material);
         let rotated_parallelepiped = YRotation::from_degrees(Box::new(parallelepiped), 10.0);
-        let hit_interval = Interval::new(0.0, std::f32::MAX).unwrap();
+        let hit_interval = Interval::new(0.0, f32::MAX).unwrap();
         let ray = Ray::new(Point3::new(1.5, 4.5, 1.5), math::vec3(0.0, -1.0, 0.0), 1.0);
         
         assert!(rotated_parallelepiped.hit(&ray, &hit_interval).is_some());
@@ -135,11 +174,11 @@ mod tests {
     
     #[test]
     fn ray_outside_a_rotated_parallelepiped_does_not_hit_it() {
-        let material_gen = || Box::new(Dielectric::new(1.5)) as Box<dyn Material>;
+        let material = Arc::new(Dielectric::new(1.5));
         let (min, max) = (Point3::new(0.0, 0.0, 0.0), Point3::new(3.0, 3.0, 3.0));
-        let parallelepiped = Parallelepiped::new(&min, &max, material_gen);
+        let parallelepiped = Parallelepiped::new(&min, &max, material);
         let rotated_parallelepiped = YRotation::from_degrees(Box::new(parallelepiped), 10.0);
-        let hit_interval = Interval::new(0.0, std::f32::MAX).unwrap();
+        let hit_interval = Interval::new(0.0, f32::MAX).unwrap();
         let ray = Ray::new(Point3::new(1.0, -5.0, -5.0), math::vec3(5.0, 5.0, 5.0), 1.0);
 
         assert!(rotated_parallelepiped.hit(&ray, &hit_interval).is_none());
@@ -21,31 +21,32 @@ use crate::{
         TextureCoordinates,
     },
 };
+use std::sync::Arc;
 
 pub struct ConstantMedium {
     density: f32,
     boundary: Box<dyn Hittable>,
-    phase_function: Box<dyn Material>,
+    phase_function: Arc<dyn Material>,
 }
 
 impl ConstantMedium {
-    pub fn new(density: f32, boundary: Box<dyn Hittable>, texture: Box<dyn Texture>) -> Self {
+    pub fn new(density: f32, boundary: Box<dyn Hittable>, texture: Arc<dyn Texture>) -> Self {
         Self {
             density,
             boundary,
-            phase_function: Box::new(Isotropic::new(texture)),
+            phase_function: Arc::new(Isotropic::new(texture)),
         }
     }
 }
 
 impl Hittable for ConstantMedium {
-    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, hit_interval: &Interval<f32>) -> Option<HitRecord<'_>> {
         use math::InnerSpace;
         
-        let interval = Interval::new(-std::f32::MAX, std::f32::MAX).unwrap();
+        let interval = Interval::new(-f32::MAX, f32::MAX).unwrap();
         self.boundary.hit(ray, &interval)
         .and_then(|entry_hit_rec| {
-            let interval = Interval::new(entry_hit_rec.t + 0.0001, std::f32::MAX).unwrap();
+            let interval = Interval::new(entry_hit_rec.t + 0.0001, f32::MAX).unwrap();
             self.boundary.hit(ray, &interval) 
             .and_then(|leave_hit_rec| {
                 let entry_hit_t = if entry_hit_rec.t < hit_interval.min() { hit_interval.min() } else { entry_hit_rec.t };
@@ -57,7 +58,11 @@ impl Hittable for ConstantMedium {
                 let entry_hit_t = if entry_hit_t < 0.0 { 0.0 } else { entry_hit_t };
                 let direction_magnitude = ray.direction.magnitude();
                 let distance_inside_boundary = (leave_hit_t - entry_hit_t) * direction_magnitude;
-                let hit_distance = -(1.0 / self.density) * random::random_float_from_0_to_1().log(std::f32::consts::E);
+                // Each medium the ray crosses needs a sample of its own, the distances in overlapping media
+                // would be correlated otherwise. Mixing in where the ray enters the boundary tells them apart.
+                let sample = random::hash_to_float(&[u64::from(ray.medium_sample.to_bits()), u64::from(entry_hit_rec.t.to_bits())]);
+                // ln(0) would give an infinite distance
+                let hit_distance = -(1.0 / self.density) * sample.max(f32::MIN_POSITIVE).ln();
                 
                 if hit_distance < distance_inside_boundary {
                     let t = entry_hit_t + hit_distance / direction_magnitude; 
@@ -79,4 +84,53 @@ impl Hittable for ConstantMedium {
     fn bounding_box(&self, time_interval: &Interval<f32>) -> Option<AABB> {
         self.boundary.bounding_box(time_interval)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shapes::Sphere,
+        materials::Dielectric,
+        textures::ConstantTexture,
+        math::Point3,
+    };
+
+    fn fog(radius: f32, density: f32) -> ConstantMedium {
+        let boundary = Box::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), radius, Arc::new(Dielectric::new(1.5))));
+        ConstantMedium::new(density, boundary, Arc::new(ConstantTexture::from_rgb(math::vec3(1.0, 1.0, 1.0))))
+    }
+
+    // The fraction of rays along the z axis which pass through all the media without scattering.
+    fn transmittance(media: &[ConstantMedium]) -> f32 {
+        let ray = Ray::new(Point3::new(0.0, 0.0, -20.0), math::vec3(0.0, 0.0, 1.0), 0.0);
+        let hit_interval = Interval::new(0.001, f32::MAX).unwrap();
+        let count = 20000;
+        let passed = (0..count)
+            .map(|i| ray.with_medium_sample((i as f32 + 0.5) / count as f32))
+            .filter(|ray| media.iter().all(|medium| medium.hit(ray, &hit_interval).is_none()))
+            .count();
+        passed as f32 / count as f32
+    }
+
+    #[test]
+    fn scattering_distance_follows_the_medium_sample() {
+        let fog = fog(10.0, 0.5);
+        let ray = Ray::new(Point3::new(0.0, 0.0, -20.0), math::vec3(0.0, 0.0, 1.0), 0.0);
+        let hit_interval = Interval::new(0.001, f32::MAX).unwrap();
+        let t = |u: f32| fog.hit(&ray.with_medium_sample(u), &hit_interval).map(|h| h.t);
+
+        assert_eq!(t(0.25), t(0.25));
+        assert_ne!(t(0.25), t(0.75));
+        // a chord of 20 through a density of 0.5
+        assert!((transmittance(&[fog]) - (-10.0f32).exp()).abs() < 0.005);
+    }
+
+    #[test]
+    fn overlapping_media_attenuate_by_the_product_of_their_transmittances() {
+        // chords of 2 and 4 with an optical depth of 1 each
+        let (inner, outer) = (fog(1.0, 0.5), fog(2.0, 0.25));
+        let expected = (-1.0f32).exp() * (-1.0f32).exp();
+
+        assert!((transmittance(&[inner, outer]) - expected).abs() < 0.01);
+    }
+}